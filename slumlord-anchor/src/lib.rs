@@ -0,0 +1,234 @@
+//! Anchor `Accounts` structs and `CpiContext` helpers for CPI-ing slumlord
+//! from Anchor programs.
+//!
+//! slumlord is not an Anchor program: its instructions use 1-byte discriminants
+//! rather than Anchor's 8-byte sighashes, which `idl.json` in this crate declares
+//! as explicit Anchor 0.30 `discriminator`s. Anchor versions that derive sighashes
+//! instead should use these helpers to CPI them.
+
+// anchor_lang::error::Error is large, nothing to be done about it
+#![allow(clippy::result_large_err)]
+
+use anchor_lang::{
+    context::CpiContext,
+    prelude::*,
+    solana_program::{instruction::AccountMeta, program::invoke_signed},
+};
+use slumlord_interface::{
+    borrow_ix, borrow_split_ix, borrow_with_min_ix, check_repaid_invoke_signed,
+    flash_loan_with_callback_ix, repay_invoke_signed, repay_with_max_invoke_signed, BorrowKeys,
+    BorrowSplitIxArgs, BorrowSplitKeys, BorrowWithMinIxArgs, BorrowWithMinKeys,
+    CheckRepaidAccounts, FlashLoanWithCallbackIxArgs, FlashLoanWithCallbackKeys, RepayAccounts,
+    RepayWithMaxAccounts, RepayWithMaxIxArgs,
+};
+
+use slumlord_lib::BORROWER_ACCOUNTS_LEN;
+
+pub use slumlord_lib::program::{ID, SLUMLORD_ID};
+
+/// For use as `Program<'info, SlumlordProgram>`
+#[derive(Clone, Copy, Debug)]
+pub struct SlumlordProgram;
+
+impl anchor_lang::Id for SlumlordProgram {
+    fn id() -> Pubkey {
+        ID
+    }
+}
+
+#[derive(Accounts)]
+pub struct Borrow<'info> {
+    /// CHECK: The slumlord PDA ["slumlord"], checked by slumlord
+    #[account(mut)]
+    pub slumlord: AccountInfo<'info>,
+
+    /// CHECK: The destination account to lend SOL to
+    #[account(mut)]
+    pub dst: AccountInfo<'info>,
+
+    /// CHECK: Instructions sysvar, checked by slumlord
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BorrowSplit<'info> {
+    /// CHECK: The slumlord PDA ["slumlord"], checked by slumlord
+    #[account(mut)]
+    pub slumlord: AccountInfo<'info>,
+
+    /// CHECK: Instructions sysvar, checked by slumlord
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Repay<'info> {
+    /// CHECK: The slumlord PDA ["slumlord"], checked by slumlord
+    #[account(mut)]
+    pub slumlord: AccountInfo<'info>,
+
+    /// CHECK: The system account paying the outstanding flash loan
+    #[account(mut)]
+    pub src: AccountInfo<'info>,
+
+    /// CHECK: System Program, checked by slumlord
+    pub system_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CheckRepaid<'info> {
+    /// CHECK: The slumlord PDA ["slumlord"], checked by slumlord
+    #[account(mut)]
+    pub slumlord: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FlashLoanWithCallback<'info> {
+    /// CHECK: The slumlord PDA ["slumlord"], checked by slumlord
+    #[account(mut)]
+    pub slumlord: AccountInfo<'info>,
+
+    /// CHECK: The account to lend SOL to
+    #[account(mut)]
+    pub receiver: AccountInfo<'info>,
+
+    /// CHECK: The FlashBorrower program to call back after lending
+    pub receiver_program: AccountInfo<'info>,
+
+    /// CHECK: The signer starting the flash loan, passed to `on_flash_loan` as a signer
+    pub initiator: AccountInfo<'info>,
+}
+
+/// Passes `ctx.remaining_accounts` through with their privileges,
+/// e.g. the borrower accounts in allowlist mode
+pub fn borrow<'info>(ctx: CpiContext<'_, '_, '_, 'info, Borrow<'info>>) -> Result<()> {
+    let mut ix = borrow_ix(BorrowKeys {
+        slumlord: ctx.accounts.slumlord.key(),
+        dst: ctx.accounts.dst.key(),
+        instructions: ctx.accounts.instructions.key(),
+    })
+    .map_err(ProgramError::from)?;
+    ix.accounts
+        .extend(ctx.remaining_accounts.iter().map(passthrough_account_meta));
+    invoke_signed(&ix, &ctx.to_account_infos(), ctx.signer_seeds).map_err(Into::into)
+}
+
+/// Passes `ctx.remaining_accounts` through with their privileges,
+/// e.g. the borrower accounts in allowlist mode
+pub fn borrow_with_min<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, Borrow<'info>>,
+    min_lamports: u64,
+) -> Result<()> {
+    let mut ix = borrow_with_min_ix(
+        BorrowWithMinKeys {
+            slumlord: ctx.accounts.slumlord.key(),
+            dst: ctx.accounts.dst.key(),
+            instructions: ctx.accounts.instructions.key(),
+        },
+        BorrowWithMinIxArgs { min_lamports },
+    )
+    .map_err(ProgramError::from)?;
+    ix.accounts
+        .extend(ctx.remaining_accounts.iter().map(passthrough_account_meta));
+    invoke_signed(&ix, &ctx.to_account_infos(), ctx.signer_seeds).map_err(Into::into)
+}
+
+/// Lends `amounts[i]` to the i-th account in `ctx.remaining_accounts`.
+///
+/// In allowlist mode, the borrower accounts follow the `dst`s
+/// and are passed through with their privileges.
+///
+/// Errors with [`ProgramError::InvalidArgument`] if `ctx.remaining_accounts` is not
+/// one `dst` per amount, optionally followed by the borrower accounts
+pub fn borrow_split<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, BorrowSplit<'info>>,
+    amounts: Vec<u64>,
+) -> Result<()> {
+    let dsts_len = amounts.len();
+    match ctx.remaining_accounts.len().checked_sub(dsts_len) {
+        Some(0 | BORROWER_ACCOUNTS_LEN) => (),
+        _ => return Err(ProgramError::InvalidArgument.into()),
+    }
+    let mut ix = borrow_split_ix(
+        BorrowSplitKeys {
+            slumlord: ctx.accounts.slumlord.key(),
+            instructions: ctx.accounts.instructions.key(),
+        },
+        BorrowSplitIxArgs { amounts },
+    )
+    .map_err(ProgramError::from)?;
+    let (dsts, rest) = ctx.remaining_accounts.split_at(dsts_len);
+    ix.accounts
+        .extend(dsts.iter().map(|dst| AccountMeta::new(dst.key(), false)));
+    ix.accounts
+        .extend(rest.iter().map(passthrough_account_meta));
+    invoke_signed(&ix, &ctx.to_account_infos(), ctx.signer_seeds).map_err(Into::into)
+}
+
+pub fn repay<'info>(ctx: CpiContext<'_, '_, '_, 'info, Repay<'info>>) -> Result<()> {
+    repay_invoke_signed(
+        RepayAccounts {
+            slumlord: &ctx.accounts.slumlord,
+            src: &ctx.accounts.src,
+            system_program: &ctx.accounts.system_program,
+        },
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+pub fn repay_with_max<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, Repay<'info>>,
+    max_lamports: u64,
+) -> Result<()> {
+    repay_with_max_invoke_signed(
+        RepayWithMaxAccounts {
+            slumlord: &ctx.accounts.slumlord,
+            src: &ctx.accounts.src,
+            system_program: &ctx.accounts.system_program,
+        },
+        RepayWithMaxIxArgs { max_lamports },
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+pub fn check_repaid<'info>(ctx: CpiContext<'_, '_, '_, 'info, CheckRepaid<'info>>) -> Result<()> {
+    check_repaid_invoke_signed(
+        CheckRepaidAccounts {
+            slumlord: &ctx.accounts.slumlord,
+        },
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Lends `amount` to `receiver` and calls `receiver_program`'s `on_flash_loan`
+/// with `data`, `initiator` and `ctx.remaining_accounts`.
+///
+/// `initiator` may be a PDA signing with `ctx.signer_seeds`
+pub fn flash_loan_with_callback<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, FlashLoanWithCallback<'info>>,
+    amount: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    let mut ix = flash_loan_with_callback_ix(
+        FlashLoanWithCallbackKeys {
+            slumlord: ctx.accounts.slumlord.key(),
+            receiver: ctx.accounts.receiver.key(),
+            receiver_program: ctx.accounts.receiver_program.key(),
+            initiator: ctx.accounts.initiator.key(),
+        },
+        FlashLoanWithCallbackIxArgs { amount, data },
+    )
+    .map_err(ProgramError::from)?;
+    ix.accounts
+        .extend(ctx.remaining_accounts.iter().map(passthrough_account_meta));
+    invoke_signed(&ix, &ctx.to_account_infos(), ctx.signer_seeds).map_err(Into::into)
+}
+
+fn passthrough_account_meta(account: &AccountInfo) -> AccountMeta {
+    match account.is_writable {
+        true => AccountMeta::new(account.key(), account.is_signer),
+        false => AccountMeta::new_readonly(account.key(), account.is_signer),
+    }
+}
@@ -0,0 +1,38 @@
+use anchor_lang::{context::CpiContext, prelude::*};
+use slumlord_anchor::{borrow_split, BorrowSplit, ID, SLUMLORD_ID};
+
+const MAX_REMAINING_ACCOUNTS: usize = 5;
+
+#[test]
+fn borrow_split_remaining_accounts_must_match_amounts() {
+    let instructions_id = anchor_lang::solana_program::sysvar::instructions::ID;
+    let remaining_keys: [Pubkey; MAX_REMAINING_ACCOUNTS] =
+        std::array::from_fn(|_| Pubkey::new_unique());
+    let mut lamports = [0u64; MAX_REMAINING_ACCOUNTS + 3];
+    let mut data = [[0u8; 0]; MAX_REMAINING_ACCOUNTS + 3];
+    let mut account_infos: Vec<AccountInfo> = [&ID, &SLUMLORD_ID, &instructions_id]
+        .into_iter()
+        .chain(remaining_keys.iter())
+        .zip(lamports.iter_mut())
+        .zip(data.iter_mut())
+        .map(|((key, lamports), data)| {
+            AccountInfo::new(key, false, true, lamports, data, &ID, false, 0)
+        })
+        .collect();
+    let remaining_accounts = account_infos.split_off(3);
+    let [program, slumlord, instructions] = <[AccountInfo; 3]>::try_from(account_infos).unwrap();
+
+    // 2 dsts, optionally followed by the 2 borrower accounts
+    for (remaining_len, is_ok) in [(1, false), (2, true), (3, false), (4, true), (5, false)] {
+        let ctx = CpiContext::new(
+            program.clone(),
+            BorrowSplit {
+                slumlord: slumlord.clone(),
+                instructions: instructions.clone(),
+            },
+        )
+        .with_remaining_accounts(remaining_accounts[..remaining_len].to_vec());
+        let res = borrow_split(ctx, vec![1, 2]);
+        assert_eq!(res.is_ok(), is_ok, "{remaining_len} remaining accounts");
+    }
+}
@@ -0,0 +1,43 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{hash::hash, instruction::AccountMeta},
+};
+use slumlord_interface::{OnFlashLoanKeys, ON_FLASH_LOAN_IX_ACCOUNTS_LEN, ON_FLASH_LOAN_IX_DISCM};
+
+#[test]
+fn on_flash_loan_discm_is_anchor_sighash() {
+    let sighash = hash(b"global:on_flash_loan");
+    assert_eq!(sighash.to_bytes()[..8], ON_FLASH_LOAN_IX_DISCM);
+}
+
+/// The `OnFlashLoan` accounts the `slumlord_interface::flash_borrower` docs describe
+#[derive(Accounts)]
+pub struct OnFlashLoan<'info> {
+    /// CHECK: The slumlord PDA ["slumlord"]
+    #[account(mut)]
+    pub slumlord: AccountInfo<'info>,
+
+    /// CHECK: The account the SOL was lent to
+    #[account(mut)]
+    pub receiver: AccountInfo<'info>,
+
+    pub initiator: Signer<'info>,
+}
+
+#[test]
+fn on_flash_loan_accounts_match_anchor_accounts() {
+    let keys = OnFlashLoanKeys {
+        slumlord: Pubkey::new_unique(),
+        receiver: Pubkey::new_unique(),
+        initiator: Pubkey::new_unique(),
+    };
+    let metas: [AccountMeta; ON_FLASH_LOAN_IX_ACCOUNTS_LEN] = keys.into();
+    // `#[program]` would re-export this as `accounts::OnFlashLoan`
+    let anchor_metas = __client_accounts_on_flash_loan::OnFlashLoan {
+        slumlord: keys.slumlord,
+        receiver: keys.receiver,
+        initiator: keys.initiator,
+    }
+    .to_account_metas(None);
+    assert_eq!(anchor_metas, metas);
+}
@@ -0,0 +1,155 @@
+//! Checks that `slumlord-anchor/idl.json` is the Anchor 0.30-format IDL generated from
+//! the workspace `idl.json`. After changing `idl.json`, regenerate it with:
+//!
+//! `UPDATE_ANCHOR_IDL=1 cargo test -p slumlord-anchor`
+
+use serde_json::{json, Value};
+
+const IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../idl.json");
+
+const ANCHOR_IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/idl.json");
+
+const UPDATE_ANCHOR_IDL_ENV_VAR: &str = "UPDATE_ANCHOR_IDL";
+
+#[test]
+fn anchor_idl_instructions_use_slumlord_discriminants() {
+    let idl: Value = serde_json::from_str(&std::fs::read_to_string(IDL_PATH).unwrap()).unwrap();
+    let anchor_idl = to_anchor_idl(&idl);
+    let ixs = idl["instructions"].as_array().unwrap();
+    let anchor_ixs = anchor_idl["instructions"].as_array().unwrap();
+    assert_eq!(anchor_ixs.len(), ixs.len());
+    for (ix, anchor_ix) in ixs.iter().zip(anchor_ixs) {
+        assert_eq!(
+            anchor_ix["discriminator"],
+            json!([ix["discriminant"]["value"]]),
+            "{} discriminator mismatch",
+            ix["name"]
+        );
+    }
+}
+
+#[test]
+fn anchor_idl_up_to_date() {
+    let idl: Value = serde_json::from_str(&std::fs::read_to_string(IDL_PATH).unwrap()).unwrap();
+    let expected = serde_json::to_string_pretty(&to_anchor_idl(&idl)).unwrap() + "\n";
+    if std::env::var(UPDATE_ANCHOR_IDL_ENV_VAR).is_ok() {
+        std::fs::write(ANCHOR_IDL_PATH, expected).unwrap();
+        return;
+    }
+    let actual = std::fs::read_to_string(ANCHOR_IDL_PATH).unwrap_or_default();
+    assert!(
+        actual == expected,
+        "{ANCHOR_IDL_PATH} is out of date, regenerate with {UPDATE_ANCHOR_IDL_ENV_VAR}=1"
+    );
+}
+
+/// Anchor 0.30 IDL format, which supports slumlord's 1-byte discriminants as
+/// explicit `discriminator`s instead of Anchor's 8-byte sighashes
+fn to_anchor_idl(idl: &Value) -> Value {
+    let instructions: Vec<Value> = idl["instructions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ix| {
+            json!({
+                "name": snake_case(&ix["name"]),
+                "discriminator": [ix["discriminant"]["value"]],
+                "accounts": to_anchor_accounts(&ix["accounts"]),
+                "args": to_anchor_fields(&ix["args"]),
+            })
+        })
+        .collect();
+    let types: Vec<Value> = idl["types"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|ty| {
+            json!({
+                "name": ty["name"],
+                "type": {
+                    "kind": ty["type"]["kind"],
+                    "fields": to_anchor_fields(&ty["type"]["fields"]),
+                },
+            })
+        })
+        .collect();
+    json!({
+        "address": idl["metadata"]["address"],
+        "metadata": {
+            "name": idl["name"],
+            "version": idl["version"],
+            "spec": "0.1.0",
+        },
+        "instructions": instructions,
+        "types": types,
+        "errors": idl["errors"],
+    })
+}
+
+/// Anchor omits `writable` and `signer` if false
+fn to_anchor_accounts(accounts: &Value) -> Vec<Value> {
+    accounts
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|account| {
+            let mut res = json!({
+                "name": account["name"],
+                "docs": [account["desc"]],
+            });
+            for (flag, anchor_flag) in [("isMut", "writable"), ("isSigner", "signer")] {
+                if account[flag] == true {
+                    res[anchor_flag] = json!(true);
+                }
+            }
+            res
+        })
+        .collect()
+}
+
+fn to_anchor_fields(fields: &Value) -> Vec<Value> {
+    fields
+        .as_array()
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|field| {
+                    json!({
+                        "name": field["name"],
+                        "type": to_anchor_type(&field["type"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `"publicKey"` -> `"pubkey"`, `{"defined": "T"}` -> `{"defined": {"name": "T"}}`
+fn to_anchor_type(ty: &Value) -> Value {
+    match ty {
+        Value::String(s) if s == "publicKey" => json!("pubkey"),
+        Value::Object(o) if o.contains_key("defined") => {
+            json!({ "defined": { "name": o["defined"] } })
+        }
+        Value::Object(o) if o.contains_key("array") => {
+            json!({ "array": [to_anchor_type(&o["array"][0]), o["array"][1]] })
+        }
+        Value::Object(o) if o.contains_key("vec") => json!({ "vec": to_anchor_type(&o["vec"]) }),
+        Value::Object(o) if o.contains_key("option") => {
+            json!({ "option": to_anchor_type(&o["option"]) })
+        }
+        _ => ty.clone(),
+    }
+}
+
+/// `"CheckRepaid"` -> `"check_repaid"`
+fn snake_case(name: &Value) -> String {
+    let mut res = String::new();
+    for (i, c) in name.as_str().unwrap().chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            res.push('_');
+        }
+        res.extend(c.to_lowercase());
+    }
+    res
+}
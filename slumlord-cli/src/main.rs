@@ -1,5 +1,5 @@
 use clap::{builder::ValueParser, Parser};
-use sanctum_solana_cli_utils::{ConfigWrapper, TxSendMode};
+use sanctum_solana_cli_utils::ConfigWrapper;
 use subcmd::Subcmd;
 
 mod rpc_client;
@@ -28,12 +28,6 @@ pub struct Args {
     pub subcmd: Subcmd,
 }
 
-impl Args {
-    pub fn tx_send_mode(&self) -> TxSendMode {
-        TxSendMode::from_should_dry_run(self.dry_run)
-    }
-}
-
 fn main() {
     let args = Args::parse();
     args.subcmd.process(&args);
@@ -1,6 +1,18 @@
-use slumlord_lib::program::SLUMLORD_ID;
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::account::Account;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use slumlord_interface::QuoteLoanReturn;
+use slumlord_lib::{
+    program::SLUMLORD_ID, quote_loan_ix_full, try_quote_loan_return, try_slumlord_err,
+};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::RpcSimulateTransactionConfig,
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
+use solana_sdk::{
+    account::Account, message::Message, pubkey::Pubkey, transaction::Transaction,
+    transaction::TransactionError,
+};
 
 pub trait SlumlordRpcClient {
     fn get_slumlord(&self) -> Option<Account>;
@@ -12,6 +24,15 @@ pub trait SlumlordRpcClient {
             None => panic!("slumlord account does not exist"),
         }
     }
+
+    /// Simulates a `QuoteLoan` instruction paid for by `payer`,
+    /// which must be an existing account, and parses its return data
+    fn quote_slumlord_loan(&self, payer: &Pubkey) -> QuoteLoanReturn;
+
+    /// Sends `tx`, or only simulates it if `dry_run`.
+    ///
+    /// Prints the decoded slumlord error, if any, and exits on failure
+    fn send_or_sim_slumlord_tx(&self, tx: &Transaction, dry_run: bool);
 }
 
 impl SlumlordRpcClient for RpcClient {
@@ -20,4 +41,71 @@ impl SlumlordRpcClient for RpcClient {
             .unwrap()
             .value
     }
+
+    fn quote_slumlord_loan(&self, payer: &Pubkey) -> QuoteLoanReturn {
+        let msg = Message::new(&[quote_loan_ix_full().unwrap()], Some(payer));
+        let tx = Transaction::new_unsigned(msg);
+        let res = self
+            .simulate_transaction_with_config(
+                &tx,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .value;
+        if let Some(err) = res.err {
+            exit_with_tx_err(&err, res.logs.as_deref());
+        }
+        let return_data = res.return_data.expect("QuoteLoan set no return data");
+        let program_id: Pubkey = return_data.program_id.parse().unwrap();
+        let data = STANDARD.decode(return_data.data.0).unwrap();
+        try_quote_loan_return(&program_id, &data).unwrap()
+    }
+
+    fn send_or_sim_slumlord_tx(&self, tx: &Transaction, dry_run: bool) {
+        if dry_run {
+            let res = self.simulate_transaction(tx).unwrap().value;
+            let logs = res.logs.unwrap_or_default();
+            for log in logs.iter() {
+                println!("{log}");
+            }
+            match res.err {
+                Some(err) => exit_with_tx_err(&err, Some(&logs)),
+                None => println!("Simulation successful"),
+            }
+            return;
+        }
+        match self.send_and_confirm_transaction_with_spinner(tx) {
+            Ok(signature) => println!("Signature: {signature}"),
+            Err(client_err) => match client_err.get_transaction_error() {
+                Some(err) => exit_with_tx_err(&err, preflight_logs(&client_err)),
+                None => panic!("{client_err}"),
+            },
+        }
+    }
+}
+
+fn preflight_logs(client_err: &ClientError) -> Option<&[String]> {
+    match &client_err.kind {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(res),
+            ..
+        }) => res.logs.as_deref(),
+        _ => None,
+    }
+}
+
+fn exit_with_tx_err(err: &TransactionError, logs: Option<&[String]>) -> ! {
+    match try_slumlord_err(err, logs) {
+        Some((ix_idx, slumlord_err)) => {
+            eprintln!(
+                "Instruction {ix_idx} failed: SlumlordError::{slumlord_err:?}: {slumlord_err}"
+            )
+        }
+        None => eprintln!("Transaction failed: {err}"),
+    }
+    std::process::exit(1)
 }
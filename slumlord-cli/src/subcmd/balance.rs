@@ -1,11 +1,18 @@
 use clap::Args;
-use solana_sdk::native_token::lamports_to_sol;
+use slumlord_lib::{quote_loan, try_pool_limits};
+use solana_sdk::{native_token::lamports_to_sol, pubkey::Pubkey};
 
-use crate::rpc_client::SlumlordRpcClient;
+use crate::{rpc_client::SlumlordRpcClient, subcmd::max_borrow_str};
 
 #[derive(Args, Debug)]
-#[clap(long_about = "Read slumlord's current SOL balance")]
-pub struct BalanceArgs;
+#[clap(long_about = "Read slumlord's current SOL balance and loanable amount")]
+pub struct BalanceArgs {
+    #[clap(
+        long,
+        help = "Existing account to pay for simulating QuoteLoan against the deployed program. If omitted, the quote is computed from the slumlord account without a keypair"
+    )]
+    pub fee_payer: Option<Pubkey>,
+}
 
 impl BalanceArgs {
     pub fn process(&self, args: &crate::Args) {
@@ -16,10 +23,25 @@ impl BalanceArgs {
         let lamports = slumlord.lamports;
         let sol = lamports_to_sol(lamports);
 
-        let loan_lamports = lamports - 1;
+        let quote = match &self.fee_payer {
+            Some(fee_payer) => client.quote_slumlord_loan(fee_payer),
+            None => quote_loan(&slumlord),
+        };
+        let loan_lamports = quote.loanable_lamports;
         let loan_sol = lamports_to_sol(loan_lamports);
 
         println!("Total balance: {sol} SOL ({lamports} lamports)");
         println!("Loan amount: {loan_sol} SOL ({loan_lamports} lamports)");
+        if let Ok(Some(pool_limits)) = try_pool_limits(&slumlord.data) {
+            println!(
+                "Pool limits: max borrow {}, reserve {} SOL",
+                max_borrow_str(pool_limits.max_borrow_lamports),
+                lamports_to_sol(pool_limits.reserve_lamports)
+            );
+        }
+        println!("Loan fee: {} lamports", quote.fee_lamports);
+        if quote.loan_active {
+            println!("A loan is currently active");
+        }
     }
 }
@@ -1,7 +1,6 @@
 use clap::Args;
-use sanctum_solana_cli_utils::TxSendingRpcClient;
 use sanctum_system_program_lib::{transfer_ix, TransferKeys};
-use slumlord_lib::{init_ix_full, program::SLUMLORD_ID};
+use slumlord_lib::{init_ix_full, program::SLUMLORD_ID, verify_slumlord_initialized};
 use solana_sdk::{message::Message, native_token::sol_to_lamports, transaction::Transaction};
 
 use crate::rpc_client::SlumlordRpcClient;
@@ -27,10 +26,12 @@ impl InitArgs {
         let payer = args.config.signer();
         let client = args.config.rpc_client();
 
-        if client.get_slumlord().is_some() {
-            println!("slumlord already initialized");
-            if !self.force {
-                return;
+        if let Some(slumlord) = client.get_slumlord() {
+            if verify_slumlord_initialized(&slumlord.owner).is_ok() {
+                println!("slumlord already initialized");
+                if !self.force {
+                    return;
+                }
             }
         }
 
@@ -49,6 +50,6 @@ impl InitArgs {
         );
         let blockhash = client.get_latest_blockhash().unwrap();
         let tx = Transaction::new(&[payer.as_ref()], msg, blockhash);
-        client.send_or_sim_tx(&tx, args.tx_send_mode());
+        client.send_or_sim_slumlord_tx(&tx, args.dry_run);
     }
 }
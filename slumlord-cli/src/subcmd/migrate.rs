@@ -0,0 +1,49 @@
+use clap::Args;
+use slumlord_interface::migrate_ix;
+use slumlord_lib::{
+    slumlord_account_version, verify_slumlord_initialized, MigrateFreeArgs,
+    SLUMLORD_ACCOUNT_VERSION,
+};
+use solana_sdk::{message::Message, transaction::Transaction};
+
+use crate::rpc_client::SlumlordRpcClient;
+
+#[derive(Args, Debug)]
+#[clap(
+    long_about = "Migrate the slumlord account to the current account layout version, paying for any additional rent-exemption"
+)]
+pub struct MigrateArgs;
+
+impl MigrateArgs {
+    pub fn process(&self, args: &crate::Args) {
+        let payer = args.config.signer();
+        let client = args.config.rpc_client();
+
+        let slumlord = match client.get_slumlord() {
+            Some(s) => s,
+            None => {
+                println!("slumlord account does not exist. Run `slumlord init <init_sol>`");
+                return;
+            }
+        };
+        if let Err(e) = verify_slumlord_initialized(&slumlord.owner) {
+            println!("{e}. Run `slumlord init <init_sol>`");
+            return;
+        }
+        if slumlord_account_version(&slumlord.data) == Ok(Some(SLUMLORD_ACCOUNT_VERSION)) {
+            println!("slumlord already at version {SLUMLORD_ACCOUNT_VERSION}");
+            return;
+        }
+
+        let msg = Message::new(
+            &[migrate_ix(MigrateFreeArgs {
+                payer: payer.pubkey(),
+            })
+            .unwrap()],
+            Some(&payer.pubkey()),
+        );
+        let blockhash = client.get_latest_blockhash().unwrap();
+        let tx = Transaction::new(&[payer.as_ref()], msg, blockhash);
+        client.send_or_sim_slumlord_tx(&tx, args.dry_run);
+    }
+}
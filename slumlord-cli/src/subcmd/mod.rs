@@ -1,14 +1,40 @@
 use clap::Subcommand;
+use slumlord_interface::{PoolConfig, PoolLimits};
+use slumlord_lib::{
+    find_borrower_entry_address, program, try_pool_config, try_pool_limits, verify_borrower_entry,
+    verify_slumlord_initialized,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{account::Account, native_token::lamports_to_sol, pubkey::Pubkey};
 
-use self::{balance::BalanceArgs, init::InitArgs};
+use self::{
+    add_borrower::AddBorrowerArgs, balance::BalanceArgs, init::InitArgs, migrate::MigrateArgs,
+    remove_borrower::RemoveBorrowerArgs, set_admin::SetAdminArgs, set_allowlist::SetAllowlistArgs,
+    set_pool_limits::SetPoolLimitsArgs, status::StatusArgs,
+};
+use crate::rpc_client::SlumlordRpcClient;
 
+mod add_borrower;
 mod balance;
 mod init;
+mod migrate;
+mod remove_borrower;
+mod set_admin;
+mod set_allowlist;
+mod set_pool_limits;
+mod status;
 
 #[derive(Debug, Subcommand)]
 pub enum Subcmd {
     Init(InitArgs),
     Balance(BalanceArgs),
+    Status(StatusArgs),
+    Migrate(MigrateArgs),
+    SetAdmin(SetAdminArgs),
+    SetAllowlist(SetAllowlistArgs),
+    AddBorrower(AddBorrowerArgs),
+    RemoveBorrower(RemoveBorrowerArgs),
+    SetPoolLimits(SetPoolLimitsArgs),
 }
 
 impl Subcmd {
@@ -16,6 +42,98 @@ impl Subcmd {
         match self {
             Self::Init(a) => a.process(args),
             Self::Balance(a) => a.process(args),
+            Self::Status(a) => a.process(args),
+            Self::Migrate(a) => a.process(args),
+            Self::SetAdmin(a) => a.process(args),
+            Self::SetAllowlist(a) => a.process(args),
+            Self::AddBorrower(a) => a.process(args),
+            Self::RemoveBorrower(a) => a.process(args),
+            Self::SetPoolLimits(a) => a.process(args),
         }
     }
 }
+
+/// Returns the initialized slumlord account for the admin subcommands,
+/// printing what to run instead if it is not
+fn get_initialized_slumlord(client: &RpcClient) -> Option<Account> {
+    let slumlord = match client.get_slumlord() {
+        Some(s) => s,
+        None => {
+            println!("slumlord account does not exist. Run `slumlord init <init_sol>`");
+            return None;
+        }
+    };
+    if let Err(e) = verify_slumlord_initialized(&slumlord.owner) {
+        println!("{e}. Run `slumlord init <init_sol>`");
+        return None;
+    }
+    Some(slumlord)
+}
+
+/// Returns the slumlord account's [`PoolConfig`] for the admin subcommands,
+/// printing what to run instead if the account is not ready for them
+fn get_pool_config(client: &RpcClient) -> Option<PoolConfig> {
+    let slumlord = get_initialized_slumlord(client)?;
+    match try_pool_config(&slumlord.data) {
+        Ok(Some(pool_config)) => Some(*pool_config),
+        Ok(None) => {
+            println!("slumlord account layout does not record pool config. Run `slumlord migrate`");
+            None
+        }
+        Err(e) => {
+            println!("Account data corrupt: {e}");
+            None
+        }
+    }
+}
+
+/// Returns true if `borrower` has an allowlist entry.
+///
+/// Checks the entry's owner and data, not its balance, since anyone can
+/// transfer lamports to the entry PDA of a borrower that has not been added
+fn is_borrower_allowed(client: &RpcClient, borrower: &Pubkey) -> bool {
+    let (borrower_entry, _bump) = find_borrower_entry_address(borrower);
+    match client
+        .get_account_with_commitment(&borrower_entry, client.commitment())
+        .unwrap()
+        .value
+    {
+        Some(entry) => {
+            entry.owner == program::ID
+                && verify_borrower_entry(borrower, &entry.owner, &entry.data).is_ok()
+        }
+        None => false,
+    }
+}
+
+fn enabled_str(enabled: bool) -> &'static str {
+    match enabled {
+        true => "enabled",
+        false => "disabled",
+    }
+}
+
+/// Returns the slumlord account's [`PoolLimits`] for `set-pool-limits`,
+/// printing what to run instead if the account is not ready for it
+fn get_pool_limits(client: &RpcClient) -> Option<PoolLimits> {
+    let slumlord = get_initialized_slumlord(client)?;
+    match try_pool_limits(&slumlord.data) {
+        Ok(Some(pool_limits)) => Some(*pool_limits),
+        Ok(None) => {
+            println!("slumlord account layout does not record pool limits. Run `slumlord migrate`");
+            None
+        }
+        Err(e) => {
+            println!("Account data corrupt: {e}");
+            None
+        }
+    }
+}
+
+/// Formats a pool's `max_borrow_lamports`, 0 being no cap
+fn max_borrow_str(max_borrow_lamports: u64) -> String {
+    match max_borrow_lamports {
+        0 => "no limit".to_owned(),
+        lamports => format!("{} SOL ({lamports} lamports)", lamports_to_sol(lamports)),
+    }
+}
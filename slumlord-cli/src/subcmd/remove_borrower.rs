@@ -0,0 +1,46 @@
+use clap::Args;
+use slumlord_interface::remove_borrower_ix;
+use slumlord_lib::RemoveBorrowerFreeArgs;
+use solana_sdk::{message::Message, pubkey::Pubkey, transaction::Transaction};
+
+use crate::{
+    rpc_client::SlumlordRpcClient,
+    subcmd::{get_pool_config, is_borrower_allowed},
+};
+
+#[derive(Args, Debug)]
+#[clap(
+    long_about = "Remove a borrower from the allowlist, refunding its entry's rent-exemption. Admin only"
+)]
+pub struct RemoveBorrowerArgs {
+    #[clap(help = "Pubkey of the borrower to disallow")]
+    pub borrower: Pubkey,
+}
+
+impl RemoveBorrowerArgs {
+    pub fn process(&self, args: &crate::Args) {
+        let payer = args.config.signer();
+        let client = args.config.rpc_client();
+
+        if get_pool_config(&client).is_none() {
+            return;
+        }
+        if !is_borrower_allowed(&client, &self.borrower) {
+            println!("{} not allowed", self.borrower);
+            return;
+        }
+
+        let msg = Message::new(
+            &[remove_borrower_ix(RemoveBorrowerFreeArgs {
+                admin: payer.pubkey(),
+                borrower: self.borrower,
+                refund_rent_to: payer.pubkey(),
+            })
+            .unwrap()],
+            Some(&payer.pubkey()),
+        );
+        let blockhash = client.get_latest_blockhash().unwrap();
+        let tx = Transaction::new(&[payer.as_ref()], msg, blockhash);
+        client.send_or_sim_slumlord_tx(&tx, args.dry_run);
+    }
+}
@@ -0,0 +1,43 @@
+use clap::Args;
+use slumlord_interface::set_admin_ix;
+use slumlord_lib::SetAdminFreeArgs;
+use solana_sdk::{message::Message, pubkey::Pubkey, transaction::Transaction};
+
+use crate::{rpc_client::SlumlordRpcClient, subcmd::get_pool_config};
+
+#[derive(Args, Debug)]
+#[clap(
+    long_about = "Set the slumlord admin, who manages the borrower allowlist. Must be run by the current admin, or the program's upgrade authority if no admin has been set"
+)]
+pub struct SetAdminArgs {
+    #[clap(help = "Pubkey of the new admin")]
+    pub new_admin: Pubkey,
+}
+
+impl SetAdminArgs {
+    pub fn process(&self, args: &crate::Args) {
+        let payer = args.config.signer();
+        let client = args.config.rpc_client();
+
+        let pool_config = match get_pool_config(&client) {
+            Some(c) => c,
+            None => return,
+        };
+        if pool_config.admin == self.new_admin {
+            println!("{} already slumlord admin", self.new_admin);
+            return;
+        }
+
+        let msg = Message::new(
+            &[set_admin_ix(SetAdminFreeArgs {
+                authority: payer.pubkey(),
+                new_admin: self.new_admin,
+            })
+            .unwrap()],
+            Some(&payer.pubkey()),
+        );
+        let blockhash = client.get_latest_blockhash().unwrap();
+        let tx = Transaction::new(&[payer.as_ref()], msg, blockhash);
+        client.send_or_sim_slumlord_tx(&tx, args.dry_run);
+    }
+}
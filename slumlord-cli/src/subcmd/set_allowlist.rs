@@ -0,0 +1,52 @@
+use clap::Args;
+use slumlord_interface::{set_allowlist_ix, SetAllowlistIxArgs};
+use slumlord_lib::SetAllowlistFreeArgs;
+use solana_sdk::{message::Message, transaction::Transaction};
+
+use crate::{
+    rpc_client::SlumlordRpcClient,
+    subcmd::{enabled_str, get_pool_config},
+};
+
+#[derive(Args, Debug)]
+#[clap(
+    long_about = "Enable allowlist mode, in which only borrowers added with `slumlord add-borrower` can borrow. Admin only"
+)]
+pub struct SetAllowlistArgs {
+    #[clap(
+        long,
+        help = "disable allowlist mode instead, allowing anyone to borrow"
+    )]
+    pub disable: bool,
+}
+
+impl SetAllowlistArgs {
+    pub fn process(&self, args: &crate::Args) {
+        let payer = args.config.signer();
+        let client = args.config.rpc_client();
+
+        let enabled = !self.disable;
+        let pool_config = match get_pool_config(&client) {
+            Some(c) => c,
+            None => return,
+        };
+        if (pool_config.allowlist_enabled != 0) == enabled {
+            println!("allowlist mode already {}", enabled_str(enabled));
+            return;
+        }
+
+        let msg = Message::new(
+            &[set_allowlist_ix(
+                SetAllowlistFreeArgs {
+                    admin: payer.pubkey(),
+                },
+                SetAllowlistIxArgs { enabled },
+            )
+            .unwrap()],
+            Some(&payer.pubkey()),
+        );
+        let blockhash = client.get_latest_blockhash().unwrap();
+        let tx = Transaction::new(&[payer.as_ref()], msg, blockhash);
+        client.send_or_sim_slumlord_tx(&tx, args.dry_run);
+    }
+}
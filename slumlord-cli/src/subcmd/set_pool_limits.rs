@@ -0,0 +1,77 @@
+use clap::Args;
+use slumlord_interface::{set_pool_limits_ix, SetPoolLimitsIxArgs};
+use slumlord_lib::SetPoolLimitsFreeArgs;
+use solana_sdk::{
+    message::Message,
+    native_token::{lamports_to_sol, sol_to_lamports},
+    transaction::Transaction,
+};
+
+use crate::{
+    rpc_client::SlumlordRpcClient,
+    subcmd::{get_pool_limits, max_borrow_str},
+};
+
+#[derive(Args, Debug)]
+#[clap(
+    long_about = "Set the most SOL a single loan can borrow and the SOL every loan leaves in slumlord. Unspecified limits are unchanged. Admin only"
+)]
+pub struct SetPoolLimitsArgs {
+    #[clap(
+        long,
+        help = "Most SOL a single loan can borrow. 0 removes the limit, letting loans borrow everything above the reserve"
+    )]
+    pub max_borrow_sol: Option<f64>,
+
+    #[clap(
+        long,
+        help = "SOL every loan must leave in slumlord. Loans always leave at least 1 lamport"
+    )]
+    pub reserve_sol: Option<f64>,
+}
+
+impl SetPoolLimitsArgs {
+    pub fn process(&self, args: &crate::Args) {
+        let payer = args.config.signer();
+        let client = args.config.rpc_client();
+
+        let pool_limits = match get_pool_limits(&client) {
+            Some(l) => l,
+            None => return,
+        };
+        let max_borrow_lamports = self
+            .max_borrow_sol
+            .map_or(pool_limits.max_borrow_lamports, sol_to_lamports);
+        let reserve_lamports = self
+            .reserve_sol
+            .map_or(pool_limits.reserve_lamports, sol_to_lamports);
+        if max_borrow_lamports == pool_limits.max_borrow_lamports
+            && reserve_lamports == pool_limits.reserve_lamports
+        {
+            println!("pool limits unchanged");
+            return;
+        }
+        println!("Max borrow: {}", max_borrow_str(max_borrow_lamports));
+        println!(
+            "Reserve: {} SOL ({reserve_lamports} lamports)",
+            lamports_to_sol(reserve_lamports)
+        );
+
+        let msg = Message::new(
+            &[set_pool_limits_ix(
+                SetPoolLimitsFreeArgs {
+                    admin: payer.pubkey(),
+                },
+                SetPoolLimitsIxArgs {
+                    max_borrow_lamports,
+                    reserve_lamports,
+                },
+            )
+            .unwrap()],
+            Some(&payer.pubkey()),
+        );
+        let blockhash = client.get_latest_blockhash().unwrap();
+        let tx = Transaction::new(&[payer.as_ref()], msg, blockhash);
+        client.send_or_sim_slumlord_tx(&tx, args.dry_run);
+    }
+}
@@ -0,0 +1,78 @@
+use clap::Args;
+use slumlord_lib::{
+    loanable_lamports, slumlord_account_version, try_pool_config, try_pool_limits,
+    verify_slumlord_initialized,
+};
+use solana_sdk::{native_token::lamports_to_sol, pubkey::Pubkey};
+
+use crate::{
+    rpc_client::SlumlordRpcClient,
+    subcmd::{enabled_str, max_borrow_str},
+};
+
+#[derive(Args, Debug)]
+#[clap(long_about = "Check whether slumlord is ready to lend")]
+pub struct StatusArgs;
+
+impl StatusArgs {
+    pub fn process(&self, args: &crate::Args) {
+        let client = args.config.rpc_client();
+
+        let slumlord = match client.get_slumlord() {
+            Some(s) => s,
+            None => {
+                println!("slumlord account does not exist. Run `slumlord init <init_sol>`");
+                return;
+            }
+        };
+
+        let sol = lamports_to_sol(slumlord.lamports);
+        println!("Total balance: {sol} SOL ({} lamports)", slumlord.lamports);
+
+        if let Err(e) = verify_slumlord_initialized(&slumlord.owner) {
+            println!(
+                "{e}: account is owned by {}. Run `slumlord init <init_sol>`",
+                slumlord.owner
+            );
+            return;
+        }
+        println!("Initialized");
+
+        match slumlord_account_version(&slumlord.data) {
+            Ok(Some(version)) => println!("Account layout version: {version}"),
+            Ok(None) => println!("Account layout not migrated. Run `slumlord migrate`"),
+            Err(e) => println!("Account data corrupt: {e}"),
+        }
+
+        if let Ok(Some(pool_config)) = try_pool_config(&slumlord.data) {
+            if pool_config.admin == Pubkey::default() {
+                println!("Admin: not set. Run `slumlord set-admin <admin>` as the program upgrade authority");
+            } else {
+                println!("Admin: {}", pool_config.admin);
+            }
+            println!(
+                "Borrower allowlist: {}",
+                enabled_str(pool_config.allowlist_enabled != 0)
+            );
+        }
+
+        if let Ok(Some(pool_limits)) = try_pool_limits(&slumlord.data) {
+            println!(
+                "Max borrow: {}",
+                max_borrow_str(pool_limits.max_borrow_lamports)
+            );
+            println!(
+                "Reserve: {} SOL ({} lamports)",
+                lamports_to_sol(pool_limits.reserve_lamports),
+                pool_limits.reserve_lamports
+            );
+        }
+        match loanable_lamports(&slumlord.data, slumlord.lamports) {
+            Ok(lamports) => println!(
+                "Max loan amount: {} SOL ({lamports} lamports)",
+                lamports_to_sol(lamports)
+            ),
+            Err(e) => println!("Max loan amount: {e}"),
+        }
+    }
+}
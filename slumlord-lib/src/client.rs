@@ -0,0 +1,41 @@
+use slumlord_interface::SlumlordError;
+use solana_program::{decode_error::DecodeError, instruction::InstructionError, pubkey::Pubkey};
+use solana_sdk::transaction::TransactionError;
+
+use crate::program;
+
+/// Decodes a failed transaction's error into the [`SlumlordError`] it corresponds to,
+/// along with the index of the failing top-level instruction.
+///
+/// `InstructionError::Custom` codes are not unique across programs.
+/// If the transaction's `logs` are provided, they are used to check that
+/// slumlord was the program that failed. Otherwise, any custom error with a
+/// matching code is decoded
+pub fn try_slumlord_err(
+    err: &TransactionError,
+    logs: Option<&[String]>,
+) -> Option<(u8, SlumlordError)> {
+    let (ix_idx, code) = match err {
+        TransactionError::InstructionError(ix_idx, InstructionError::Custom(code)) => {
+            (*ix_idx, *code)
+        }
+        _ => return None,
+    };
+    if let Some(logs) = logs {
+        if failed_program_id(logs)? != program::ID {
+            return None;
+        }
+    }
+    let slumlord_err = SlumlordError::decode_custom_error_to_enum(code)?;
+    Some((ix_idx, slumlord_err))
+}
+
+/// Returns the program that originated the error from a failed transaction's logs:
+/// the first `Program <id> failed: <err>` line. Outer programs of a failed CPI log
+/// the same line after it.
+fn failed_program_id(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().find_map(|log| {
+        let (program_id, _err) = log.strip_prefix("Program ")?.split_once(" failed: ")?;
+        program_id.parse().ok()
+    })
+}
@@ -1,11 +1,39 @@
+use borsh::BorshDeserialize;
 use bytemuck::{try_from_bytes, try_from_bytes_mut};
+use sanctum_system_program_lib::{transfer_direct_increment, TransferAccounts};
 use slumlord_interface::{
-    check_repaid_ix, init_ix, BorrowKeys, CheckRepaidKeys, InitKeys, RepayKeys, Slumlord,
+    borrow_invoke, borrow_split_ix, check_repaid_invoke, check_repaid_ix,
+    flash_loan_with_callback_ix, init_ix, quote_loan_ix, AddBorrowerKeys, BorrowAccounts,
+    BorrowKeys, BorrowSplitIxArgs, BorrowSplitKeys, BorrowWithMinKeys, BorrowerEntry,
+    CheckRepaidAccounts, CheckRepaidKeys, CheckRepaidReturn, FlashLoanWithCallbackIxArgs,
+    FlashLoanWithCallbackKeys, InitKeys, LoanContext, MigrateKeys, PoolConfig, PoolLimits,
+    QuoteLoanKeys, QuoteLoanReturn, RemoveBorrowerKeys, RepayKeys, RepayWithMaxKeys, SetAdminKeys,
+    SetAllowlistKeys, SetPoolLimitsKeys, Slumlord, SlumlordAccount, SlumlordError, SlumlordHeader,
+    CHECK_REPAID_IX_DISCM,
 };
 use solana_program::{
-    instruction::Instruction, program_error::ProgramError, pubkey::Pubkey, system_program, sysvar,
+    account_info::AccountInfo,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    instruction::{AccountMeta, Instruction},
+    program::get_return_data,
+    program_error::ProgramError,
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    system_program, sysvar,
 };
-use solana_readonly_account::{ReadonlyAccountData, ReadonlyAccountLamports};
+use solana_readonly_account::{ReadonlyAccountData, ReadonlyAccountLamports, ReadonlyAccountOwner};
+
+#[cfg(feature = "client")]
+mod client;
+mod loan_detection;
+mod scanner;
+#[cfg(feature = "client")]
+pub mod simulator;
+
+#[cfg(feature = "client")]
+pub use client::*;
+pub use loan_detection::*;
+pub use scanner::*;
 
 pub mod program {
     sanctum_macros::declare_program_keys!(
@@ -14,7 +42,77 @@ pub mod program {
     );
 }
 
-pub const SLUMLORD_ACCOUNT_LEN: usize = std::mem::size_of::<Slumlord>();
+/// Current version of the slumlord account layout, which `Migrate` upgrades to
+pub const SLUMLORD_ACCOUNT_VERSION: u8 = 4;
+
+/// Data length of a slumlord account of the current version
+pub const SLUMLORD_ACCOUNT_LEN: usize = std::mem::size_of::<SlumlordAccount>();
+
+/// Data length of an unmigrated slumlord account during a flash loan.
+///
+/// Unmigrated accounts have no data outside of flash loans and
+/// only the loan state during one, which is the `old_lamports: u64` that
+/// clients built before versioning read with an exact-size cast
+pub const LEGACY_SLUMLORD_ACCOUNT_LEN: usize = 8;
+
+// The loan state of every layout must stay readable by clients built before versioning
+const _: () = assert!(std::mem::size_of::<Slumlord>() == LEGACY_SLUMLORD_ACCOUNT_LEN);
+
+/// `borrow_ix_index` recorded for `FlashLoanWithCallback` loans,
+/// which have no instructions sysvar to read the current instruction index from
+pub const UNKNOWN_BORROW_IX_INDEX: u16 = u16::MAX;
+
+/// `dst` recorded for `BorrowSplit` loans split across more than one `dst`,
+/// which have no single account to repay from
+pub const SPLIT_LOAN_DST: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// Seed prefix of borrower allowlist entry PDAs ["borrower", borrower]
+pub const BORROWER_ENTRY_SEED: &[u8] = b"borrower";
+
+/// Data length of a borrower allowlist entry
+pub const BORROWER_ENTRY_LEN: usize = std::mem::size_of::<BorrowerEntry>();
+
+/// Number of accounts a borrow instruction must additionally be passed
+/// in allowlist mode, see [`borrower_account_metas`]
+pub const BORROWER_ACCOUNTS_LEN: usize = 2;
+
+/// Borsh-serialized length of [`QuoteLoanReturn`]
+pub const QUOTE_LOAN_RETURN_LEN: usize = 2 * std::mem::size_of::<u64>() + 1;
+
+const SLUMLORD_HEADER_LEN: usize = std::mem::size_of::<SlumlordHeader>();
+
+const LOAN_CONTEXT_LEN: usize = std::mem::size_of::<LoanContext>();
+
+/// Version 1 only has the header and loan state
+const SLUMLORD_ACCOUNT_V1_LEN: usize = SLUMLORD_HEADER_LEN + LEGACY_SLUMLORD_ACCOUNT_LEN;
+
+/// First version that records [`LoanContext`], right after the loan state
+const LOAN_CONTEXT_VERSION: u8 = 2;
+
+const SLUMLORD_ACCOUNT_V2_LEN: usize = SLUMLORD_ACCOUNT_V1_LEN + LOAN_CONTEXT_LEN;
+
+const POOL_CONFIG_LEN: usize = std::mem::size_of::<PoolConfig>();
+
+/// First version that records [`PoolConfig`], right after [`LoanContext`]
+const POOL_CONFIG_VERSION: u8 = 3;
+
+const SLUMLORD_ACCOUNT_V3_LEN: usize = SLUMLORD_ACCOUNT_V2_LEN + POOL_CONFIG_LEN;
+
+const POOL_LIMITS_LEN: usize = std::mem::size_of::<PoolLimits>();
+
+/// First version that records [`PoolLimits`], right after [`PoolConfig`]
+const POOL_LIMITS_VERSION: u8 = 4;
+
+/// Data length of each version of the slumlord account layout, indexed by `version - 1`.
+///
+/// New versions only append fields to [`SlumlordAccount`] so that the header
+/// and loan state are at the same offsets in every version
+const SLUMLORD_ACCOUNT_VERSION_LENS: [usize; SLUMLORD_ACCOUNT_VERSION as usize] = [
+    SLUMLORD_ACCOUNT_V1_LEN,
+    SLUMLORD_ACCOUNT_V2_LEN,
+    SLUMLORD_ACCOUNT_V3_LEN,
+    SLUMLORD_ACCOUNT_LEN,
+];
 
 pub const INIT_KEYS: InitKeys = InitKeys {
     slumlord: program::SLUMLORD_ID,
@@ -25,6 +123,15 @@ pub const CHECK_REPAID_KEYS: CheckRepaidKeys = CheckRepaidKeys {
     slumlord: program::SLUMLORD_ID,
 };
 
+pub const QUOTE_LOAN_KEYS: QuoteLoanKeys = QuoteLoanKeys {
+    slumlord: program::SLUMLORD_ID,
+};
+
+pub const BORROW_SPLIT_KEYS: BorrowSplitKeys = BorrowSplitKeys {
+    slumlord: program::SLUMLORD_ID,
+    instructions: sysvar::instructions::ID,
+};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct BorrowFreeArgs {
     pub dst: Pubkey,
@@ -46,12 +153,44 @@ impl From<BorrowFreeArgs> for BorrowKeys {
     }
 }
 
+impl From<BorrowFreeArgs> for BorrowWithMinKeys {
+    fn from(value: BorrowFreeArgs) -> Self {
+        let BorrowKeys {
+            slumlord,
+            dst,
+            instructions,
+        } = value.resolve();
+        Self {
+            slumlord,
+            dst,
+            instructions,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RepayFreeArgs {
     pub src: Pubkey,
 }
 
 impl RepayFreeArgs {
+    /// Repays from the `dst` the current flash loan was lent to.
+    ///
+    /// Errors if no flash loan is active or the slumlord account does not
+    /// record [`LoanContext`], see [`LoanActiveSlumlordAccount::loan_context`].
+    ///
+    /// Errors with [`ProgramError::InvalidArgument`] if the loan was split across
+    /// multiple `dst`s by `BorrowSplit`, since there is no single `dst` to repay from
+    pub fn for_active_loan<D: ReadonlyAccountData + ReadonlyAccountLamports>(
+        slumlord: &D,
+    ) -> Result<Self, ProgramError> {
+        let dst = slumlord.loan_context()?.dst;
+        if dst == SPLIT_LOAN_DST {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(Self { src: dst })
+    }
+
     pub fn resolve(self) -> RepayKeys {
         RepayKeys {
             src: self.src,
@@ -67,6 +206,211 @@ impl From<RepayFreeArgs> for RepayKeys {
     }
 }
 
+impl From<RepayFreeArgs> for RepayWithMaxKeys {
+    fn from(value: RepayFreeArgs) -> Self {
+        let RepayKeys {
+            slumlord,
+            src,
+            system_program,
+        } = value.resolve();
+        Self {
+            slumlord,
+            src,
+            system_program,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FlashLoanWithCallbackFreeArgs {
+    pub receiver: Pubkey,
+    pub receiver_program: Pubkey,
+    pub initiator: Pubkey,
+}
+
+impl FlashLoanWithCallbackFreeArgs {
+    pub fn resolve(self) -> FlashLoanWithCallbackKeys {
+        FlashLoanWithCallbackKeys {
+            receiver: self.receiver,
+            receiver_program: self.receiver_program,
+            initiator: self.initiator,
+            slumlord: program::SLUMLORD_ID,
+        }
+    }
+}
+
+impl From<FlashLoanWithCallbackFreeArgs> for FlashLoanWithCallbackKeys {
+    fn from(value: FlashLoanWithCallbackFreeArgs) -> Self {
+        value.resolve()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MigrateFreeArgs {
+    pub payer: Pubkey,
+}
+
+impl MigrateFreeArgs {
+    pub fn resolve(self) -> MigrateKeys {
+        MigrateKeys {
+            payer: self.payer,
+            slumlord: program::SLUMLORD_ID,
+            system_program: system_program::ID,
+        }
+    }
+}
+
+impl From<MigrateFreeArgs> for MigrateKeys {
+    fn from(value: MigrateFreeArgs) -> Self {
+        value.resolve()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SetAdminFreeArgs {
+    pub authority: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+impl SetAdminFreeArgs {
+    pub fn resolve(self) -> SetAdminKeys {
+        SetAdminKeys {
+            authority: self.authority,
+            new_admin: self.new_admin,
+            slumlord: program::SLUMLORD_ID,
+            program_data: program_data_address(),
+        }
+    }
+}
+
+impl From<SetAdminFreeArgs> for SetAdminKeys {
+    fn from(value: SetAdminFreeArgs) -> Self {
+        value.resolve()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SetAllowlistFreeArgs {
+    pub admin: Pubkey,
+}
+
+impl SetAllowlistFreeArgs {
+    pub fn resolve(self) -> SetAllowlistKeys {
+        SetAllowlistKeys {
+            admin: self.admin,
+            slumlord: program::SLUMLORD_ID,
+        }
+    }
+}
+
+impl From<SetAllowlistFreeArgs> for SetAllowlistKeys {
+    fn from(value: SetAllowlistFreeArgs) -> Self {
+        value.resolve()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AddBorrowerFreeArgs {
+    pub admin: Pubkey,
+    pub payer: Pubkey,
+    pub borrower: Pubkey,
+}
+
+impl AddBorrowerFreeArgs {
+    /// Returns the resolved keys along with the bump of `borrower_entry`
+    pub fn resolve_with_bump(self) -> (AddBorrowerKeys, u8) {
+        let (borrower_entry, bump) = find_borrower_entry_address(&self.borrower);
+        let keys = AddBorrowerKeys {
+            admin: self.admin,
+            payer: self.payer,
+            borrower: self.borrower,
+            borrower_entry,
+            slumlord: program::SLUMLORD_ID,
+            system_program: system_program::ID,
+        };
+        (keys, bump)
+    }
+
+    pub fn resolve(self) -> AddBorrowerKeys {
+        self.resolve_with_bump().0
+    }
+}
+
+impl From<AddBorrowerFreeArgs> for AddBorrowerKeys {
+    fn from(value: AddBorrowerFreeArgs) -> Self {
+        value.resolve()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RemoveBorrowerFreeArgs {
+    pub admin: Pubkey,
+    pub borrower: Pubkey,
+    pub refund_rent_to: Pubkey,
+}
+
+impl RemoveBorrowerFreeArgs {
+    pub fn resolve(self) -> RemoveBorrowerKeys {
+        RemoveBorrowerKeys {
+            admin: self.admin,
+            borrower: self.borrower,
+            borrower_entry: find_borrower_entry_address(&self.borrower).0,
+            refund_rent_to: self.refund_rent_to,
+            slumlord: program::SLUMLORD_ID,
+        }
+    }
+}
+
+impl From<RemoveBorrowerFreeArgs> for RemoveBorrowerKeys {
+    fn from(value: RemoveBorrowerFreeArgs) -> Self {
+        value.resolve()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SetPoolLimitsFreeArgs {
+    pub admin: Pubkey,
+}
+
+impl SetPoolLimitsFreeArgs {
+    pub fn resolve(self) -> SetPoolLimitsKeys {
+        SetPoolLimitsKeys {
+            admin: self.admin,
+            slumlord: program::SLUMLORD_ID,
+        }
+    }
+}
+
+impl From<SetPoolLimitsFreeArgs> for SetPoolLimitsKeys {
+    fn from(value: SetPoolLimitsFreeArgs) -> Self {
+        value.resolve()
+    }
+}
+
+/// Returns the slumlord program's program data account,
+/// which records its upgrade authority
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[program::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// Returns the borrower allowlist entry PDA of `borrower` and its bump
+pub fn find_borrower_entry_address(borrower: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BORROWER_ENTRY_SEED, borrower.as_ref()], &program::ID)
+}
+
+/// Returns the accounts a borrow instruction must additionally be passed
+/// in allowlist mode: `borrower` as signer followed by its allowlist entry.
+///
+/// They go right after the instruction's accounts for `Borrow` and `BorrowWithMin`,
+/// after the `dst`s for `BorrowSplit` and before the callback accounts
+/// for `FlashLoanWithCallback`
+pub fn borrower_account_metas(borrower: Pubkey) -> [AccountMeta; BORROWER_ACCOUNTS_LEN] {
+    [
+        AccountMeta::new_readonly(borrower, true),
+        AccountMeta::new_readonly(find_borrower_entry_address(&borrower).0, false),
+    ]
+}
+
 pub fn init_ix_full() -> std::io::Result<Instruction> {
     init_ix(INIT_KEYS)
 }
@@ -75,12 +419,692 @@ pub fn check_repaid_ix_full() -> std::io::Result<Instruction> {
     check_repaid_ix(CHECK_REPAID_KEYS)
 }
 
+pub fn quote_loan_ix_full() -> std::io::Result<Instruction> {
+    quote_loan_ix(QUOTE_LOAN_KEYS)
+}
+
+/// Creates a `BorrowSplit` instruction lending each `(dst, amount)` pair,
+/// appending the `dst`s as writable remaining accounts in the same order.
+pub fn borrow_split_ix_full(loans: &[(Pubkey, u64)]) -> std::io::Result<Instruction> {
+    let mut ix = borrow_split_ix(
+        BORROW_SPLIT_KEYS,
+        BorrowSplitIxArgs {
+            amounts: loans.iter().map(|(_dst, amount)| *amount).collect(),
+        },
+    )?;
+    ix.accounts.extend(
+        loans
+            .iter()
+            .map(|(dst, _amount)| AccountMeta::new(*dst, false)),
+    );
+    Ok(ix)
+}
+
+/// Creates a `FlashLoanWithCallback` instruction, appending `callback_accounts`
+/// as the remaining accounts passed to the receiver program's `OnFlashLoan`.
+pub fn flash_loan_with_callback_ix_full(
+    free_args: FlashLoanWithCallbackFreeArgs,
+    args: FlashLoanWithCallbackIxArgs,
+    callback_accounts: &[AccountMeta],
+) -> std::io::Result<Instruction> {
+    let mut ix = flash_loan_with_callback_ix(free_args, args)?;
+    ix.accounts.extend_from_slice(callback_accounts);
+    Ok(ix)
+}
+
+/// Checks that the slumlord account has been assigned to the slumlord program by `Init`.
+///
+/// `slumlord_owner` is the slumlord account's owner
+pub fn verify_slumlord_initialized(slumlord_owner: &Pubkey) -> Result<(), SlumlordError> {
+    if *slumlord_owner != program::ID {
+        return Err(SlumlordError::NotInitialized);
+    }
+    Ok(())
+}
+
+/// Returns the loan a `Borrow` of the slumlord account would currently lend,
+/// as set in `QuoteLoan`'s return data.
+///
+/// Nothing is loanable if the slumlord account is uninitialized, a loan is active,
+/// its balance is below the reserve or its data is corrupt.
+///
+/// Does not check identity of slumlord account
+pub fn quote_loan<D: ReadonlyAccountData + ReadonlyAccountLamports + ReadonlyAccountOwner>(
+    slumlord: &D,
+) -> QuoteLoanReturn {
+    let loan_active = is_loan_active(&slumlord.data()).unwrap_or(true);
+    let loanable_lamports = match verify_slumlord_initialized(slumlord.owner()) {
+        Ok(()) if !loan_active => {
+            loanable_lamports(&slumlord.data(), slumlord.lamports()).unwrap_or(0)
+        }
+        _ => 0,
+    };
+    QuoteLoanReturn {
+        loanable_lamports,
+        fee_lamports: 0,
+        loan_active,
+    }
+}
+
+/// Parses the return data of a simulated `QuoteLoan` instruction.
+///
+/// `program_id` is the program that set the return data.
+/// Transaction return data has its trailing zero bytes trimmed, so `data`
+/// is zero-padded to [`QUOTE_LOAN_RETURN_LEN`] before deserializing
+pub fn try_quote_loan_return(
+    program_id: &Pubkey,
+    data: &[u8],
+) -> Result<QuoteLoanReturn, ProgramError> {
+    if *program_id != program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut padded = [0u8; QUOTE_LOAN_RETURN_LEN];
+    padded
+        .get_mut(..data.len())
+        .ok_or(ProgramError::InvalidInstructionData)?
+        .copy_from_slice(data);
+    Ok(QuoteLoanReturn::try_from_slice(&padded)?)
+}
+
+/// Returns true if `ix` is a slumlord CheckRepaid instruction
+pub fn is_check_repaid_ix(ix: &Instruction) -> bool {
+    let discm = match ix.data.first() {
+        Some(d) => d,
+        None => return false,
+    };
+    if *discm != CHECK_REPAID_IX_DISCM {
+        return false;
+    }
+    if ix.program_id != program::ID {
+        return false;
+    }
+    true
+}
+
+/// Returns true if `ix` is a slumlord CheckRepaid instruction
+/// whose `slumlord` account is `slumlord`
+pub fn is_check_repaid_ix_for(ix: &Instruction, slumlord: &Pubkey) -> bool {
+    is_check_repaid_ix(ix) && ix.accounts.first().map(|meta| meta.pubkey) == Some(*slumlord)
+}
+
+/// Returns the layout version of the slumlord account data,
+/// `None` if the account has not been migrated by `Migrate`.
+///
+/// Errors with [`SlumlordError::CorruptLoanState`] if the data is of neither
+/// the unmigrated layout nor a known version
+pub fn slumlord_account_version(slumlord_acc_data: &[u8]) -> Result<Option<u8>, ProgramError> {
+    let version = match slumlord_acc_data.len() {
+        0 | LEGACY_SLUMLORD_ACCOUNT_LEN => return Ok(None),
+        _ => slumlord_acc_data[0],
+    };
+    match usize::from(version)
+        .checked_sub(1)
+        .and_then(|i| SLUMLORD_ACCOUNT_VERSION_LENS.get(i))
+    {
+        Some(len) if *len == slumlord_acc_data.len() => Ok(Some(version)),
+        _ => Err(SlumlordError::CorruptLoanState.into()),
+    }
+}
+
+/// Returns the header of versioned slumlord account data,
+/// `None` if the account has not been migrated by `Migrate`
+pub fn try_slumlord_header(
+    slumlord_acc_data: &[u8],
+) -> Result<Option<&SlumlordHeader>, ProgramError> {
+    if slumlord_account_version(slumlord_acc_data)?.is_none() {
+        return Ok(None);
+    }
+    try_from_bytes(&slumlord_acc_data[..SLUMLORD_HEADER_LEN])
+        .map(Some)
+        .map_err(|_e| SlumlordError::CorruptLoanState.into())
+}
+
+pub fn try_slumlord_header_mut(
+    slumlord_acc_data: &mut [u8],
+) -> Result<Option<&mut SlumlordHeader>, ProgramError> {
+    if slumlord_account_version(slumlord_acc_data)?.is_none() {
+        return Ok(None);
+    }
+    try_from_bytes_mut(&mut slumlord_acc_data[..SLUMLORD_HEADER_LEN])
+        .map(Some)
+        .map_err(|_e| SlumlordError::CorruptLoanState.into())
+}
+
+/// Returns true if the slumlord account data records an active flash loan.
+///
+/// Unmigrated accounts only have data during a flash loan,
+/// versioned accounts record it in their header
+pub fn is_loan_active(slumlord_acc_data: &[u8]) -> Result<bool, ProgramError> {
+    Ok(match try_slumlord_header(slumlord_acc_data)? {
+        Some(header) => header.loan_active != 0,
+        None => !slumlord_acc_data.is_empty(),
+    })
+}
+
+/// Returns the loan state of slumlord account data of any layout.
+///
+/// The loan state of versioned accounts is zeroed while no flash loan is active,
+/// unmigrated accounts have none
 pub fn try_slumlord(slumlord_acc_data: &[u8]) -> Result<&Slumlord, ProgramError> {
-    try_from_bytes(slumlord_acc_data).map_err(|_e| ProgramError::InvalidAccountData)
+    let loan_data = match slumlord_account_version(slumlord_acc_data)? {
+        Some(_) => &slumlord_acc_data[SLUMLORD_HEADER_LEN..][..LEGACY_SLUMLORD_ACCOUNT_LEN],
+        None => slumlord_acc_data,
+    };
+    try_from_bytes(loan_data).map_err(|_e| SlumlordError::CorruptLoanState.into())
 }
 
 pub fn try_slumlord_mut(slumlord_acc_data: &mut [u8]) -> Result<&mut Slumlord, ProgramError> {
-    try_from_bytes_mut(slumlord_acc_data).map_err(|_e| ProgramError::InvalidAccountData)
+    let loan_data = match slumlord_account_version(slumlord_acc_data)? {
+        Some(_) => &mut slumlord_acc_data[SLUMLORD_HEADER_LEN..][..LEGACY_SLUMLORD_ACCOUNT_LEN],
+        None => slumlord_acc_data,
+    };
+    try_from_bytes_mut(loan_data).map_err(|_e| SlumlordError::CorruptLoanState.into())
+}
+
+/// Returns the [`LoanContext`] of versioned slumlord account data,
+/// `None` if the account's version predates it.
+///
+/// Zeroed while no flash loan is active
+pub fn try_loan_context(slumlord_acc_data: &[u8]) -> Result<Option<&LoanContext>, ProgramError> {
+    match slumlord_account_version(slumlord_acc_data)? {
+        Some(version) if version >= LOAN_CONTEXT_VERSION => {
+            try_from_bytes(&slumlord_acc_data[SLUMLORD_ACCOUNT_V1_LEN..][..LOAN_CONTEXT_LEN])
+                .map(Some)
+                .map_err(|_e| SlumlordError::CorruptLoanState.into())
+        }
+        _ => Ok(None),
+    }
+}
+
+pub fn try_loan_context_mut(
+    slumlord_acc_data: &mut [u8],
+) -> Result<Option<&mut LoanContext>, ProgramError> {
+    match slumlord_account_version(slumlord_acc_data)? {
+        Some(version) if version >= LOAN_CONTEXT_VERSION => try_from_bytes_mut(
+            &mut slumlord_acc_data[SLUMLORD_ACCOUNT_V1_LEN..][..LOAN_CONTEXT_LEN],
+        )
+        .map(Some)
+        .map_err(|_e| SlumlordError::CorruptLoanState.into()),
+        _ => Ok(None),
+    }
+}
+
+/// Errors with [`SlumlordError::BorrowAlreadyActive`] if the slumlord account data
+/// records an active flash loan
+pub fn verify_no_active_loan(slumlord_acc_data: &[u8]) -> Result<(), ProgramError> {
+    if is_loan_active(slumlord_acc_data)? {
+        return Err(SlumlordError::BorrowAlreadyActive.into());
+    }
+    Ok(())
+}
+
+/// Checks that a top-level CheckRepaid instruction of `slumlord` follows the
+/// instruction at `curr_ix_idx` in `ixs`, returning `curr_ix_idx`
+pub fn verify_succeeding_check_repaid(
+    ixs: SlumlordIxScanner,
+    curr_ix_idx: u16,
+    slumlord: &Pubkey,
+) -> Result<u16, SlumlordError> {
+    let next_ix_idx = curr_ix_idx
+        .checked_add(1)
+        .ok_or(SlumlordError::NoSucceedingCheckRepaid)?;
+    if !ixs
+        .starting_at(next_ix_idx)
+        .any(|ix| ix.is_check_repaid_for(slumlord))
+    {
+        return Err(SlumlordError::NoSucceedingCheckRepaid);
+    }
+    Ok(curr_ix_idx)
+}
+
+/// Lending to the slumlord account itself debits and credits the same account,
+/// which the runtime rejects as an unbalanced instruction
+pub fn verify_not_loan_to_self(dst: &Pubkey) -> Result<(), SlumlordError> {
+    if *dst == program::SLUMLORD_ID {
+        return Err(SlumlordError::LoanToSelf);
+    }
+    Ok(())
+}
+
+/// Checks that every `dst` of a `BorrowSplit` is writable and not the slumlord account
+pub fn verify_split_loan_dsts<A: InstructionAccount>(dsts: &[A]) -> Result<(), ProgramError> {
+    for dst in dsts {
+        if !dst.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        verify_not_loan_to_self(dst.key())?;
+    }
+    Ok(())
+}
+
+/// Starts a flash loan in slumlord account data with no active loan, recording
+/// `slumlord_lamports` as the pre-loan balance and returning the most lamports
+/// the loan can lend, see [`loanable_lamports`].
+///
+/// Unmigrated account data, which is empty while no flash loan is active, must be
+/// extended to [`LEGACY_SLUMLORD_ACCOUNT_LEN`] after [`verify_no_active_loan`]
+pub fn start_loan(
+    slumlord_acc_data: &mut [u8],
+    slumlord_lamports: u64,
+) -> Result<u64, ProgramError> {
+    let loanable_lamports = loanable_lamports(slumlord_acc_data, slumlord_lamports)?;
+    if let Some(header) = try_slumlord_header_mut(slumlord_acc_data)? {
+        header.loan_active = 1;
+    }
+    *try_slumlord_mut(slumlord_acc_data)? = Slumlord {
+        old_lamports: slumlord_lamports,
+    };
+    Ok(loanable_lamports)
+}
+
+/// Errors with [`SlumlordError::InsufficientLiquidity`] if a loan of `lamports`
+/// exceeds `loanable_lamports`, as returned by [`start_loan`]
+pub fn verify_loanable(lamports: u64, loanable_lamports: u64) -> Result<(), SlumlordError> {
+    if lamports > loanable_lamports {
+        return Err(SlumlordError::InsufficientLiquidity);
+    }
+    Ok(())
+}
+
+/// Returns the total lamports lent by a `BorrowSplit` of `amounts`.
+///
+/// Errors with [`SlumlordError::InsufficientLiquidity`] if it exceeds `loanable_lamports`
+pub fn split_loan_lamports(amounts: &[u64], loanable_lamports: u64) -> Result<u64, SlumlordError> {
+    amounts
+        .iter()
+        .try_fold(0u64, |sum, amount| sum.checked_add(*amount))
+        .filter(|total| *total <= loanable_lamports)
+        .ok_or(SlumlordError::InsufficientLiquidity)
+}
+
+/// Returns the [`CheckRepaidReturn`] of the active flash loan recorded in slumlord
+/// account data now holding `slumlord_lamports`, which must then be ended with
+/// [`clear_versioned_loan`].
+///
+/// `repaid` is `None` if the account's version predates [`LoanContext`],
+/// which records the lent lamports.
+///
+/// Errors with [`SlumlordError::InsufficientRepay`] if `slumlord_lamports`
+/// is below the pre-loan balance, see [`unrepaid_loan_msg`]
+pub fn check_loan_repaid(
+    slumlord_acc_data: &[u8],
+    slumlord_lamports: u64,
+) -> Result<CheckRepaidReturn, ProgramError> {
+    let old_lamports = try_slumlord(slumlord_acc_data)?.old_lamports;
+    let surplus = slumlord_lamports
+        .checked_sub(old_lamports)
+        .ok_or(SlumlordError::InsufficientRepay)?;
+    let repaid = try_loan_context(slumlord_acc_data)?
+        .map(|loan_context| loan_context.lent_lamports.saturating_add(surplus));
+    Ok(CheckRepaidReturn {
+        old_lamports,
+        repaid,
+        surplus,
+    })
+}
+
+/// The `dst` to record for a `BorrowSplit` loan lending to `dsts`:
+/// the only `dst` if there is exactly one, [`SPLIT_LOAN_DST`] otherwise
+pub fn split_loan_dst<'a>(mut dsts: impl Iterator<Item = &'a Pubkey>) -> Pubkey {
+    match (dsts.next(), dsts.next()) {
+        (Some(dst), None) => *dst,
+        _ => SPLIT_LOAN_DST,
+    }
+}
+
+/// Records the active flash loan's total lent lamports, the `dst` lent to
+/// and the index of the top-level instruction that started it
+/// if the slumlord account's version records [`LoanContext`].
+///
+/// No-op otherwise, since the unmigrated loan state must keep
+/// the layout older clients read
+pub fn record_loan(
+    slumlord_acc_data: &mut [u8],
+    lent_lamports: u64,
+    dst: &Pubkey,
+    borrow_ix_index: u16,
+) -> Result<(), ProgramError> {
+    if let Some(loan_context) = try_loan_context_mut(slumlord_acc_data)? {
+        loan_context.dst = *dst;
+        loan_context.borrow_ix_index = borrow_ix_index;
+        loan_context.lent_lamports = lent_lamports;
+    }
+    Ok(())
+}
+
+/// Ends the flash loan recorded in versioned slumlord account data,
+/// zeroing its loan state and [`LoanContext`].
+///
+/// Returns false without modifying unmigrated account data,
+/// which must instead be emptied
+pub fn clear_versioned_loan(slumlord_acc_data: &mut [u8]) -> Result<bool, ProgramError> {
+    match try_slumlord_header_mut(slumlord_acc_data)? {
+        Some(header) => header.loan_active = 0,
+        None => return Ok(false),
+    }
+    *try_slumlord_mut(slumlord_acc_data)? = Slumlord { old_lamports: 0 };
+    if let Some(loan_context) = try_loan_context_mut(slumlord_acc_data)? {
+        *loan_context = LoanContext {
+            dst: Pubkey::default(),
+            borrow_ix_index: 0,
+            padding: [0; 6],
+            lent_lamports: 0,
+        };
+    }
+    Ok(true)
+}
+
+/// Describes a flash loan that was not repaid by `shortfall_lamports`,
+/// as logged before failing with [`SlumlordError::InsufficientRepay`]
+pub fn unrepaid_loan_msg(loan_context: Option<&LoanContext>, shortfall_lamports: u64) -> String {
+    let Some(LoanContext {
+        dst,
+        borrow_ix_index,
+        ..
+    }) = loan_context
+    else {
+        return format!("Loan not repaid, short by {shortfall_lamports} lamports");
+    };
+    let to = if *dst == SPLIT_LOAN_DST {
+        "multiple accounts".to_owned()
+    } else {
+        dst.to_string()
+    };
+    if *borrow_ix_index == UNKNOWN_BORROW_IX_INDEX {
+        format!("Loan to {to} not repaid, short by {shortfall_lamports} lamports")
+    } else {
+        format!(
+            "Loan from instruction {borrow_ix_index} to {to} not repaid, short by {shortfall_lamports} lamports"
+        )
+    }
+}
+
+/// Upgrades slumlord account data with no active flash loan to the current version in place,
+/// zero-initializing the fields it did not have.
+///
+/// `slumlord_acc_data` must already be resized to [`SLUMLORD_ACCOUNT_LEN`]
+/// from its previous length of `old_len`
+pub fn migrate_slumlord_data(
+    slumlord_acc_data: &mut [u8],
+    old_len: usize,
+) -> Result<(), ProgramError> {
+    if slumlord_acc_data.len() != SLUMLORD_ACCOUNT_LEN || old_len == LEGACY_SLUMLORD_ACCOUNT_LEN {
+        return Err(SlumlordError::CorruptLoanState.into());
+    }
+    let new_fields = slumlord_acc_data
+        .get_mut(old_len..)
+        .ok_or(SlumlordError::CorruptLoanState)?;
+    new_fields.fill(0);
+    let header: &mut SlumlordHeader =
+        try_from_bytes_mut(&mut slumlord_acc_data[..SLUMLORD_HEADER_LEN])
+            .map_err(|_e| SlumlordError::CorruptLoanState)?;
+    header.version = SLUMLORD_ACCOUNT_VERSION;
+    Ok(())
+}
+
+/// Returns the [`PoolConfig`] of versioned slumlord account data,
+/// `None` if the account's version predates it
+pub fn try_pool_config(slumlord_acc_data: &[u8]) -> Result<Option<&PoolConfig>, ProgramError> {
+    match slumlord_account_version(slumlord_acc_data)? {
+        Some(version) if version >= POOL_CONFIG_VERSION => {
+            try_from_bytes(&slumlord_acc_data[SLUMLORD_ACCOUNT_V2_LEN..][..POOL_CONFIG_LEN])
+                .map(Some)
+                .map_err(|_e| SlumlordError::CorruptLoanState.into())
+        }
+        _ => Ok(None),
+    }
+}
+
+pub fn try_pool_config_mut(
+    slumlord_acc_data: &mut [u8],
+) -> Result<Option<&mut PoolConfig>, ProgramError> {
+    match slumlord_account_version(slumlord_acc_data)? {
+        Some(version) if version >= POOL_CONFIG_VERSION => {
+            try_from_bytes_mut(&mut slumlord_acc_data[SLUMLORD_ACCOUNT_V2_LEN..][..POOL_CONFIG_LEN])
+                .map(Some)
+                .map_err(|_e| SlumlordError::CorruptLoanState.into())
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns the [`PoolLimits`] of versioned slumlord account data,
+/// `None` if the account's version predates it
+pub fn try_pool_limits(slumlord_acc_data: &[u8]) -> Result<Option<&PoolLimits>, ProgramError> {
+    match slumlord_account_version(slumlord_acc_data)? {
+        Some(version) if version >= POOL_LIMITS_VERSION => {
+            try_from_bytes(&slumlord_acc_data[SLUMLORD_ACCOUNT_V3_LEN..][..POOL_LIMITS_LEN])
+                .map(Some)
+                .map_err(|_e| SlumlordError::CorruptLoanState.into())
+        }
+        _ => Ok(None),
+    }
+}
+
+pub fn try_pool_limits_mut(
+    slumlord_acc_data: &mut [u8],
+) -> Result<Option<&mut PoolLimits>, ProgramError> {
+    match slumlord_account_version(slumlord_acc_data)? {
+        Some(version) if version >= POOL_LIMITS_VERSION => {
+            try_from_bytes_mut(&mut slumlord_acc_data[SLUMLORD_ACCOUNT_V3_LEN..][..POOL_LIMITS_LEN])
+                .map(Some)
+                .map_err(|_e| SlumlordError::CorruptLoanState.into())
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Returns the most lamports a loan can take from a slumlord account holding
+/// `slumlord_lamports` and `slumlord_acc_data`: everything above its `reserve_lamports`,
+/// which is at least 1, capped at its `max_borrow_lamports` if nonzero.
+///
+/// Accounts whose version predates [`PoolLimits`] have no cap and a 1 lamport reserve.
+///
+/// Errors with [`SlumlordError::PoolEmpty`] if `slumlord_lamports` is below the reserve
+pub fn loanable_lamports(
+    slumlord_acc_data: &[u8],
+    slumlord_lamports: u64,
+) -> Result<u64, ProgramError> {
+    let (max_borrow_lamports, reserve_lamports) = match try_pool_limits(slumlord_acc_data)? {
+        Some(limits) => (limits.max_borrow_lamports, limits.reserve_lamports),
+        None => (0, 0),
+    };
+    let above_reserve = slumlord_lamports
+        .checked_sub(reserve_lamports.max(1))
+        .ok_or(SlumlordError::PoolEmpty)?;
+    Ok(match max_borrow_lamports {
+        0 => above_reserve,
+        max_borrow_lamports => above_reserve.min(max_borrow_lamports),
+    })
+}
+
+/// Returns true if borrowing requires an allowlisted borrower's signature.
+///
+/// Accounts whose version predates [`PoolConfig`] are not in allowlist mode
+pub fn is_allowlist_enabled(slumlord_acc_data: &[u8]) -> Result<bool, ProgramError> {
+    Ok(matches!(
+        try_pool_config(slumlord_acc_data)?,
+        Some(pool_config) if pool_config.allowlist_enabled != 0
+    ))
+}
+
+/// Checks that `admin` is the admin recorded in the slumlord account data.
+///
+/// Errors with [`SlumlordError::NoPoolConfig`] if the account has not been migrated
+/// to a version that records it or [`SlumlordError::NotAdmin`] if no admin has been set.
+///
+/// Does not check that `admin` signed
+pub fn verify_admin(slumlord_acc_data: &[u8], admin: &Pubkey) -> Result<(), ProgramError> {
+    let pool_config = try_pool_config(slumlord_acc_data)?.ok_or(SlumlordError::NoPoolConfig)?;
+    if pool_config.admin == Pubkey::default() || pool_config.admin != *admin {
+        return Err(SlumlordError::NotAdmin.into());
+    }
+    Ok(())
+}
+
+/// Returns the upgrade authority recorded in the slumlord program's program data account
+/// with `program_data_owner` and `program_data`, `None` if the program is immutable
+pub fn program_upgrade_authority(
+    program_data_owner: &Pubkey,
+    program_data: &[u8],
+) -> Result<Option<Pubkey>, ProgramError> {
+    if *program_data_owner != bpf_loader_upgradeable::ID {
+        return Err(ProgramError::IllegalOwner);
+    }
+    match limited_deserialize(
+        program_data,
+        UpgradeableLoaderState::size_of_programdata_metadata() as u64,
+    ) {
+        Ok(UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address,
+            ..
+        }) => Ok(upgrade_authority_address),
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Sets the admin recorded in the slumlord account data to `new_admin`.
+///
+/// `authority` must be the current admin, or the program's upgrade authority
+/// returned by `upgrade_authority` if no admin has been set.
+///
+/// Does not check that `authority` signed
+pub fn set_admin(
+    slumlord_acc_data: &mut [u8],
+    authority: &Pubkey,
+    new_admin: &Pubkey,
+    upgrade_authority: impl FnOnce() -> Result<Option<Pubkey>, ProgramError>,
+) -> Result<(), ProgramError> {
+    let pool_config = try_pool_config_mut(slumlord_acc_data)?.ok_or(SlumlordError::NoPoolConfig)?;
+    let current_authority = if pool_config.admin == Pubkey::default() {
+        upgrade_authority()?
+    } else {
+        Some(pool_config.admin)
+    };
+    if current_authority != Some(*authority) {
+        return Err(SlumlordError::NotAdmin.into());
+    }
+    pool_config.admin = *new_admin;
+    Ok(())
+}
+
+/// Enables or disables allowlist mode in the slumlord account data if `admin` is its admin.
+///
+/// Does not check that `admin` signed
+pub fn set_allowlist_enabled(
+    slumlord_acc_data: &mut [u8],
+    admin: &Pubkey,
+    enabled: bool,
+) -> Result<(), ProgramError> {
+    verify_admin(slumlord_acc_data, admin)?;
+    let pool_config = try_pool_config_mut(slumlord_acc_data)?.ok_or(SlumlordError::NoPoolConfig)?;
+    pool_config.allowlist_enabled = u8::from(enabled);
+    Ok(())
+}
+
+/// Sets the [`PoolLimits`] of the slumlord account data if `admin` is its admin.
+///
+/// Does not check that `admin` signed
+pub fn set_pool_limits(
+    slumlord_acc_data: &mut [u8],
+    admin: &Pubkey,
+    limits: PoolLimits,
+) -> Result<(), ProgramError> {
+    verify_admin(slumlord_acc_data, admin)?;
+    *try_pool_limits_mut(slumlord_acc_data)?.ok_or(SlumlordError::NoPoolConfig)? = limits;
+    Ok(())
+}
+
+/// Checks that the account with `entry_owner` and `entry_data` is the
+/// allowlist entry created by `AddBorrower` for `borrower`.
+///
+/// Only `AddBorrower` creates slumlord-owned accounts of [`BORROWER_ENTRY_LEN`],
+/// so the entry's address does not need to be derived
+pub fn verify_borrower_entry(
+    borrower: &Pubkey,
+    entry_owner: &Pubkey,
+    entry_data: &[u8],
+) -> Result<(), SlumlordError> {
+    if *entry_owner != program::ID {
+        return Err(SlumlordError::BorrowerNotAllowed);
+    }
+    match try_borrower_entry(entry_data) {
+        Ok(entry) if entry.borrower == *borrower => Ok(()),
+        _ => Err(SlumlordError::BorrowerNotAllowed),
+    }
+}
+
+/// In allowlist mode, checks that `borrower_accounts` starts with a signing borrower
+/// followed by its allowlist entry, returning the accounts after them.
+/// `verify_entry` is called with the borrower's pubkey and the entry account,
+/// see [`verify_borrower_entry`].
+///
+/// Returns `borrower_accounts` as-is if the allowlist is disabled
+pub fn verify_allowed_borrower<'a, A: InstructionAccount>(
+    slumlord_acc_data: &[u8],
+    borrower_accounts: &'a [A],
+    verify_entry: impl FnOnce(&Pubkey, &A) -> Result<(), ProgramError>,
+) -> Result<&'a [A], ProgramError> {
+    if !is_allowlist_enabled(slumlord_acc_data)? {
+        return Ok(borrower_accounts);
+    }
+    let (borrower, borrower_entry, rest) = match borrower_accounts {
+        [borrower, borrower_entry, rest @ ..] => (borrower, borrower_entry, rest),
+        _ => return Err(SlumlordError::BorrowerNotAllowed.into()),
+    };
+    if !borrower.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    verify_entry(borrower.key(), borrower_entry)?;
+    Ok(rest)
+}
+
+pub fn try_borrower_entry(borrower_entry_data: &[u8]) -> Result<&BorrowerEntry, ProgramError> {
+    try_from_bytes(borrower_entry_data).map_err(|_e| ProgramError::InvalidAccountData)
+}
+
+pub fn try_borrower_entry_mut(
+    borrower_entry_data: &mut [u8],
+) -> Result<&mut BorrowerEntry, ProgramError> {
+    try_from_bytes_mut(borrower_entry_data).map_err(|_e| ProgramError::InvalidAccountData)
+}
+
+/// An account passed to a slumlord instruction, for the checks shared by
+/// the program's [`AccountInfo`]s and the simulator's [`AccountMeta`]s
+pub trait InstructionAccount {
+    fn key(&self) -> &Pubkey;
+
+    fn is_signer(&self) -> bool;
+
+    fn is_writable(&self) -> bool;
+}
+
+impl InstructionAccount for AccountInfo<'_> {
+    fn key(&self) -> &Pubkey {
+        self.key
+    }
+
+    fn is_signer(&self) -> bool {
+        self.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.is_writable
+    }
+}
+
+impl InstructionAccount for AccountMeta {
+    fn key(&self) -> &Pubkey {
+        &self.pubkey
+    }
+
+    fn is_signer(&self) -> bool {
+        self.is_signer
+    }
+
+    fn is_writable(&self) -> bool {
+        self.is_writable
+    }
 }
 
 /// Other programs can make use of this trait for onchain calculations
@@ -92,10 +1116,22 @@ pub trait LoanActiveSlumlordAccount {
     fn curr_loan_lamports_outstanding(&self) -> Result<u64, ProgramError>;
 
     /// Returns the original lamports owned by this slumlord account
-    /// before the flash loan was disbursed, recorded in the account data
+    /// before the flash loan was disbursed, recorded in the account data.
+    ///
+    /// Errors with [`SlumlordError::NoActiveLoan`] if no flash loan is active.
     ///
     /// Does not check identity of slumlord account
     fn old_lamports(&self) -> Result<u64, ProgramError>;
+
+    /// Returns the `dst` and top-level borrow instruction index
+    /// of the current flash loan, recorded in the account data.
+    ///
+    /// Errors with [`SlumlordError::NoActiveLoan`] if no flash loan is active or
+    /// [`SlumlordError::NoLoanContext`] if the account has not been migrated
+    /// to a version that records it.
+    ///
+    /// Does not check identity of slumlord account
+    fn loan_context(&self) -> Result<LoanContext, ProgramError>;
 }
 
 impl<D: ReadonlyAccountData + ReadonlyAccountLamports> LoanActiveSlumlordAccount for D {
@@ -106,7 +1142,90 @@ impl<D: ReadonlyAccountData + ReadonlyAccountLamports> LoanActiveSlumlordAccount
 
     fn old_lamports(&self) -> Result<u64, ProgramError> {
         let data = &self.data();
+        if !is_loan_active(data)? {
+            return Err(SlumlordError::NoActiveLoan.into());
+        }
         let slumlord = try_slumlord(data)?;
         Ok(slumlord.old_lamports)
     }
+
+    fn loan_context(&self) -> Result<LoanContext, ProgramError> {
+        let data = &self.data();
+        if !is_loan_active(data)? {
+            return Err(SlumlordError::NoActiveLoan.into());
+        }
+        let loan_context = try_loan_context(data)?.ok_or(SlumlordError::NoLoanContext)?;
+        Ok(*loan_context)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RepayDirectAccounts<'me, 'info> {
+    /// The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+
+    /// The account paying the outstanding flash loan.
+    /// Must be owned by the program calling [`repay_direct`]
+    pub src: &'me AccountInfo<'info>,
+}
+
+/// Repays the current flash loan by directly debiting the outstanding loan amount
+/// from `src` and crediting it to the slumlord account, returning the amount repaid.
+///
+/// For programs whose funds sit in accounts they own, which cannot be used as
+/// `Repay`'s `src` since that transfers via the system program.
+/// This is not a slumlord instruction because only the owner of `src` may debit it:
+/// the owning program must call this directly instead of CPI-ing slumlord.
+pub fn repay_direct(accounts: RepayDirectAccounts) -> Result<u64, ProgramError> {
+    if *accounts.slumlord.key != program::SLUMLORD_ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let lamports = accounts.slumlord.curr_loan_lamports_outstanding()?;
+    transfer_direct_increment(
+        TransferAccounts {
+            from: accounts.src,
+            to: accounts.slumlord,
+        },
+        lamports,
+    )?;
+    Ok(lamports)
+}
+
+/// [`borrow_invoke`], returning the lamports lent to `dst` from the
+/// return data set by slumlord
+pub fn borrow_invoke_with_return(accounts: BorrowAccounts) -> Result<u64, ProgramError> {
+    borrow_invoke(accounts)?;
+    let data = get_slumlord_return_data()?;
+    let lent_lamports = data
+        .try_into()
+        .map_err(|_e| ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(lent_lamports))
+}
+
+/// [`check_repaid_invoke`], returning the ended flash loan's [`CheckRepaidReturn`]
+/// from the return data set by slumlord, `None` if no flash loan was active
+pub fn check_repaid_invoke_with_return(
+    accounts: CheckRepaidAccounts,
+) -> Result<Option<CheckRepaidReturn>, ProgramError> {
+    check_repaid_invoke(accounts)?;
+    let data = get_slumlord_return_data()?;
+    if data.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(CheckRepaidReturn::try_from_slice(&data)?))
+}
+
+/// Returns the current return data, erroring if it was not set by the slumlord program.
+///
+/// Empty return data is returned as-is since it does not record the program that set it
+fn get_slumlord_return_data() -> Result<Vec<u8>, ProgramError> {
+    match get_return_data() {
+        Some((program_id, data)) if !data.is_empty() => {
+            if program_id != program::ID {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Ok(data)
+        }
+        _ => Ok(Vec::new()),
+    }
 }
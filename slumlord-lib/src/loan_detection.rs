@@ -0,0 +1,142 @@
+//! Detection of active slumlord loans for programs that price off an account's SOL balance
+//! and want to know if it is currently inflated by a flash loan.
+
+use std::cmp::Ordering;
+
+use slumlord_interface::{
+    LoanContext, BORROW_IX_DISCM, BORROW_SPLIT_IX_ACCOUNTS_LEN, BORROW_SPLIT_IX_DISCM,
+    BORROW_WITH_MIN_IX_DISCM, FLASH_LOAN_WITH_CALLBACK_IX_DISCM,
+};
+use solana_program::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    sysvar::instructions::load_current_index_checked,
+};
+
+use crate::{
+    is_loan_active, program::SLUMLORD_ID, scan_instructions_sysvar, try_loan_context,
+    ScannedSlumlordIx, SPLIT_LOAN_DST,
+};
+
+/// Index of `dst` in `Borrow` and `BorrowWithMin`, `receiver` in `FlashLoanWithCallback`
+const LOAN_DST_ACCOUNT_INDEX: usize = 1;
+
+/// Slumlord loans to an account detected by [`detect_loan`] from the instructions sysvar
+/// and the slumlord account.
+///
+/// Only top-level instructions are recorded in the instructions sysvar, so:
+/// - loans made by other programs CPI-ing slumlord are not detected from it.
+///   CPI-ed `Borrow`s must still be followed by a top-level `CheckRepaid`, see
+///   [`Self::succeeding_check_repaid`], while CPI-ed `FlashLoanWithCallback`s are
+///   only visible in the slumlord account, see [`Self::recorded_loan_to_account`].
+/// - the account's balance may have been moved elsewhere since it was lent to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoanDetection {
+    /// Index of the top-level `Borrow`, `BorrowWithMin` or `BorrowSplit` instruction
+    /// lending to the account that precedes the current instruction
+    /// without a top-level `CheckRepaid` in between
+    pub borrow_ix_index: Option<u16>,
+
+    /// Whether the current top-level instruction is a `FlashLoanWithCallback`
+    /// lending to the account, i.e. this is being called during its callback
+    pub in_flash_loan_callback: bool,
+
+    /// Whether a top-level `CheckRepaid` instruction follows the current instruction.
+    ///
+    /// If true without a detected loan, a loan may have been made via CPI
+    /// by a preceding instruction
+    pub succeeding_check_repaid: bool,
+
+    /// Whether the slumlord account records an active loan that may have lent to the account:
+    /// its [`LoanContext`] `dst` is the account or [`SPLIT_LOAN_DST`], or it has not been
+    /// migrated to record [`LoanContext`].
+    ///
+    /// Includes loans made via CPI
+    pub recorded_loan_to_account: bool,
+}
+
+impl LoanDetection {
+    /// Returns true if a top-level slumlord loan to the account is active
+    pub fn is_detected(&self) -> bool {
+        self.borrow_ix_index.is_some() || self.in_flash_loan_callback
+    }
+
+    /// Returns true if a slumlord loan to the account may be active,
+    /// conservatively treating any succeeding `CheckRepaid` as a possible CPI-ed loan
+    pub fn may_be_active(&self) -> bool {
+        self.is_detected() || self.succeeding_check_repaid || self.recorded_loan_to_account
+    }
+}
+
+/// Detects slumlord loans to `account` that are active at the current instruction
+/// from the instructions sysvar and the slumlord account.
+///
+/// Errors with [`ProgramError::InvalidArgument`] if `slumlord` is not the slumlord account.
+///
+/// See [`LoanDetection`] for caveats
+pub fn detect_loan(
+    instructions: &AccountInfo,
+    slumlord: &AccountInfo,
+    account: &Pubkey,
+) -> Result<LoanDetection, ProgramError> {
+    if *slumlord.key != SLUMLORD_ID {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let recorded_loan_to_account = {
+        let data = slumlord.try_borrow_data()?;
+        is_loan_active(&data)?
+            && match try_loan_context(&data)? {
+                Some(LoanContext { dst, .. }) => dst == account || *dst == SPLIT_LOAN_DST,
+                None => true,
+            }
+    };
+    let curr_ix_idx = load_current_index_checked(instructions)?;
+    scan_instructions_sysvar(instructions, |scanner| {
+        let mut res = LoanDetection {
+            recorded_loan_to_account,
+            ..Default::default()
+        };
+        for ix in scanner {
+            match ix.index.cmp(&curr_ix_idx) {
+                Ordering::Less => {
+                    if ix.is_check_repaid() {
+                        res.borrow_ix_index = None;
+                    } else if lends_to(&ix, account) {
+                        res.borrow_ix_index = Some(ix.index);
+                    }
+                }
+                Ordering::Equal => {
+                    res.in_flash_loan_callback = ix.discm()
+                        == Some(FLASH_LOAN_WITH_CALLBACK_IX_DISCM)
+                        && ix.key(LOAN_DST_ACCOUNT_INDEX) == Some(*account);
+                }
+                Ordering::Greater => {
+                    if ix.is_check_repaid() {
+                        res.succeeding_check_repaid = true;
+                        break;
+                    }
+                }
+            }
+        }
+        res
+    })
+}
+
+/// Returns true if `ix` is a slumlord borrow instruction that lends to `account`
+fn lends_to(ix: &ScannedSlumlordIx, account: &Pubkey) -> bool {
+    match ix.discm() {
+        Some(BORROW_IX_DISCM | BORROW_WITH_MIN_IX_DISCM) => {
+            ix.key(LOAN_DST_ACCOUNT_INDEX) == Some(*account)
+        }
+        Some(BORROW_SPLIT_IX_DISCM) => {
+            // amounts.len() is borsh-serialized as u32 right after the discriminant
+            let dsts_len = match ix.data.get(1..5).and_then(|len| len.try_into().ok()) {
+                Some(len) => u32::from_le_bytes(len),
+                None => return false,
+            };
+            (0..dsts_len)
+                .map_while(|i| ix.key(BORROW_SPLIT_IX_ACCOUNTS_LEN + i as usize))
+                .any(|dst| dst == *account)
+        }
+        _ => false,
+    }
+}
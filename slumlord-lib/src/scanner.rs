@@ -0,0 +1,231 @@
+//! Zero-alloc iteration over a transaction's top-level slumlord instructions,
+//! from the instructions sysvar on-chain or from a [`Message`] off-chain.
+
+use slumlord_interface::{SlumlordProgramIx, CHECK_REPAID_IX_DISCM};
+use solana_program::{
+    account_info::AccountInfo, instruction::AccountMeta, message::Message,
+    program_error::ProgramError, pubkey::Pubkey, sysvar,
+};
+
+use crate::program;
+
+/// Serialized `(meta_byte, pubkey)` length of an account in the instructions sysvar
+const SYSVAR_ACCOUNT_META_LEN: usize = 33;
+
+const SYSVAR_IS_SIGNER_FLAG: u8 = 0b01;
+
+const SYSVAR_IS_WRITABLE_FLAG: u8 = 0b10;
+
+const CHECK_REPAID_SLUMLORD_ACCOUNT_INDEX: usize = 0;
+
+/// Iterator over the top-level slumlord instructions of a transaction.
+///
+/// Malformed instructions sysvar data ends iteration early.
+/// The instructions sysvar provided by the runtime is always well-formed.
+#[derive(Clone, Copy, Debug)]
+pub struct SlumlordIxScanner<'a> {
+    src: IxSource<'a>,
+    next_index: u16,
+    len: u16,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum IxSource<'a> {
+    Sysvar(&'a [u8]),
+    Message(&'a Message),
+}
+
+impl<'a> SlumlordIxScanner<'a> {
+    /// `data` is the instructions sysvar account's data.
+    ///
+    /// Does not check identity of the instructions sysvar account
+    pub fn from_instructions_sysvar(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let len = read_u16(data, 0).ok_or(ProgramError::InvalidAccountData)?;
+        Ok(Self {
+            src: IxSource::Sysvar(data),
+            next_index: 0,
+            len,
+        })
+    }
+
+    pub fn from_message(message: &'a Message) -> Self {
+        Self {
+            src: IxSource::Message(message),
+            next_index: 0,
+            len: u16::try_from(message.instructions.len()).unwrap_or(u16::MAX),
+        }
+    }
+
+    /// Skips all instructions before the one at `index`
+    pub fn starting_at(mut self, index: u16) -> Self {
+        self.next_index = self.next_index.max(index);
+        self
+    }
+
+    /// Returns `Some(None)` if the instruction at `index` is not a slumlord instruction,
+    /// `None` if the data is malformed
+    fn slumlord_ix_at(&self, index: u16) -> Option<Option<ScannedSlumlordIx<'a>>> {
+        match self.src {
+            IxSource::Sysvar(data) => sysvar_slumlord_ix_at(data, index),
+            IxSource::Message(message) => {
+                let ix = message.instructions.get(usize::from(index))?;
+                if message.account_keys.get(usize::from(ix.program_id_index)) != Some(&program::ID)
+                {
+                    return Some(None);
+                }
+                Some(Some(ScannedSlumlordIx {
+                    index,
+                    data: &ix.data,
+                    accounts: IxAccounts::Message {
+                        message,
+                        indices: &ix.accounts,
+                    },
+                }))
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for SlumlordIxScanner<'a> {
+    type Item = ScannedSlumlordIx<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next_index < self.len {
+            let index = self.next_index;
+            self.next_index += 1;
+            match self.slumlord_ix_at(index) {
+                Some(Some(ix)) => return Some(ix),
+                Some(None) => continue,
+                None => self.next_index = self.len,
+            }
+        }
+        None
+    }
+}
+
+/// Calls `f` with a [`SlumlordIxScanner`] over the instructions sysvar account's data.
+///
+/// Errors with [`ProgramError::UnsupportedSysvar`] if `instructions` is not the instructions sysvar
+pub fn scan_instructions_sysvar<T>(
+    instructions: &AccountInfo,
+    f: impl FnOnce(SlumlordIxScanner) -> T,
+) -> Result<T, ProgramError> {
+    if *instructions.key != sysvar::instructions::ID {
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+    let data = instructions.try_borrow_data()?;
+    Ok(f(SlumlordIxScanner::from_instructions_sysvar(&data)?))
+}
+
+/// See [`SlumlordIxScanner::slumlord_ix_at`]
+fn sysvar_slumlord_ix_at(data: &[u8], index: u16) -> Option<Option<ScannedSlumlordIx<'_>>> {
+    let offset_offset = usize::from(index).checked_mul(2)?.checked_add(2)?;
+    let ix_offset = usize::from(read_u16(data, offset_offset)?);
+    let accounts_len = usize::from(read_u16(data, ix_offset)?);
+    let accounts_offset = ix_offset.checked_add(2)?;
+    let program_id_offset =
+        accounts_offset.checked_add(accounts_len.checked_mul(SYSVAR_ACCOUNT_META_LEN)?)?;
+    let data_len_offset = program_id_offset.checked_add(32)?;
+    if data.get(program_id_offset..data_len_offset)? != program::ID.as_ref() {
+        return Some(None);
+    }
+    let data_len = usize::from(read_u16(data, data_len_offset)?);
+    let ix_data_offset = data_len_offset.checked_add(2)?;
+    Some(Some(ScannedSlumlordIx {
+        index,
+        data: data.get(ix_data_offset..ix_data_offset.checked_add(data_len)?)?,
+        accounts: IxAccounts::Sysvar(data.get(accounts_offset..program_id_offset)?),
+    }))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// A top-level slumlord instruction yielded by [`SlumlordIxScanner`]
+#[derive(Clone, Copy, Debug)]
+pub struct ScannedSlumlordIx<'a> {
+    /// Index of the instruction in the transaction
+    pub index: u16,
+
+    /// The instruction's data
+    pub data: &'a [u8],
+
+    accounts: IxAccounts<'a>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum IxAccounts<'a> {
+    /// Serialized `(meta_byte, pubkey)`s
+    Sysvar(&'a [u8]),
+    Message {
+        message: &'a Message,
+        indices: &'a [u8],
+    },
+}
+
+impl<'a> ScannedSlumlordIx<'a> {
+    pub fn discm(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Deserializes the instruction.
+    ///
+    /// Allocates for instructions with variable-length args
+    pub fn ix(&self) -> std::io::Result<SlumlordProgramIx> {
+        SlumlordProgramIx::deserialize(self.data)
+    }
+
+    pub fn is_check_repaid(&self) -> bool {
+        self.discm() == Some(CHECK_REPAID_IX_DISCM)
+    }
+
+    /// Returns true if this is a CheckRepaid instruction
+    /// whose `slumlord` account is `slumlord`
+    pub fn is_check_repaid_for(&self, slumlord: &Pubkey) -> bool {
+        self.is_check_repaid() && self.key(CHECK_REPAID_SLUMLORD_ACCOUNT_INDEX) == Some(*slumlord)
+    }
+
+    pub fn accounts_len(&self) -> usize {
+        match self.accounts {
+            IxAccounts::Sysvar(metas) => metas.len() / SYSVAR_ACCOUNT_META_LEN,
+            IxAccounts::Message { indices, .. } => indices.len(),
+        }
+    }
+
+    /// Returns the instruction's `i`-th account
+    pub fn account(&self, i: usize) -> Option<AccountMeta> {
+        match self.accounts {
+            IxAccounts::Sysvar(metas) => {
+                let start = i.checked_mul(SYSVAR_ACCOUNT_META_LEN)?;
+                let meta = metas.get(start..start.checked_add(SYSVAR_ACCOUNT_META_LEN)?)?;
+                let (meta_byte, pubkey) = meta.split_first()?;
+                Some(AccountMeta {
+                    pubkey: Pubkey::new_from_array(pubkey.try_into().ok()?),
+                    is_signer: meta_byte & SYSVAR_IS_SIGNER_FLAG != 0,
+                    is_writable: meta_byte & SYSVAR_IS_WRITABLE_FLAG != 0,
+                })
+            }
+            IxAccounts::Message { message, indices } => {
+                let key_index = usize::from(*indices.get(i)?);
+                Some(AccountMeta {
+                    pubkey: *message.account_keys.get(key_index)?,
+                    is_signer: message.is_signer(key_index),
+                    is_writable: message.is_writable(key_index),
+                })
+            }
+        }
+    }
+
+    /// Returns the instruction's `i`-th account's pubkey
+    pub fn key(&self, i: usize) -> Option<Pubkey> {
+        self.account(i).map(|meta| meta.pubkey)
+    }
+
+    /// Iterates over the instruction's account pubkeys
+    pub fn keys(&self) -> impl Iterator<Item = Pubkey> + 'a {
+        let ix = *self;
+        (0..self.accounts_len()).filter_map(move |i| ix.key(i))
+    }
+}
@@ -0,0 +1,1005 @@
+//! Pure rust simulation of transactions containing slumlord instructions,
+//! for predicting their lamport changes and errors without a validator.
+//!
+//! Supports every slumlord instruction and the system program's
+//! `CreateAccount`, `Assign`, `Allocate` and `Transfer` instructions.
+//! Other programs, such as `FlashLoanWithCallback` receivers,
+//! must be added with [`Simulator::add_program`].
+//! Does not charge transaction fees, check rent-exemption or record return data.
+//! `Migrate` and `AddBorrower` compute rent-exemption at the default rent.
+//!
+//! The slumlord account's state transitions are the same `slumlord_lib`
+//! functions the program runs.
+
+use std::collections::HashMap;
+
+use slumlord_interface::{
+    on_flash_loan_ix, AddBorrowerKeys, BorrowKeys, BorrowSplitIxArgs, BorrowSplitKeys,
+    BorrowWithMinKeys, CheckRepaidKeys, FlashLoanWithCallbackIxArgs, FlashLoanWithCallbackKeys,
+    InitKeys, MigrateKeys, OnFlashLoanIxArgs, OnFlashLoanKeys, PoolLimits, QuoteLoanKeys,
+    RemoveBorrowerKeys, RepayKeys, RepayWithMaxKeys, SetAdminKeys, SetAllowlistKeys,
+    SetPoolLimitsKeys, SlumlordError, SlumlordProgramIx, ADD_BORROWER_IX_ACCOUNTS_LEN,
+    BORROW_IX_ACCOUNTS_LEN, BORROW_SPLIT_IX_ACCOUNTS_LEN, BORROW_WITH_MIN_IX_ACCOUNTS_LEN,
+    CHECK_REPAID_IX_ACCOUNTS_LEN, FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN, INIT_IX_ACCOUNTS_LEN,
+    MIGRATE_IX_ACCOUNTS_LEN, QUOTE_LOAN_IX_ACCOUNTS_LEN, REMOVE_BORROWER_IX_ACCOUNTS_LEN,
+    REPAY_IX_ACCOUNTS_LEN, REPAY_WITH_MAX_IX_ACCOUNTS_LEN, SET_ADMIN_IX_ACCOUNTS_LEN,
+    SET_ALLOWLIST_IX_ACCOUNTS_LEN, SET_POOL_LIMITS_IX_ACCOUNTS_LEN,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProcessInstruction,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    message::Message,
+    program_error::ProgramError,
+    program_utils::limited_deserialize,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::{SystemError, SystemInstruction, MAX_PERMITTED_DATA_LENGTH},
+    system_program,
+};
+use solana_sdk::account::Account;
+
+use crate::{
+    check_loan_repaid, clear_versioned_loan, is_loan_active, migrate_slumlord_data, program,
+    program_upgrade_authority, set_admin, set_allowlist_enabled, set_pool_limits,
+    slumlord_account_version, split_loan_dst, split_loan_lamports, start_loan,
+    try_borrower_entry_mut, verify_admin, verify_borrower_entry, verify_loanable,
+    verify_no_active_loan, verify_not_loan_to_self, verify_slumlord_initialized,
+    verify_split_loan_dsts, AddBorrowerFreeArgs, BorrowFreeArgs, FlashLoanWithCallbackFreeArgs,
+    LoanActiveSlumlordAccount, MigrateFreeArgs, RemoveBorrowerFreeArgs, RepayFreeArgs,
+    SetAdminFreeArgs, SetAllowlistFreeArgs, SetPoolLimitsFreeArgs, SlumlordIxScanner,
+    BORROWER_ENTRY_LEN, BORROW_SPLIT_KEYS, CHECK_REPAID_KEYS, INIT_KEYS,
+    LEGACY_SLUMLORD_ACCOUNT_LEN, QUOTE_LOAN_KEYS, SLUMLORD_ACCOUNT_LEN, SLUMLORD_ACCOUNT_VERSION,
+    UNKNOWN_BORROW_IX_INDEX,
+};
+
+/// PACKET_DATA_SIZE, the limit the system program deserializes its instructions with
+const SYSTEM_IX_DESERIALIZE_LIMIT: u64 = 1232;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+}
+
+impl From<Account> for SimAccount {
+    fn from(
+        Account {
+            lamports,
+            data,
+            owner,
+            ..
+        }: Account,
+    ) -> Self {
+        Self {
+            lamports,
+            data,
+            owner,
+        }
+    }
+}
+
+type Accounts = HashMap<Pubkey, SimAccount>;
+
+type Programs = HashMap<Pubkey, ProcessInstruction>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulationError {
+    /// The instruction at the given index failed
+    InstructionError(u8, InstructionError),
+
+    /// The instruction at the given index can't be simulated
+    UnsupportedInstruction(u8),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimulationResult {
+    /// Post-transaction state of the message's accounts.
+    /// Accounts left with 0 lamports are removed
+    pub accounts: HashMap<Pubkey, SimAccount>,
+
+    /// Post-transaction lamports - pre-transaction lamports
+    /// of each of the message's accounts
+    pub lamport_deltas: HashMap<Pubkey, i128>,
+}
+
+/// In-memory account state transactions are simulated against.
+///
+/// Accounts not added are treated as empty system accounts
+#[derive(Clone, Default)]
+pub struct Simulator {
+    accounts: Accounts,
+    programs: Programs,
+}
+
+impl std::fmt::Debug for Simulator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Simulator")
+            .field("accounts", &self.accounts)
+            .field("programs", &self.programs.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl Simulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_account(&mut self, pubkey: Pubkey, account: SimAccount) {
+        self.accounts.insert(pubkey, account);
+    }
+
+    pub fn account(&self, pubkey: &Pubkey) -> Option<&SimAccount> {
+        self.accounts.get(pubkey)
+    }
+
+    /// Executes instructions of and CPIs into `program_id` with `process_instruction`.
+    ///
+    /// `process_instruction` must not make CPIs or resize accounts,
+    /// which are not available outside the runtime
+    pub fn add_program(&mut self, program_id: Pubkey, process_instruction: ProcessInstruction) {
+        self.programs.insert(program_id, process_instruction);
+    }
+
+    /// Simulates `message` as a single transaction without modifying
+    /// the simulator's accounts
+    pub fn simulate_message(&self, message: &Message) -> Result<SimulationResult, SimulationError> {
+        let ixs = decompile_instructions(message);
+        let scanner = SlumlordIxScanner::from_message(message);
+        let mut accounts = self.accounts.clone();
+        for (curr_ix_idx, ix) in ixs.iter().enumerate() {
+            let ix_idx: u8 = curr_ix_idx.try_into().unwrap();
+            execute_instruction(&mut accounts, &self.programs, ix, scanner, curr_ix_idx).map_err(
+                |e| match e {
+                    ExecutionError::Unsupported => SimulationError::UnsupportedInstruction(ix_idx),
+                    ExecutionError::Instruction(err) => {
+                        SimulationError::InstructionError(ix_idx, err)
+                    }
+                },
+            )?;
+        }
+
+        let mut res = SimulationResult::default();
+        for pubkey in message.account_keys.iter() {
+            let pre_lamports = self.accounts.get(pubkey).map_or(0, |a| a.lamports);
+            let post = accounts.remove(pubkey).unwrap_or_default();
+            res.lamport_deltas.insert(
+                *pubkey,
+                i128::from(post.lamports) - i128::from(pre_lamports),
+            );
+            if post.lamports != 0 {
+                res.accounts.insert(*pubkey, post);
+            }
+        }
+        Ok(res)
+    }
+
+    /// Simulates `message`, updating the simulator's accounts if it succeeds
+    pub fn process_message(
+        &mut self,
+        message: &Message,
+    ) -> Result<SimulationResult, SimulationError> {
+        let res = self.simulate_message(message)?;
+        for pubkey in message.account_keys.iter() {
+            match res.accounts.get(pubkey) {
+                Some(account) => self.accounts.insert(*pubkey, account.clone()),
+                None => self.accounts.remove(pubkey),
+            };
+        }
+        Ok(res)
+    }
+}
+
+fn decompile_instructions(message: &Message) -> Vec<Instruction> {
+    message
+        .instructions
+        .iter()
+        .map(|ix| Instruction {
+            program_id: message.account_keys[usize::from(ix.program_id_index)],
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|i| {
+                    let i = usize::from(*i);
+                    AccountMeta {
+                        pubkey: message.account_keys[i],
+                        is_signer: message.is_signer(i),
+                        is_writable: message.is_writable(i),
+                    }
+                })
+                .collect(),
+            data: ix.data.clone(),
+        })
+        .collect()
+}
+
+enum ExecutionError {
+    Unsupported,
+    Instruction(InstructionError),
+}
+
+impl From<InstructionError> for ExecutionError {
+    fn from(e: InstructionError) -> Self {
+        Self::Instruction(e)
+    }
+}
+
+impl From<ProgramError> for ExecutionError {
+    fn from(e: ProgramError) -> Self {
+        Self::Instruction(u64::from(e).into())
+    }
+}
+
+impl From<SlumlordError> for ExecutionError {
+    fn from(e: SlumlordError) -> Self {
+        ProgramError::from(e).into()
+    }
+}
+
+impl From<SystemError> for ExecutionError {
+    fn from(e: SystemError) -> Self {
+        Self::Instruction(e.into())
+    }
+}
+
+type ExecutionResult = Result<(), ExecutionError>;
+
+/// Executes `ix`, the top-level instruction at `curr_ix_idx` of the message `ixs` scans
+fn execute_instruction(
+    accounts: &mut Accounts,
+    programs: &Programs,
+    ix: &Instruction,
+    ixs: SlumlordIxScanner,
+    curr_ix_idx: usize,
+) -> ExecutionResult {
+    let mut pre = snapshot(accounts, &ix.accounts);
+    if ix.program_id == system_program::ID {
+        process_system_ix(accounts, ix)?;
+    } else if ix.program_id == program::ID {
+        process_slumlord_ix(accounts, &mut pre, programs, ix, ixs, curr_ix_idx)?;
+    } else {
+        process_program_ix(accounts, programs, ix)?;
+    }
+    verify_account_changes(&ix.program_id, &ix.accounts, &pre, accounts)
+}
+
+/// Executes the system program instruction `ix` as a CPI made by the caller
+/// whose pre-instruction account states are `caller_pre`
+fn invoke(accounts: &mut Accounts, caller_pre: &mut Accounts, ix: &Instruction) -> ExecutionResult {
+    invoke_with(accounts, caller_pre, ix, process_system_ix)
+}
+
+/// Executes `ix` with `process` as a CPI made by the caller
+/// whose pre-instruction account states are `caller_pre`
+fn invoke_with(
+    accounts: &mut Accounts,
+    caller_pre: &mut Accounts,
+    ix: &Instruction,
+    process: impl FnOnce(&mut Accounts, &Instruction) -> ExecutionResult,
+) -> ExecutionResult {
+    let pre = snapshot(accounts, &ix.accounts);
+    process(accounts, ix)?;
+    verify_account_changes(&ix.program_id, &ix.accounts, &pre, accounts)?;
+    // callee's changes are not the caller's
+    caller_pre.extend(snapshot(accounts, &ix.accounts));
+    Ok(())
+}
+
+fn snapshot(accounts: &mut Accounts, metas: &[AccountMeta]) -> Accounts {
+    metas
+        .iter()
+        .map(|m| (m.pubkey, accounts.entry(m.pubkey).or_default().clone()))
+        .collect()
+}
+
+fn acc<'a>(accounts: &'a mut Accounts, pubkey: &Pubkey) -> &'a mut SimAccount {
+    accounts.entry(*pubkey).or_default()
+}
+
+/// Mirrors the runtime's checks on the changes an instruction made to its accounts
+fn verify_account_changes(
+    program_id: &Pubkey,
+    metas: &[AccountMeta],
+    pre: &Accounts,
+    post: &Accounts,
+) -> ExecutionResult {
+    for meta in metas {
+        let pre = &pre[&meta.pubkey];
+        let post = &post[&meta.pubkey];
+        let is_owned = pre.owner == *program_id;
+        if post.lamports != pre.lamports {
+            if !is_owned && post.lamports < pre.lamports {
+                return Err(InstructionError::ExternalAccountLamportSpend.into());
+            }
+            if !meta.is_writable {
+                return Err(InstructionError::ReadonlyLamportChange.into());
+            }
+        }
+        if post.data != pre.data {
+            if post.data.len() != pre.data.len() && !is_owned {
+                return Err(InstructionError::AccountDataSizeChanged.into());
+            }
+            if !meta.is_writable {
+                return Err(InstructionError::ReadonlyDataModified.into());
+            }
+            if !is_owned {
+                return Err(InstructionError::ExternalAccountDataModified.into());
+            }
+        }
+        if post.owner != pre.owner
+            && (!is_owned || !meta.is_writable || post.data.iter().any(|b| *b != 0))
+        {
+            return Err(InstructionError::ModifiedProgramId.into());
+        }
+    }
+    Ok(())
+}
+
+fn instruction_meta(ix: &Instruction, index: usize) -> Result<&AccountMeta, ExecutionError> {
+    ix.accounts
+        .get(index)
+        .ok_or_else(|| InstructionError::NotEnoughAccountKeys.into())
+}
+
+fn process_system_ix(accounts: &mut Accounts, ix: &Instruction) -> ExecutionResult {
+    let system_ix: SystemInstruction = limited_deserialize(&ix.data, SYSTEM_IX_DESERIALIZE_LIMIT)?;
+    match system_ix {
+        SystemInstruction::CreateAccount {
+            lamports,
+            space,
+            owner,
+        } => {
+            let from = instruction_meta(ix, 0)?;
+            let to = instruction_meta(ix, 1)?;
+            if acc(accounts, &to.pubkey).lamports > 0 {
+                return Err(SystemError::AccountAlreadyInUse.into());
+            }
+            system_allocate(accounts, to, space)?;
+            system_assign(accounts, to, owner)?;
+            system_transfer(accounts, from, to, lamports)
+        }
+        SystemInstruction::Assign { owner } => {
+            system_assign(accounts, instruction_meta(ix, 0)?, owner)
+        }
+        SystemInstruction::Transfer { lamports } => system_transfer(
+            accounts,
+            instruction_meta(ix, 0)?,
+            instruction_meta(ix, 1)?,
+            lamports,
+        ),
+        SystemInstruction::Allocate { space } => {
+            system_allocate(accounts, instruction_meta(ix, 0)?, space)
+        }
+        _ => Err(ExecutionError::Unsupported),
+    }
+}
+
+fn system_allocate(accounts: &mut Accounts, account: &AccountMeta, space: u64) -> ExecutionResult {
+    if !account.is_signer {
+        return Err(InstructionError::MissingRequiredSignature.into());
+    }
+    let account = acc(accounts, &account.pubkey);
+    if !account.data.is_empty() || account.owner != system_program::ID {
+        return Err(SystemError::AccountAlreadyInUse.into());
+    }
+    if space > MAX_PERMITTED_DATA_LENGTH {
+        return Err(SystemError::InvalidAccountDataLength.into());
+    }
+    account.data = vec![0; space.try_into().unwrap()];
+    Ok(())
+}
+
+fn system_assign(accounts: &mut Accounts, account: &AccountMeta, owner: Pubkey) -> ExecutionResult {
+    let signer = account.is_signer;
+    let account = acc(accounts, &account.pubkey);
+    if account.owner == owner {
+        return Ok(());
+    }
+    if !signer {
+        return Err(InstructionError::MissingRequiredSignature.into());
+    }
+    account.owner = owner;
+    Ok(())
+}
+
+fn system_transfer(
+    accounts: &mut Accounts,
+    from: &AccountMeta,
+    to: &AccountMeta,
+    lamports: u64,
+) -> ExecutionResult {
+    if !from.is_signer {
+        return Err(InstructionError::MissingRequiredSignature.into());
+    }
+    let from = acc(accounts, &from.pubkey);
+    if !from.data.is_empty() {
+        return Err(InstructionError::InvalidArgument.into());
+    }
+    if lamports > from.lamports {
+        return Err(SystemError::ResultWithNegativeLamports.into());
+    }
+    from.lamports -= lamports;
+    let to = acc(accounts, &to.pubkey);
+    to.lamports = to
+        .lamports
+        .checked_add(lamports)
+        .ok_or(InstructionError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Executes `ix` with the processor added for its program with [`Simulator::add_program`]
+fn process_program_ix(
+    accounts: &mut Accounts,
+    programs: &Programs,
+    ix: &Instruction,
+) -> ExecutionResult {
+    let process_instruction = programs
+        .get(&ix.program_id)
+        .ok_or(ExecutionError::Unsupported)?;
+    let mut keys: Vec<Pubkey> = Vec::new();
+    for meta in ix.accounts.iter() {
+        if !keys.contains(&meta.pubkey) {
+            keys.push(meta.pubkey);
+        }
+    }
+    let mut states: Vec<SimAccount> = keys.iter().map(|k| acc(accounts, k).clone()).collect();
+    let res = {
+        let unique_account_infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(states.iter_mut())
+            .map(
+                |(
+                    key,
+                    SimAccount {
+                        lamports,
+                        data,
+                        owner,
+                    },
+                )| {
+                    let mut metas = ix.accounts.iter().filter(|m| m.pubkey == *key);
+                    let is_signer = metas.clone().any(|m| m.is_signer);
+                    let is_writable = metas.any(|m| m.is_writable);
+                    AccountInfo::new(
+                        key,
+                        is_signer,
+                        is_writable,
+                        lamports,
+                        data,
+                        owner,
+                        programs.contains_key(key),
+                        0,
+                    )
+                },
+            )
+            .collect();
+        let account_infos: Vec<AccountInfo> = ix
+            .accounts
+            .iter()
+            .map(|m| {
+                let i = keys.iter().position(|k| *k == m.pubkey).unwrap();
+                unique_account_infos[i].clone()
+            })
+            .collect();
+        process_instruction(&ix.program_id, &account_infos, &ix.data)
+    };
+    res?;
+    accounts.extend(keys.into_iter().zip(states));
+    Ok(())
+}
+
+fn process_slumlord_ix(
+    accounts: &mut Accounts,
+    pre: &mut Accounts,
+    programs: &Programs,
+    ix: &Instruction,
+    ixs: SlumlordIxScanner,
+    curr_ix_idx: usize,
+) -> ExecutionResult {
+    match SlumlordProgramIx::deserialize(&ix.data).map_err(ProgramError::from)? {
+        SlumlordProgramIx::Init => {
+            verify_account_metas::<INIT_IX_ACCOUNTS_LEN, _>(ix, |_: InitKeys| INIT_KEYS)?;
+            // PDA signs for assign CPI
+            let slumlord = AccountMeta::new(program::SLUMLORD_ID, true);
+            let assign_ix = Instruction::new_with_bincode(
+                system_program::ID,
+                &SystemInstruction::Assign { owner: program::ID },
+                vec![slumlord],
+            );
+            invoke(accounts, pre, &assign_ix)
+        }
+        SlumlordProgramIx::Borrow => {
+            let keys = verify_account_metas::<BORROW_IX_ACCOUNTS_LEN, _>(ix, |k: BorrowKeys| {
+                BorrowFreeArgs { dst: k.dst }.resolve()
+            })?;
+            let borrower_accounts = &ix.accounts[BORROW_IX_ACCOUNTS_LEN..];
+            borrow(accounts, ixs, curr_ix_idx, &keys.dst, borrower_accounts, 0)
+        }
+        SlumlordProgramIx::BorrowWithMin(args) => {
+            let keys = verify_account_metas::<BORROW_WITH_MIN_IX_ACCOUNTS_LEN, _>(
+                ix,
+                |k: BorrowWithMinKeys| BorrowFreeArgs { dst: k.dst }.into(),
+            )?;
+            let borrower_accounts = &ix.accounts[BORROW_WITH_MIN_IX_ACCOUNTS_LEN..];
+            borrow(
+                accounts,
+                ixs,
+                curr_ix_idx,
+                &keys.dst,
+                borrower_accounts,
+                args.min_lamports,
+            )
+        }
+        SlumlordProgramIx::BorrowSplit(args) => borrow_split(accounts, ix, ixs, curr_ix_idx, args),
+        SlumlordProgramIx::Repay => {
+            let keys = verify_account_metas::<REPAY_IX_ACCOUNTS_LEN, _>(ix, |k: RepayKeys| {
+                RepayFreeArgs { src: k.src }.resolve()
+            })?;
+            let lamports = curr_loan_lamports_outstanding(accounts)?;
+            repay(accounts, pre, ix, &keys.src, lamports)
+        }
+        SlumlordProgramIx::RepayWithMax(args) => {
+            let keys = verify_account_metas::<REPAY_WITH_MAX_IX_ACCOUNTS_LEN, _>(
+                ix,
+                |k: RepayWithMaxKeys| RepayFreeArgs { src: k.src }.into(),
+            )?;
+            let lamports = curr_loan_lamports_outstanding(accounts)?;
+            if lamports > args.max_lamports {
+                return Err(SlumlordError::RepayExceedsMax.into());
+            }
+            repay(accounts, pre, ix, &keys.src, lamports)
+        }
+        SlumlordProgramIx::CheckRepaid => {
+            verify_account_metas::<CHECK_REPAID_IX_ACCOUNTS_LEN, _>(ix, |_: CheckRepaidKeys| {
+                CHECK_REPAID_KEYS
+            })?;
+            // no flash loan active, successful no-op
+            if !is_loan_active(&acc(accounts, &program::SLUMLORD_ID).data)? {
+                return Ok(());
+            }
+            end_loan(accounts)
+        }
+        SlumlordProgramIx::FlashLoanWithCallback(args) => {
+            flash_loan_with_callback(accounts, pre, programs, ix, args)
+        }
+        // read-only, return data is not simulated
+        SlumlordProgramIx::QuoteLoan => {
+            verify_account_metas::<QUOTE_LOAN_IX_ACCOUNTS_LEN, _>(ix, |_: QuoteLoanKeys| {
+                QUOTE_LOAN_KEYS
+            })?;
+            Ok(())
+        }
+        SlumlordProgramIx::Migrate => {
+            let keys = verify_account_metas::<MIGRATE_IX_ACCOUNTS_LEN, _>(ix, |k: MigrateKeys| {
+                MigrateFreeArgs { payer: k.payer }.resolve()
+            })?;
+            migrate(accounts, pre, &keys.payer)
+        }
+        SlumlordProgramIx::SetAdmin => {
+            let keys =
+                verify_account_metas::<SET_ADMIN_IX_ACCOUNTS_LEN, _>(ix, |k: SetAdminKeys| {
+                    SetAdminFreeArgs {
+                        authority: k.authority,
+                        new_admin: k.new_admin,
+                    }
+                    .resolve()
+                })?;
+            verify_slumlord_initialized(&acc(accounts, &program::SLUMLORD_ID).owner)?;
+            let program_data = acc(accounts, &keys.program_data).clone();
+            set_admin(
+                &mut acc(accounts, &program::SLUMLORD_ID).data,
+                &keys.authority,
+                &keys.new_admin,
+                || program_upgrade_authority(&program_data.owner, &program_data.data),
+            )?;
+            Ok(())
+        }
+        SlumlordProgramIx::SetAllowlist(args) => {
+            let keys = verify_account_metas::<SET_ALLOWLIST_IX_ACCOUNTS_LEN, _>(
+                ix,
+                |k: SetAllowlistKeys| SetAllowlistFreeArgs { admin: k.admin }.resolve(),
+            )?;
+            let slumlord = acc(accounts, &program::SLUMLORD_ID);
+            verify_slumlord_initialized(&slumlord.owner)?;
+            set_allowlist_enabled(&mut slumlord.data, &keys.admin, args.enabled)?;
+            Ok(())
+        }
+        SlumlordProgramIx::AddBorrower => add_borrower(accounts, pre, ix),
+        SlumlordProgramIx::RemoveBorrower => remove_borrower(accounts, ix),
+        SlumlordProgramIx::SetPoolLimits(args) => {
+            let keys = verify_account_metas::<SET_POOL_LIMITS_IX_ACCOUNTS_LEN, _>(
+                ix,
+                |k: SetPoolLimitsKeys| SetPoolLimitsFreeArgs { admin: k.admin }.resolve(),
+            )?;
+            let slumlord = acc(accounts, &program::SLUMLORD_ID);
+            verify_slumlord_initialized(&slumlord.owner)?;
+            set_pool_limits(
+                &mut slumlord.data,
+                &keys.admin,
+                PoolLimits {
+                    max_borrow_lamports: args.max_borrow_lamports,
+                    reserve_lamports: args.reserve_lamports,
+                },
+            )?;
+            Ok(())
+        }
+    }
+}
+
+/// Verifies the instruction's accounts against the expected keys
+/// resolved from the actual keys, returning the expected keys
+fn verify_account_metas<const N: usize, K>(
+    ix: &Instruction,
+    resolve: impl FnOnce(K) -> K,
+) -> Result<K, ExecutionError>
+where
+    K: Copy + From<[Pubkey; N]>,
+    [AccountMeta; N]: From<K>,
+{
+    let actual = ix
+        .accounts
+        .get(..N)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let keys = resolve(K::from(std::array::from_fn(|i| actual[i].pubkey)));
+    let expected = <[AccountMeta; N]>::from(keys);
+    for (actual, expected) in actual.iter().zip(expected.iter()) {
+        if actual.pubkey != expected.pubkey {
+            return Err(ProgramError::InvalidArgument.into());
+        }
+    }
+    for (actual, expected) in actual.iter().zip(expected.iter()) {
+        if expected.is_writable && !actual.is_writable {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+    }
+    for (actual, expected) in actual.iter().zip(expected.iter()) {
+        if expected.is_signer && !actual.is_signer {
+            return Err(ProgramError::MissingRequiredSignature.into());
+        }
+    }
+    Ok(keys)
+}
+
+/// See [`crate::verify_succeeding_check_repaid`]
+fn verify_succeeding_check_repaid(
+    ixs: SlumlordIxScanner,
+    curr_ix_idx: usize,
+) -> Result<u16, ExecutionError> {
+    let curr_ix_idx = u16::try_from(curr_ix_idx).map_err(|_e| ProgramError::InvalidArgument)?;
+    Ok(crate::verify_succeeding_check_repaid(
+        ixs,
+        curr_ix_idx,
+        &program::SLUMLORD_ID,
+    )?)
+}
+
+/// See [`crate::verify_allowed_borrower`]
+fn verify_allowed_borrower<'a>(
+    accounts: &Accounts,
+    borrower_accounts: &'a [AccountMeta],
+) -> Result<&'a [AccountMeta], ExecutionError> {
+    let empty = SimAccount::default();
+    let account = |pubkey: &Pubkey| accounts.get(pubkey).unwrap_or(&empty);
+    Ok(crate::verify_allowed_borrower(
+        &account(&program::SLUMLORD_ID).data,
+        borrower_accounts,
+        |borrower, borrower_entry| {
+            let borrower_entry = account(&borrower_entry.pubkey);
+            verify_borrower_entry(borrower, &borrower_entry.owner, &borrower_entry.data)
+                .map_err(Into::into)
+        },
+    )?)
+}
+
+/// See [`crate::start_loan`]
+fn begin_loan(accounts: &mut Accounts) -> Result<u64, ExecutionError> {
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    verify_no_active_loan(&slumlord.data)?;
+    // unmigrated accounts only have data during a flash loan
+    if slumlord.data.is_empty() {
+        slumlord.data = vec![0; LEGACY_SLUMLORD_ACCOUNT_LEN];
+    }
+    Ok(start_loan(&mut slumlord.data, slumlord.lamports)?)
+}
+
+/// See [`check_loan_repaid`]
+fn end_loan(accounts: &mut Accounts) -> ExecutionResult {
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    check_loan_repaid(&slumlord.data, slumlord.lamports)?;
+    if !clear_versioned_loan(&mut slumlord.data)? {
+        slumlord.data.clear();
+    }
+    Ok(())
+}
+
+fn record_loan(
+    accounts: &mut Accounts,
+    lent_lamports: u64,
+    dst: &Pubkey,
+    borrow_ix_index: u16,
+) -> ExecutionResult {
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    crate::record_loan(&mut slumlord.data, lent_lamports, dst, borrow_ix_index)?;
+    Ok(())
+}
+
+fn migrate(accounts: &mut Accounts, pre: &mut Accounts, payer: &Pubkey) -> ExecutionResult {
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    verify_slumlord_initialized(&slumlord.owner)?;
+    verify_no_active_loan(&slumlord.data)?;
+    if slumlord_account_version(&slumlord.data)? == Some(SLUMLORD_ACCOUNT_VERSION) {
+        return Ok(());
+    }
+    let rent_shortfall = Rent::default()
+        .minimum_balance(SLUMLORD_ACCOUNT_LEN)
+        .saturating_sub(slumlord.lamports);
+    if rent_shortfall > 0 {
+        invoke(
+            accounts,
+            pre,
+            &transfer_ix(payer, &program::SLUMLORD_ID, rent_shortfall),
+        )?;
+    }
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    let old_len = slumlord.data.len();
+    slumlord.data.resize(SLUMLORD_ACCOUNT_LEN, 0);
+    migrate_slumlord_data(&mut slumlord.data, old_len)?;
+    Ok(())
+}
+
+fn borrow(
+    accounts: &mut Accounts,
+    ixs: SlumlordIxScanner,
+    curr_ix_idx: usize,
+    dst: &Pubkey,
+    borrower_accounts: &[AccountMeta],
+    min_lamports: u64,
+) -> ExecutionResult {
+    verify_slumlord_initialized(&acc(accounts, &program::SLUMLORD_ID).owner)?;
+    verify_allowed_borrower(accounts, borrower_accounts)?;
+    verify_not_loan_to_self(dst)?;
+    let borrow_ix_index = verify_succeeding_check_repaid(ixs, curr_ix_idx)?;
+    let borrow_lamports = begin_loan(accounts)?;
+    verify_loanable(min_lamports, borrow_lamports)?;
+    record_loan(accounts, borrow_lamports, dst, borrow_ix_index)?;
+    transfer_direct(accounts, &program::SLUMLORD_ID, dst, borrow_lamports)
+}
+
+fn borrow_split(
+    accounts: &mut Accounts,
+    ix: &Instruction,
+    ixs: SlumlordIxScanner,
+    curr_ix_idx: usize,
+    args: BorrowSplitIxArgs,
+) -> ExecutionResult {
+    let (dsts, borrower_accounts) = ix
+        .accounts
+        .get(BORROW_SPLIT_IX_ACCOUNTS_LEN..)
+        .filter(|remaining| remaining.len() >= args.amounts.len())
+        .map(|remaining| remaining.split_at(args.amounts.len()))
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    verify_account_metas::<BORROW_SPLIT_IX_ACCOUNTS_LEN, _>(ix, |_: BorrowSplitKeys| {
+        BORROW_SPLIT_KEYS
+    })?;
+    verify_slumlord_initialized(&acc(accounts, &program::SLUMLORD_ID).owner)?;
+    verify_allowed_borrower(accounts, borrower_accounts)?;
+    verify_split_loan_dsts(dsts)?;
+
+    let borrow_ix_index = verify_succeeding_check_repaid(ixs, curr_ix_idx)?;
+    let max_borrow_lamports = begin_loan(accounts)?;
+    let total_borrow_lamports = split_loan_lamports(&args.amounts, max_borrow_lamports)?;
+    let dst = split_loan_dst(dsts.iter().map(|dst| &dst.pubkey));
+    record_loan(accounts, total_borrow_lamports, &dst, borrow_ix_index)?;
+    for (dst, amount) in dsts.iter().zip(args.amounts) {
+        transfer_direct(accounts, &program::SLUMLORD_ID, &dst.pubkey, amount)?;
+    }
+    Ok(())
+}
+
+fn flash_loan_with_callback(
+    accounts: &mut Accounts,
+    pre: &mut Accounts,
+    programs: &Programs,
+    ix: &Instruction,
+    args: FlashLoanWithCallbackIxArgs,
+) -> ExecutionResult {
+    let remaining_accounts = ix
+        .accounts
+        .get(FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN..)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let keys = verify_account_metas::<FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN, _>(
+        ix,
+        |k: FlashLoanWithCallbackKeys| {
+            FlashLoanWithCallbackFreeArgs {
+                receiver: k.receiver,
+                receiver_program: k.receiver_program,
+                initiator: k.initiator,
+            }
+            .resolve()
+        },
+    )?;
+    verify_slumlord_initialized(&acc(accounts, &program::SLUMLORD_ID).owner)?;
+    let callback_accounts = verify_allowed_borrower(accounts, remaining_accounts)?;
+    verify_not_loan_to_self(&keys.receiver)?;
+
+    let max_borrow_lamports = begin_loan(accounts)?;
+    verify_loanable(args.amount, max_borrow_lamports)?;
+    record_loan(
+        accounts,
+        args.amount,
+        &keys.receiver,
+        UNKNOWN_BORROW_IX_INDEX,
+    )?;
+    transfer_direct(accounts, &program::SLUMLORD_ID, &keys.receiver, args.amount)?;
+
+    let FlashLoanWithCallbackIxArgs { amount, data } = args;
+    let mut on_flash_loan = on_flash_loan_ix(
+        keys.receiver_program,
+        OnFlashLoanKeys {
+            slumlord: program::SLUMLORD_ID,
+            receiver: keys.receiver,
+            initiator: keys.initiator,
+        },
+        OnFlashLoanIxArgs { amount, data },
+    )
+    .map_err(ProgramError::from)?;
+    on_flash_loan.accounts[1].is_signer = is_signer(ix, &keys.receiver);
+    on_flash_loan.accounts.extend_from_slice(callback_accounts);
+    invoke_with(accounts, pre, &on_flash_loan, |accounts, ix| {
+        process_program_ix(accounts, programs, ix)
+    })?;
+
+    end_loan(accounts)
+}
+
+fn add_borrower(accounts: &mut Accounts, pre: &mut Accounts, ix: &Instruction) -> ExecutionResult {
+    let keys =
+        verify_account_metas::<ADD_BORROWER_IX_ACCOUNTS_LEN, _>(ix, |k: AddBorrowerKeys| {
+            AddBorrowerFreeArgs {
+                admin: k.admin,
+                payer: k.payer,
+                borrower: k.borrower,
+            }
+            .resolve()
+        })?;
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    verify_slumlord_initialized(&slumlord.owner)?;
+    verify_admin(&slumlord.data, &keys.admin)?;
+
+    let rent_shortfall = Rent::default()
+        .minimum_balance(BORROWER_ENTRY_LEN)
+        .saturating_sub(acc(accounts, &keys.borrower_entry).lamports);
+    if rent_shortfall > 0 {
+        invoke(
+            accounts,
+            pre,
+            &transfer_ix(&keys.payer, &keys.borrower_entry, rent_shortfall),
+        )?;
+    }
+    // PDA signs for allocate and assign CPIs
+    let borrower_entry = AccountMeta::new(keys.borrower_entry, true);
+    let allocate_ix = Instruction::new_with_bincode(
+        system_program::ID,
+        &SystemInstruction::Allocate {
+            space: BORROWER_ENTRY_LEN as u64,
+        },
+        vec![borrower_entry.clone()],
+    );
+    invoke(accounts, pre, &allocate_ix)?;
+    let assign_ix = Instruction::new_with_bincode(
+        system_program::ID,
+        &SystemInstruction::Assign { owner: program::ID },
+        vec![borrower_entry],
+    );
+    invoke(accounts, pre, &assign_ix)?;
+
+    try_borrower_entry_mut(&mut acc(accounts, &keys.borrower_entry).data)?.borrower = keys.borrower;
+    Ok(())
+}
+
+fn remove_borrower(accounts: &mut Accounts, ix: &Instruction) -> ExecutionResult {
+    let keys =
+        verify_account_metas::<REMOVE_BORROWER_IX_ACCOUNTS_LEN, _>(ix, |k: RemoveBorrowerKeys| {
+            RemoveBorrowerFreeArgs {
+                admin: k.admin,
+                borrower: k.borrower,
+                refund_rent_to: k.refund_rent_to,
+            }
+            .resolve()
+        })?;
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    verify_slumlord_initialized(&slumlord.owner)?;
+    verify_admin(&slumlord.data, &keys.admin)?;
+    let borrower_entry = acc(accounts, &keys.borrower_entry);
+    verify_borrower_entry(&keys.borrower, &borrower_entry.owner, &borrower_entry.data)?;
+
+    let lamports = borrower_entry.lamports;
+    transfer_direct(
+        accounts,
+        &keys.borrower_entry,
+        &keys.refund_rent_to,
+        lamports,
+    )?;
+    let borrower_entry = acc(accounts, &keys.borrower_entry);
+    borrower_entry.data.clear();
+    borrower_entry.owner = system_program::ID;
+    Ok(())
+}
+
+/// Directly debits `lamports` from `from` and credits them to `to`
+fn transfer_direct(
+    accounts: &mut Accounts,
+    from: &Pubkey,
+    to: &Pubkey,
+    lamports: u64,
+) -> ExecutionResult {
+    let from = acc(accounts, from);
+    from.lamports = from
+        .lamports
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let to = acc(accounts, to);
+    to.lamports = to
+        .lamports
+        .checked_add(lamports)
+        .ok_or(InstructionError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// System program transfer of `lamports` from the signing `from` to `to`
+fn transfer_ix(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    Instruction::new_with_bincode(
+        system_program::ID,
+        &SystemInstruction::Transfer { lamports },
+        vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+    )
+}
+
+fn is_signer(ix: &Instruction, pubkey: &Pubkey) -> bool {
+    ix.accounts
+        .iter()
+        .any(|m| m.pubkey == *pubkey && m.is_signer)
+}
+
+/// System transfers `lamports` from `src` to slumlord via CPI
+fn repay(
+    accounts: &mut Accounts,
+    pre: &mut Accounts,
+    ix: &Instruction,
+    src: &Pubkey,
+    lamports: u64,
+) -> ExecutionResult {
+    let transfer_ix = Instruction::new_with_bincode(
+        system_program::ID,
+        &SystemInstruction::Transfer { lamports },
+        vec![
+            AccountMeta::new(*src, is_signer(ix, src)),
+            AccountMeta::new(program::SLUMLORD_ID, false),
+        ],
+    );
+    invoke(accounts, pre, &transfer_ix)
+}
+
+fn with_slumlord_account_info<T>(
+    accounts: &mut Accounts,
+    f: impl FnOnce(&AccountInfo) -> Result<T, ProgramError>,
+) -> Result<T, ExecutionError> {
+    let slumlord = acc(accounts, &program::SLUMLORD_ID);
+    let mut lamports = slumlord.lamports;
+    let account_info = AccountInfo::new(
+        &program::SLUMLORD_ID,
+        false,
+        true,
+        &mut lamports,
+        &mut slumlord.data,
+        &slumlord.owner,
+        false,
+        0,
+    );
+    Ok(f(&account_info)?)
+}
+
+fn curr_loan_lamports_outstanding(accounts: &mut Accounts) -> Result<u64, ExecutionError> {
+    with_slumlord_account_info(accounts, |a| a.curr_loan_lamports_outstanding())
+}
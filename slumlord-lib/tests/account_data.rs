@@ -0,0 +1,354 @@
+//! Tests of the versioned slumlord account data layout and the fields it records
+
+use slumlord_interface::{
+    BorrowerEntry, CheckRepaidReturn, LoanContext, PoolLimits, SlumlordError, SlumlordHeader,
+};
+use slumlord_lib::{
+    check_loan_repaid, clear_versioned_loan, is_allowlist_enabled, is_loan_active,
+    loanable_lamports, migrate_slumlord_data, program, quote_loan, slumlord_account_version,
+    split_loan_dst, try_loan_context, try_pool_config, try_pool_config_mut, try_pool_limits,
+    unrepaid_loan_msg, verify_admin, verify_borrower_entry, LoanActiveSlumlordAccount,
+    RepayFreeArgs, BORROWER_ENTRY_LEN, LEGACY_SLUMLORD_ACCOUNT_LEN, SLUMLORD_ACCOUNT_LEN,
+    SLUMLORD_ACCOUNT_VERSION, SPLIT_LOAN_DST, UNKNOWN_BORROW_IX_INDEX,
+};
+use slumlord_test_utils::SlumlordAccountBuilder;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+const SLUMLORD_LAMPORTS: u64 = 1_000_000;
+
+#[test]
+fn record_and_clear_loan_context() {
+    let dst = Pubkey::new_unique();
+    let mut slumlord =
+        SlumlordAccountBuilder::migrated(1).loan(SLUMLORD_LAMPORTS, SLUMLORD_LAMPORTS - 1, &dst, 3);
+
+    assert_eq!(
+        try_loan_context(slumlord.data()).unwrap(),
+        Some(&LoanContext {
+            dst,
+            borrow_ix_index: 3,
+            padding: [0; 6],
+            lent_lamports: SLUMLORD_LAMPORTS - 1,
+        })
+    );
+    assert_eq!(
+        check_loan_repaid(slumlord.data(), SLUMLORD_LAMPORTS + 1).unwrap(),
+        CheckRepaidReturn {
+            old_lamports: SLUMLORD_LAMPORTS,
+            repaid: Some(SLUMLORD_LAMPORTS),
+            surplus: 1,
+        }
+    );
+    slumlord.with_account_info(|slumlord| {
+        assert_eq!(slumlord.loan_context().unwrap().dst, dst);
+        assert_eq!(
+            RepayFreeArgs::for_active_loan(slumlord).unwrap(),
+            RepayFreeArgs { src: dst }
+        );
+    });
+
+    assert!(clear_versioned_loan(slumlord.data_mut()).unwrap());
+    assert!(!is_loan_active(slumlord.data()).unwrap());
+    assert_eq!(slumlord, SlumlordAccountBuilder::migrated(1));
+    slumlord.with_account_info(|slumlord| {
+        assert_eq!(
+            slumlord.loan_context().unwrap_err(),
+            ProgramError::from(SlumlordError::NoActiveLoan)
+        );
+    });
+}
+
+#[test]
+fn split_loan_has_no_single_dst() {
+    let dst = Pubkey::new_unique();
+    assert_eq!(split_loan_dst([dst].iter()), dst);
+    assert_eq!(split_loan_dst([dst, dst].iter()), SPLIT_LOAN_DST);
+    assert_eq!(
+        split_loan_dst([dst, Pubkey::new_unique()].iter()),
+        SPLIT_LOAN_DST
+    );
+    assert_eq!(split_loan_dst([].iter()), SPLIT_LOAN_DST);
+
+    let mut slumlord = SlumlordAccountBuilder::migrated(1).loan(
+        SLUMLORD_LAMPORTS,
+        SLUMLORD_LAMPORTS - 1,
+        &SPLIT_LOAN_DST,
+        0,
+    );
+    slumlord.with_account_info(|slumlord| {
+        assert_eq!(slumlord.loan_context().unwrap().dst, SPLIT_LOAN_DST);
+        assert_eq!(
+            RepayFreeArgs::for_active_loan(slumlord).unwrap_err(),
+            ProgramError::InvalidArgument
+        );
+    });
+    assert_eq!(
+        unrepaid_loan_msg(try_loan_context(slumlord.data()).unwrap(), 1),
+        "Loan from instruction 0 to multiple accounts not repaid, short by 1 lamports"
+    );
+}
+
+#[test]
+fn unmigrated_loan_has_no_context() {
+    let dst = Pubkey::new_unique();
+    let mut slumlord = SlumlordAccountBuilder::unmigrated(1).loan(
+        SLUMLORD_LAMPORTS,
+        SLUMLORD_LAMPORTS - 1,
+        &dst,
+        0,
+    );
+
+    assert!(is_loan_active(slumlord.data()).unwrap());
+    assert_eq!(try_loan_context(slumlord.data()).unwrap(), None);
+    assert_eq!(
+        check_loan_repaid(slumlord.data(), SLUMLORD_LAMPORTS + 1).unwrap(),
+        CheckRepaidReturn {
+            old_lamports: SLUMLORD_LAMPORTS,
+            repaid: None,
+            surplus: 1,
+        }
+    );
+    slumlord.with_account_info(|slumlord| {
+        assert_eq!(
+            RepayFreeArgs::for_active_loan(slumlord).unwrap_err(),
+            ProgramError::from(SlumlordError::NoLoanContext)
+        );
+    });
+    assert!(!clear_versioned_loan(slumlord.data_mut()).unwrap());
+}
+
+/// `LoanActiveSlumlordAccount::old_lamports()` of clients built before versioning,
+/// which casts the entire account data to the 8-byte loan state
+fn baseline_old_lamports(slumlord_acc_data: &[u8]) -> Option<u64> {
+    slumlord_acc_data.try_into().ok().map(u64::from_le_bytes)
+}
+
+#[test]
+fn loan_state_keeps_baseline_layout() {
+    let dst = Pubkey::new_unique();
+
+    // lent to by the program before versioning
+    let mut baseline = SlumlordAccountBuilder::new(1, SLUMLORD_LAMPORTS.to_le_bytes().to_vec());
+    assert_eq!(baseline.data().len(), LEGACY_SLUMLORD_ACCOUNT_LEN);
+    assert_eq!(slumlord_account_version(baseline.data()).unwrap(), None);
+    baseline.with_account_info(|slumlord| {
+        assert_eq!(slumlord.old_lamports().unwrap(), SLUMLORD_LAMPORTS);
+        assert_eq!(
+            slumlord.curr_loan_lamports_outstanding().unwrap(),
+            SLUMLORD_LAMPORTS - 1
+        );
+    });
+    let baseline = baseline.data_mut();
+    baseline.resize(SLUMLORD_ACCOUNT_LEN, 0);
+    assert_eq!(
+        migrate_slumlord_data(baseline, LEGACY_SLUMLORD_ACCOUNT_LEN).unwrap_err(),
+        ProgramError::from(SlumlordError::CorruptLoanState)
+    );
+
+    // lent to by the current program before migration
+    let unmigrated = SlumlordAccountBuilder::unmigrated(1).loan(SLUMLORD_LAMPORTS, 1, &dst, 0);
+    assert_eq!(
+        baseline_old_lamports(unmigrated.data()),
+        Some(SLUMLORD_LAMPORTS)
+    );
+
+    // migrated once the loan ended and the data was emptied
+    let mut migrated = SlumlordAccountBuilder::migrated(1).loan(SLUMLORD_LAMPORTS, 1, &dst, 0);
+    let loan_state = &migrated.data()[std::mem::size_of::<SlumlordHeader>()..];
+    assert_eq!(
+        baseline_old_lamports(&loan_state[..LEGACY_SLUMLORD_ACCOUNT_LEN]),
+        Some(SLUMLORD_LAMPORTS)
+    );
+    migrated.with_account_info(|slumlord| {
+        assert_eq!(slumlord.old_lamports().unwrap(), SLUMLORD_LAMPORTS);
+    });
+}
+
+#[test]
+fn migrate_v1_appends_loan_context() {
+    const V1_LEN: usize = 16;
+
+    let mut data = vec![0; V1_LEN];
+    data[0] = 1;
+    assert_eq!(slumlord_account_version(&data).unwrap(), Some(1));
+    // V1 predates loan context
+    assert_eq!(try_loan_context(&data).unwrap(), None);
+
+    data.resize(SLUMLORD_ACCOUNT_LEN, 0xff);
+    migrate_slumlord_data(&mut data, V1_LEN).unwrap();
+    assert_eq!(
+        slumlord_account_version(&data).unwrap(),
+        Some(SLUMLORD_ACCOUNT_VERSION)
+    );
+    assert_eq!(data, SlumlordAccountBuilder::migrated(0).data());
+}
+
+#[test]
+fn unrepaid_loan_msgs() {
+    let dst = Pubkey::new_unique();
+    let context = LoanContext {
+        dst,
+        borrow_ix_index: 3,
+        padding: [0; 6],
+        lent_lamports: 1,
+    };
+    assert_eq!(
+        unrepaid_loan_msg(Some(&context), 5),
+        format!("Loan from instruction 3 to {dst} not repaid, short by 5 lamports")
+    );
+    let flash_loan_context = LoanContext {
+        borrow_ix_index: UNKNOWN_BORROW_IX_INDEX,
+        ..context
+    };
+    assert_eq!(
+        unrepaid_loan_msg(Some(&flash_loan_context), 5),
+        format!("Loan to {dst} not repaid, short by 5 lamports")
+    );
+    assert_eq!(
+        unrepaid_loan_msg(None, 5),
+        "Loan not repaid, short by 5 lamports"
+    );
+}
+
+#[test]
+fn migrated_pool_config_unset() {
+    let admin = Pubkey::new_unique();
+    let mut slumlord = SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS);
+    let data = slumlord.data_mut();
+
+    assert_eq!(
+        try_pool_config(data).unwrap().unwrap().admin,
+        Pubkey::default()
+    );
+    assert!(!is_allowlist_enabled(data).unwrap());
+    assert_eq!(
+        verify_admin(data, &admin).unwrap_err(),
+        ProgramError::from(SlumlordError::NotAdmin)
+    );
+    assert_eq!(
+        verify_admin(data, &Pubkey::default()).unwrap_err(),
+        ProgramError::from(SlumlordError::NotAdmin)
+    );
+
+    let pool_config = try_pool_config_mut(data).unwrap().unwrap();
+    pool_config.admin = admin;
+    pool_config.allowlist_enabled = 1;
+    verify_admin(data, &admin).unwrap();
+    assert!(is_allowlist_enabled(data).unwrap());
+}
+
+#[test]
+fn unmigrated_has_no_pool_config() {
+    let data = vec![0; LEGACY_SLUMLORD_ACCOUNT_LEN];
+
+    assert_eq!(try_pool_config(&data).unwrap(), None);
+    assert!(!is_allowlist_enabled(&data).unwrap());
+    assert_eq!(
+        verify_admin(&data, &Pubkey::new_unique()).unwrap_err(),
+        ProgramError::from(SlumlordError::NoPoolConfig)
+    );
+}
+
+#[test]
+fn verify_borrower_entries() {
+    let borrower = Pubkey::new_unique();
+    let entry = BorrowerEntry { borrower };
+    let entry_data = bytemuck::bytes_of(&entry);
+    assert_eq!(entry_data.len(), BORROWER_ENTRY_LEN);
+
+    verify_borrower_entry(&borrower, &program::ID, entry_data).unwrap();
+    assert_eq!(
+        verify_borrower_entry(&Pubkey::new_unique(), &program::ID, entry_data).unwrap_err(),
+        SlumlordError::BorrowerNotAllowed
+    );
+    assert_eq!(
+        verify_borrower_entry(&borrower, &Pubkey::new_unique(), entry_data).unwrap_err(),
+        SlumlordError::BorrowerNotAllowed
+    );
+    assert_eq!(
+        verify_borrower_entry(&borrower, &program::ID, &[]).unwrap_err(),
+        SlumlordError::BorrowerNotAllowed
+    );
+}
+
+#[test]
+fn unlimited_lends_all_but_1_lamport() {
+    for slumlord in [
+        SlumlordAccountBuilder::unmigrated(SLUMLORD_LAMPORTS),
+        SlumlordAccountBuilder::new(SLUMLORD_LAMPORTS, vec![0; LEGACY_SLUMLORD_ACCOUNT_LEN]),
+        SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS).pool_limits(0, 0),
+        SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS).pool_limits(0, 1),
+    ] {
+        assert_eq!(
+            loanable_lamports(slumlord.data(), SLUMLORD_LAMPORTS).unwrap(),
+            SLUMLORD_LAMPORTS - 1
+        );
+        assert_eq!(
+            loanable_lamports(slumlord.data(), 0).unwrap_err(),
+            ProgramError::from(SlumlordError::PoolEmpty)
+        );
+    }
+}
+
+#[test]
+fn limits_cap_loans() {
+    let capped = SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS).pool_limits(1_000, 0);
+    assert_eq!(
+        loanable_lamports(capped.data(), SLUMLORD_LAMPORTS).unwrap(),
+        1_000
+    );
+    assert_eq!(loanable_lamports(capped.data(), 500).unwrap(), 499);
+
+    let reserved = SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS).pool_limits(0, 1_000);
+    assert_eq!(
+        loanable_lamports(reserved.data(), SLUMLORD_LAMPORTS).unwrap(),
+        SLUMLORD_LAMPORTS - 1_000
+    );
+    assert_eq!(loanable_lamports(reserved.data(), 1_000).unwrap(), 0);
+    assert_eq!(
+        loanable_lamports(reserved.data(), 999).unwrap_err(),
+        ProgramError::from(SlumlordError::PoolEmpty)
+    );
+
+    let both = SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS)
+        .pool_limits(1_000, SLUMLORD_LAMPORTS - 500);
+    assert_eq!(
+        loanable_lamports(both.data(), SLUMLORD_LAMPORTS).unwrap(),
+        500
+    );
+}
+
+#[test]
+fn quote_loan_reflects_limits() {
+    let mut slumlord = SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS).pool_limits(1_000, 0);
+    slumlord.with_account_info(|slumlord| {
+        assert_eq!(quote_loan(slumlord).loanable_lamports, 1_000);
+    });
+}
+
+#[test]
+fn migrate_v3_appends_unlimited_pool_limits() {
+    const V3_LEN: usize = SLUMLORD_ACCOUNT_LEN - std::mem::size_of::<PoolLimits>();
+
+    let mut data = vec![0; V3_LEN];
+    data[0] = 3;
+    assert_eq!(slumlord_account_version(&data).unwrap(), Some(3));
+    assert_eq!(try_pool_limits(&data).unwrap(), None);
+
+    data.resize(SLUMLORD_ACCOUNT_LEN, 0xff);
+    migrate_slumlord_data(&mut data, V3_LEN).unwrap();
+    assert_eq!(
+        slumlord_account_version(&data).unwrap(),
+        Some(SLUMLORD_ACCOUNT_VERSION)
+    );
+    assert_eq!(
+        data,
+        SlumlordAccountBuilder::migrated(SLUMLORD_LAMPORTS)
+            .pool_limits(0, 0)
+            .data()
+    );
+    assert_eq!(
+        loanable_lamports(&data, SLUMLORD_LAMPORTS).unwrap(),
+        SLUMLORD_LAMPORTS - 1
+    );
+}
@@ -0,0 +1,598 @@
+//! Checks that the solores-generated `slumlord_interface` crate matches `idl.json`
+//! and the instructions of the Anchor IDL generated from it, `slumlord-anchor/idl.json`,
+//! so that they cannot drift apart between regenerations.
+
+use borsh::BorshSerialize;
+use num_traits::FromPrimitive;
+use serde_json::Value;
+use slumlord_interface::*;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+
+const IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../idl.json");
+
+const ANCHOR_IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../slumlord-anchor/idl.json");
+
+fn load_idl() -> Value {
+    serde_json::from_str(&std::fs::read_to_string(IDL_PATH).unwrap()).unwrap()
+}
+
+/// The Anchor IDL instruction with `discriminator` `[discm]`
+fn anchor_ix(discm: u8) -> Value {
+    let anchor_idl: Value =
+        serde_json::from_str(&std::fs::read_to_string(ANCHOR_IDL_PATH).unwrap()).unwrap();
+    anchor_idl["instructions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|ix| ix["discriminator"] == serde_json::json!([discm]))
+        .cloned()
+        .unwrap_or_else(|| panic!("discriminator [{discm}] not found in Anchor IDL instructions"))
+}
+
+fn idl_entry<'a>(idl: &'a Value, section: &str, name: &str) -> &'a Value {
+    idl[section]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["name"] == name)
+        .unwrap_or_else(|| panic!("{name} not found in idl.json {section}"))
+}
+
+fn idl_names(entries: &Value) -> Vec<&str> {
+    entries
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .map(|entry| entry["name"].as_str().unwrap())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Serialized size of `ty`'s zero/empty value
+fn zero_value_len(ty: &Value) -> usize {
+    match ty {
+        Value::String(s) => match s.as_str() {
+            "bool" | "u8" | "i8" => 1,
+            "u16" | "i16" => 2,
+            "u32" | "i32" => 4,
+            "u64" | "i64" => 8,
+            "u128" | "i128" => 16,
+            "publicKey" => 32,
+            "bytes" | "string" => 4,
+            _ => panic!("unsupported idl type {s}"),
+        },
+        Value::Object(o) if o.contains_key("vec") => 4,
+        Value::Object(o) if o.contains_key("option") => 1,
+        Value::Object(o) if o.contains_key("array") => {
+            let elem_ty = &o["array"][0];
+            let len = o["array"][1].as_u64().unwrap() as usize;
+            zero_value_len(elem_ty) * len
+        }
+        Value::Object(o) if o.contains_key("defined") => {
+            let name = o["defined"].as_str().unwrap();
+            zero_fields_len(&idl_entry(&load_idl(), "types", name)["type"]["fields"])
+        }
+        _ => panic!("unsupported idl type {ty}"),
+    }
+}
+
+fn zero_fields_len(fields: &Value) -> usize {
+    fields
+        .as_array()
+        .map(|fields| fields.iter().map(|f| zero_value_len(&f["type"])).sum())
+        .unwrap_or_default()
+}
+
+/// `keys` are the generated `Keys` struct's `(field name, pubkey)`s in idl order,
+/// created from `pks`, and `metas` the `AccountMeta`s converted from it
+fn check_ix_accounts(
+    ix: &Value,
+    discm: u8,
+    pks: &[Pubkey],
+    keys: &[(&str, Pubkey)],
+    metas: &[AccountMeta],
+) {
+    let name = &ix["name"];
+    let accounts = ix["accounts"].as_array().unwrap();
+    assert_eq!(accounts.len(), pks.len(), "{name} accounts len mismatch");
+    let anchor_accounts = anchor_ix(discm)["accounts"].clone();
+    assert_eq!(
+        idl_names(&anchor_accounts),
+        keys.iter()
+            .map(|(key_name, _)| *key_name)
+            .collect::<Vec<_>>(),
+        "{name} Anchor IDL accounts mismatch"
+    );
+    for (i, account) in accounts.iter().enumerate() {
+        let (key_name, key) = keys[i];
+        assert_eq!(
+            account["name"], key_name,
+            "{name} account {i} name mismatch"
+        );
+        assert_eq!(key, pks[i], "{name} account {key_name} not at index {i}");
+        assert_eq!(metas[i].pubkey, pks[i], "{name} account {key_name} meta");
+        assert_eq!(
+            account["isMut"], metas[i].is_writable,
+            "{name} account {key_name} isMut mismatch"
+        );
+        assert_eq!(
+            account["isSigner"], metas[i].is_signer,
+            "{name} account {key_name} isSigner mismatch"
+        );
+        let anchor_account = &anchor_accounts[i];
+        assert_eq!(
+            anchor_account["writable"] == true,
+            metas[i].is_writable,
+            "{name} account {key_name} Anchor IDL writable mismatch"
+        );
+        assert_eq!(
+            anchor_account["signer"] == true,
+            metas[i].is_signer,
+            "{name} account {key_name} Anchor IDL signer mismatch"
+        );
+    }
+}
+
+/// `ix_data` is the serialized generated `IxData` with zero/empty args
+fn check_ix_data(ix: &Value, discm: u8, arg_names: &[&str], ix_data: &[u8]) {
+    let name = &ix["name"];
+    assert_eq!(ix["discriminant"]["type"], "u8", "{name} discriminant type");
+    assert_eq!(
+        ix["discriminant"]["value"], discm,
+        "{name} discriminant mismatch"
+    );
+    assert_eq!(idl_names(&ix["args"]), arg_names, "{name} args mismatch");
+    assert_eq!(
+        idl_names(&anchor_ix(discm)["args"]),
+        arg_names,
+        "{name} Anchor IDL args mismatch"
+    );
+    let mut expected_data = vec![0u8; 1 + zero_fields_len(&ix["args"])];
+    expected_data[0] = discm;
+    assert_eq!(ix_data, expected_data, "{name} ix data layout mismatch");
+    let decoded = SlumlordProgramIx::deserialize(ix_data).unwrap();
+    let decoded_name = format!("{decoded:?}");
+    assert!(
+        decoded_name.starts_with(name.as_str().unwrap()),
+        "{name} discriminant decoded as {decoded_name}"
+    );
+}
+
+macro_rules! check_ix {
+    (
+        $idl:expr,
+        $name:literal,
+        $discm:ident,
+        $accounts_len:ident,
+        $keys:ident { $($acc:ident),* },
+        $ix_data:ident
+    ) => {
+        check_ix!(@accounts $idl, $name, $discm, $accounts_len, $keys { $($acc),* });
+        check_ix_data(
+            idl_entry(&$idl, "instructions", $name),
+            $discm,
+            &[],
+            &$ix_data.try_to_vec().unwrap(),
+        );
+    };
+    (
+        $idl:expr,
+        $name:literal,
+        $discm:ident,
+        $accounts_len:ident,
+        $keys:ident { $($acc:ident),* },
+        $ix_data:ident($args:ident { $($arg:ident),* })
+    ) => {
+        check_ix!(@accounts $idl, $name, $discm, $accounts_len, $keys { $($acc),* });
+        check_ix_data(
+            idl_entry(&$idl, "instructions", $name),
+            $discm,
+            &[$(stringify!($arg)),*],
+            &$ix_data($args { $($arg: Default::default()),* })
+                .try_to_vec()
+                .unwrap(),
+        );
+    };
+    (@accounts $idl:expr, $name:literal, $discm:ident, $accounts_len:ident, $keys:ident { $($acc:ident),* }) => {
+        let pks: [Pubkey; $accounts_len] = std::array::from_fn(|_| Pubkey::new_unique());
+        let keys = $keys::from(pks);
+        let key_names = [$((stringify!($acc), keys.$acc)),*];
+        let metas: [AccountMeta; $accounts_len] = keys.into();
+        check_ix_accounts(
+            idl_entry(&$idl, "instructions", $name),
+            $discm,
+            &pks,
+            &key_names,
+            &metas,
+        );
+    };
+}
+
+#[test]
+fn program_id_matches_idl() {
+    let idl = load_idl();
+    assert_eq!(
+        idl["metadata"]["address"],
+        slumlord_interface::ID.to_string()
+    );
+}
+
+#[test]
+fn instructions_match_idl() {
+    let idl = load_idl();
+    assert_eq!(
+        idl_names(&idl["instructions"]),
+        [
+            "Init",
+            "Borrow",
+            "Repay",
+            "CheckRepaid",
+            "BorrowSplit",
+            "RepayWithMax",
+            "BorrowWithMin",
+            "FlashLoanWithCallback",
+            "QuoteLoan",
+            "Migrate",
+            "SetAdmin",
+            "SetAllowlist",
+            "AddBorrower",
+            "RemoveBorrower",
+            "SetPoolLimits",
+        ],
+        "instructions added or removed, update this test"
+    );
+    check_ix!(
+        idl,
+        "Init",
+        INIT_IX_DISCM,
+        INIT_IX_ACCOUNTS_LEN,
+        InitKeys {
+            slumlord,
+            system_program
+        },
+        InitIxData
+    );
+    check_ix!(
+        idl,
+        "Borrow",
+        BORROW_IX_DISCM,
+        BORROW_IX_ACCOUNTS_LEN,
+        BorrowKeys {
+            slumlord,
+            dst,
+            instructions
+        },
+        BorrowIxData
+    );
+    check_ix!(
+        idl,
+        "Repay",
+        REPAY_IX_DISCM,
+        REPAY_IX_ACCOUNTS_LEN,
+        RepayKeys {
+            slumlord,
+            src,
+            system_program
+        },
+        RepayIxData
+    );
+    check_ix!(
+        idl,
+        "CheckRepaid",
+        CHECK_REPAID_IX_DISCM,
+        CHECK_REPAID_IX_ACCOUNTS_LEN,
+        CheckRepaidKeys { slumlord },
+        CheckRepaidIxData
+    );
+    check_ix!(
+        idl,
+        "BorrowSplit",
+        BORROW_SPLIT_IX_DISCM,
+        BORROW_SPLIT_IX_ACCOUNTS_LEN,
+        BorrowSplitKeys {
+            slumlord,
+            instructions
+        },
+        BorrowSplitIxData(BorrowSplitIxArgs { amounts })
+    );
+    check_ix!(
+        idl,
+        "RepayWithMax",
+        REPAY_WITH_MAX_IX_DISCM,
+        REPAY_WITH_MAX_IX_ACCOUNTS_LEN,
+        RepayWithMaxKeys {
+            slumlord,
+            src,
+            system_program
+        },
+        RepayWithMaxIxData(RepayWithMaxIxArgs { max_lamports })
+    );
+    check_ix!(
+        idl,
+        "BorrowWithMin",
+        BORROW_WITH_MIN_IX_DISCM,
+        BORROW_WITH_MIN_IX_ACCOUNTS_LEN,
+        BorrowWithMinKeys {
+            slumlord,
+            dst,
+            instructions
+        },
+        BorrowWithMinIxData(BorrowWithMinIxArgs { min_lamports })
+    );
+    check_ix!(
+        idl,
+        "FlashLoanWithCallback",
+        FLASH_LOAN_WITH_CALLBACK_IX_DISCM,
+        FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN,
+        FlashLoanWithCallbackKeys {
+            slumlord,
+            receiver,
+            receiver_program,
+            initiator
+        },
+        FlashLoanWithCallbackIxData(FlashLoanWithCallbackIxArgs { amount, data })
+    );
+    check_ix!(
+        idl,
+        "QuoteLoan",
+        QUOTE_LOAN_IX_DISCM,
+        QUOTE_LOAN_IX_ACCOUNTS_LEN,
+        QuoteLoanKeys { slumlord },
+        QuoteLoanIxData
+    );
+    check_ix!(
+        idl,
+        "Migrate",
+        MIGRATE_IX_DISCM,
+        MIGRATE_IX_ACCOUNTS_LEN,
+        MigrateKeys {
+            slumlord,
+            payer,
+            system_program
+        },
+        MigrateIxData
+    );
+    check_ix!(
+        idl,
+        "SetAdmin",
+        SET_ADMIN_IX_DISCM,
+        SET_ADMIN_IX_ACCOUNTS_LEN,
+        SetAdminKeys {
+            slumlord,
+            authority,
+            new_admin,
+            program_data
+        },
+        SetAdminIxData
+    );
+    check_ix!(
+        idl,
+        "SetAllowlist",
+        SET_ALLOWLIST_IX_DISCM,
+        SET_ALLOWLIST_IX_ACCOUNTS_LEN,
+        SetAllowlistKeys { slumlord, admin },
+        SetAllowlistIxData(SetAllowlistIxArgs { enabled })
+    );
+    check_ix!(
+        idl,
+        "AddBorrower",
+        ADD_BORROWER_IX_DISCM,
+        ADD_BORROWER_IX_ACCOUNTS_LEN,
+        AddBorrowerKeys {
+            slumlord,
+            admin,
+            payer,
+            borrower,
+            borrower_entry,
+            system_program
+        },
+        AddBorrowerIxData
+    );
+    check_ix!(
+        idl,
+        "RemoveBorrower",
+        REMOVE_BORROWER_IX_DISCM,
+        REMOVE_BORROWER_IX_ACCOUNTS_LEN,
+        RemoveBorrowerKeys {
+            slumlord,
+            admin,
+            borrower,
+            borrower_entry,
+            refund_rent_to
+        },
+        RemoveBorrowerIxData
+    );
+    check_ix!(
+        idl,
+        "SetPoolLimits",
+        SET_POOL_LIMITS_IX_DISCM,
+        SET_POOL_LIMITS_IX_ACCOUNTS_LEN,
+        SetPoolLimitsKeys { slumlord, admin },
+        SetPoolLimitsIxData(SetPoolLimitsIxArgs {
+            max_borrow_lamports,
+            reserve_lamports
+        })
+    );
+}
+
+#[test]
+fn errors_match_idl() {
+    let idl = load_idl();
+    let errors = idl["errors"].as_array().unwrap();
+    for error in errors {
+        let code = error["code"].as_u64().unwrap();
+        let e = SlumlordError::from_u64(code)
+            .unwrap_or_else(|| panic!("error code {code} not in SlumlordError"));
+        assert_eq!(
+            error["name"],
+            format!("{e:?}"),
+            "error {code} name mismatch"
+        );
+        assert_eq!(error["msg"], e.to_string(), "error {code} msg mismatch");
+    }
+    assert!(
+        SlumlordError::from_usize(errors.len()).is_none(),
+        "SlumlordError has variants not in idl.json"
+    );
+}
+
+#[test]
+fn typedefs_match_idl() {
+    let idl = load_idl();
+    assert_eq!(
+        idl_names(&idl["types"]),
+        [
+            "Slumlord",
+            "SlumlordHeader",
+            "LoanContext",
+            "PoolConfig",
+            "PoolLimits",
+            "SlumlordAccount",
+            "BorrowerEntry",
+            "CheckRepaidReturn",
+            "QuoteLoanReturn"
+        ]
+    );
+
+    let slumlord = idl_entry(&idl, "types", "Slumlord");
+    let _exhaustive_fields = Slumlord { old_lamports: 0 };
+    assert_eq!(idl_names(&slumlord["type"]["fields"]), ["old_lamports"]);
+    assert_eq!(
+        std::mem::size_of::<Slumlord>(),
+        zero_fields_len(&slumlord["type"]["fields"]),
+        "Slumlord layout mismatch"
+    );
+
+    let slumlord_header = idl_entry(&idl, "types", "SlumlordHeader");
+    let header = SlumlordHeader {
+        version: 0,
+        loan_active: 0,
+        padding: [0; 6],
+    };
+    assert_eq!(
+        idl_names(&slumlord_header["type"]["fields"]),
+        ["version", "loan_active", "padding"]
+    );
+    assert_eq!(
+        std::mem::size_of::<SlumlordHeader>(),
+        zero_fields_len(&slumlord_header["type"]["fields"]),
+        "SlumlordHeader layout mismatch"
+    );
+
+    let loan_context = idl_entry(&idl, "types", "LoanContext");
+    let context = LoanContext {
+        dst: Pubkey::default(),
+        borrow_ix_index: 0,
+        padding: [0; 6],
+        lent_lamports: 0,
+    };
+    assert_eq!(
+        idl_names(&loan_context["type"]["fields"]),
+        ["dst", "borrow_ix_index", "padding", "lent_lamports"]
+    );
+    assert_eq!(
+        std::mem::size_of::<LoanContext>(),
+        zero_fields_len(&loan_context["type"]["fields"]),
+        "LoanContext layout mismatch"
+    );
+
+    let pool_config = idl_entry(&idl, "types", "PoolConfig");
+    let config = PoolConfig {
+        admin: Pubkey::default(),
+        allowlist_enabled: 0,
+        padding: [0; 7],
+    };
+    assert_eq!(
+        idl_names(&pool_config["type"]["fields"]),
+        ["admin", "allowlist_enabled", "padding"]
+    );
+    assert_eq!(
+        std::mem::size_of::<PoolConfig>(),
+        zero_fields_len(&pool_config["type"]["fields"]),
+        "PoolConfig layout mismatch"
+    );
+
+    let pool_limits = idl_entry(&idl, "types", "PoolLimits");
+    let limits = PoolLimits {
+        max_borrow_lamports: 0,
+        reserve_lamports: 0,
+    };
+    assert_eq!(
+        idl_names(&pool_limits["type"]["fields"]),
+        ["max_borrow_lamports", "reserve_lamports"]
+    );
+    assert_eq!(
+        std::mem::size_of::<PoolLimits>(),
+        zero_fields_len(&pool_limits["type"]["fields"]),
+        "PoolLimits layout mismatch"
+    );
+
+    let slumlord_account = idl_entry(&idl, "types", "SlumlordAccount");
+    let _exhaustive_fields = SlumlordAccount {
+        header,
+        loan: Slumlord { old_lamports: 0 },
+        loan_context: context,
+        pool_config: config,
+        pool_limits: limits,
+    };
+    assert_eq!(
+        idl_names(&slumlord_account["type"]["fields"]),
+        [
+            "header",
+            "loan",
+            "loan_context",
+            "pool_config",
+            "pool_limits"
+        ]
+    );
+    assert_eq!(
+        std::mem::size_of::<SlumlordAccount>(),
+        zero_fields_len(&slumlord_account["type"]["fields"]),
+        "SlumlordAccount layout mismatch"
+    );
+
+    let borrower_entry = idl_entry(&idl, "types", "BorrowerEntry");
+    let _exhaustive_fields = BorrowerEntry {
+        borrower: Pubkey::default(),
+    };
+    assert_eq!(idl_names(&borrower_entry["type"]["fields"]), ["borrower"]);
+    assert_eq!(
+        std::mem::size_of::<BorrowerEntry>(),
+        zero_fields_len(&borrower_entry["type"]["fields"]),
+        "BorrowerEntry layout mismatch"
+    );
+
+    let check_repaid_return = idl_entry(&idl, "types", "CheckRepaidReturn");
+    let zero = CheckRepaidReturn {
+        old_lamports: 0,
+        repaid: None,
+        surplus: 0,
+    };
+    assert_eq!(
+        idl_names(&check_repaid_return["type"]["fields"]),
+        ["old_lamports", "repaid", "surplus"]
+    );
+    assert_eq!(
+        zero.try_to_vec().unwrap().len(),
+        zero_fields_len(&check_repaid_return["type"]["fields"]),
+        "CheckRepaidReturn layout mismatch"
+    );
+
+    let quote_loan_return = idl_entry(&idl, "types", "QuoteLoanReturn");
+    let zero = QuoteLoanReturn {
+        loanable_lamports: 0,
+        fee_lamports: 0,
+        loan_active: false,
+    };
+    assert_eq!(
+        idl_names(&quote_loan_return["type"]["fields"]),
+        ["loanable_lamports", "fee_lamports", "loan_active"]
+    );
+    assert_eq!(
+        zero.try_to_vec().unwrap().len(),
+        zero_fields_len(&quote_loan_return["type"]["fields"]),
+        "QuoteLoanReturn layout mismatch"
+    );
+}
@@ -0,0 +1,193 @@
+use slumlord_interface::{borrow_ix, flash_loan_with_callback_ix, FlashLoanWithCallbackIxArgs};
+use slumlord_lib::{
+    borrow_split_ix_full, check_repaid_ix_full, detect_loan, BorrowFreeArgs,
+    FlashLoanWithCallbackFreeArgs, LoanDetection, SPLIT_LOAN_DST, UNKNOWN_BORROW_IX_INDEX,
+};
+use slumlord_test_utils::SlumlordAccountBuilder;
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    system_instruction, sysvar,
+    sysvar::instructions::{
+        construct_instructions_data, store_current_index, BorrowedAccountMeta, BorrowedInstruction,
+    },
+};
+
+/// Runs [`detect_loan`] for `account` with `ixs[curr_ix_idx]` as the current instruction
+/// and a slumlord account with no active loan
+fn detect(ixs: &[Instruction], curr_ix_idx: u16, account: &Pubkey) -> LoanDetection {
+    detect_with_slumlord(
+        ixs,
+        curr_ix_idx,
+        account,
+        &mut SlumlordAccountBuilder::unmigrated(1),
+    )
+}
+
+fn detect_with_slumlord(
+    ixs: &[Instruction],
+    curr_ix_idx: u16,
+    account: &Pubkey,
+    slumlord: &mut SlumlordAccountBuilder,
+) -> LoanDetection {
+    let borrowed_ixs: Vec<BorrowedInstruction> = ixs
+        .iter()
+        .map(|ix| BorrowedInstruction {
+            program_id: &ix.program_id,
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| BorrowedAccountMeta {
+                    pubkey: &meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: &ix.data,
+        })
+        .collect();
+    let mut data = construct_instructions_data(&borrowed_ixs);
+    store_current_index(&mut data, curr_ix_idx);
+    let mut lamports = 0;
+    let instructions = AccountInfo::new(
+        &sysvar::instructions::ID,
+        false,
+        false,
+        &mut lamports,
+        &mut data,
+        &sysvar::ID,
+        false,
+        0,
+    );
+    slumlord.with_account_info(|slumlord| detect_loan(&instructions, slumlord, account).unwrap())
+}
+
+/// Current version slumlord account with an active loan to `dst`
+/// started by a CPI-ed `FlashLoanWithCallback`
+fn cpi_flash_loan_slumlord(dst: &Pubkey) -> SlumlordAccountBuilder {
+    SlumlordAccountBuilder::migrated(1).loan(2, 1, dst, UNKNOWN_BORROW_IX_INDEX)
+}
+
+/// Stands in for the instruction of the program calling [`detect_loan`]
+fn caller_ix(account: &Pubkey) -> Instruction {
+    system_instruction::transfer(account, account, 0)
+}
+
+#[test]
+fn detect_preceding_borrow() {
+    let dst = Pubkey::new_unique();
+    let ixs = [
+        borrow_ix(BorrowFreeArgs { dst }).unwrap(),
+        caller_ix(&dst),
+        check_repaid_ix_full().unwrap(),
+    ];
+    let res = detect(&ixs, 1, &dst);
+    assert_eq!(
+        res,
+        LoanDetection {
+            borrow_ix_index: Some(0),
+            in_flash_loan_callback: false,
+            succeeding_check_repaid: true,
+            recorded_loan_to_account: false,
+        }
+    );
+    assert!(res.is_detected());
+
+    // other accounts not lent to
+    let other = Pubkey::new_unique();
+    let res = detect(&ixs, 1, &other);
+    assert!(!res.is_detected());
+    assert!(res.may_be_active());
+}
+
+#[test]
+fn detect_preceding_borrow_split() {
+    let dst = Pubkey::new_unique();
+    let ixs = [
+        borrow_split_ix_full(&[(Pubkey::new_unique(), 1), (dst, 1)]).unwrap(),
+        caller_ix(&dst),
+        check_repaid_ix_full().unwrap(),
+    ];
+    assert_eq!(detect(&ixs, 1, &dst).borrow_ix_index, Some(0));
+}
+
+#[test]
+fn no_detection_after_check_repaid() {
+    let dst = Pubkey::new_unique();
+    let ixs = [
+        borrow_ix(BorrowFreeArgs { dst }).unwrap(),
+        check_repaid_ix_full().unwrap(),
+        caller_ix(&dst),
+    ];
+    let res = detect(&ixs, 2, &dst);
+    assert_eq!(res, LoanDetection::default());
+    assert!(!res.may_be_active());
+}
+
+#[test]
+fn no_detection_before_borrow() {
+    let dst = Pubkey::new_unique();
+    let ixs = [
+        caller_ix(&dst),
+        borrow_ix(BorrowFreeArgs { dst }).unwrap(),
+        check_repaid_ix_full().unwrap(),
+    ];
+    let res = detect(&ixs, 0, &dst);
+    assert!(!res.is_detected());
+    // can't tell the CheckRepaid is for the later Borrow and not a CPI-ed loan
+    assert!(res.succeeding_check_repaid);
+}
+
+#[test]
+fn detect_flash_loan_callback() {
+    let receiver = Pubkey::new_unique();
+    let ixs = [flash_loan_with_callback_ix(
+        FlashLoanWithCallbackFreeArgs {
+            receiver,
+            receiver_program: Pubkey::new_unique(),
+            initiator: Pubkey::new_unique(),
+        },
+        FlashLoanWithCallbackIxArgs {
+            amount: 1,
+            data: Vec::new(),
+        },
+    )
+    .unwrap()];
+    let res = detect(&ixs, 0, &receiver);
+    assert!(res.in_flash_loan_callback);
+    assert!(res.is_detected());
+    assert!(!detect(&ixs, 0, &Pubkey::new_unique()).is_detected());
+}
+
+#[test]
+fn detect_cpi_flash_loan_from_slumlord_account() {
+    let receiver = Pubkey::new_unique();
+    // the CPI-ing program's instruction, with no CheckRepaid after it
+    let ixs = [caller_ix(&receiver)];
+
+    let res = detect_with_slumlord(&ixs, 0, &receiver, &mut cpi_flash_loan_slumlord(&receiver));
+    assert!(!res.is_detected());
+    assert!(!res.succeeding_check_repaid);
+    assert!(res.recorded_loan_to_account);
+    assert!(res.may_be_active());
+
+    let other = Pubkey::new_unique();
+    let res = detect_with_slumlord(&ixs, 0, &other, &mut cpi_flash_loan_slumlord(&receiver));
+    assert!(!res.may_be_active());
+
+    // no single dst recorded
+    let res = detect_with_slumlord(
+        &ixs,
+        0,
+        &other,
+        &mut cpi_flash_loan_slumlord(&SPLIT_LOAN_DST),
+    );
+    assert!(res.may_be_active());
+
+    // unmigrated accounts don't record the dst
+    let mut unmigrated = SlumlordAccountBuilder::unmigrated(1).loan(2, 1, &receiver, 0);
+    let res = detect_with_slumlord(&ixs, 0, &other, &mut unmigrated);
+    assert!(res.recorded_loan_to_account);
+    assert!(res.may_be_active());
+}
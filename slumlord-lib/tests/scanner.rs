@@ -0,0 +1,141 @@
+use slumlord_interface::{borrow_ix, BorrowSplitIxArgs, SlumlordProgramIx};
+use slumlord_lib::{
+    borrow_split_ix_full, check_repaid_ix_full, program::SLUMLORD_ID, BorrowFreeArgs,
+    ScannedSlumlordIx, SlumlordIxScanner,
+};
+use solana_program::{
+    instruction::AccountMeta,
+    message::Message,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::instructions::{construct_instructions_data, BorrowedAccountMeta, BorrowedInstruction},
+};
+
+/// Serializes `message`'s instructions as the runtime does for the instructions sysvar
+fn instructions_sysvar_data(message: &Message) -> Vec<u8> {
+    let borrowed_ixs: Vec<BorrowedInstruction> = message
+        .instructions
+        .iter()
+        .map(|ix| BorrowedInstruction {
+            program_id: &message.account_keys[usize::from(ix.program_id_index)],
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|i| BorrowedAccountMeta {
+                    pubkey: &message.account_keys[usize::from(*i)],
+                    is_signer: message.is_signer(usize::from(*i)),
+                    is_writable: message.is_writable(usize::from(*i)),
+                })
+                .collect(),
+            data: &ix.data,
+        })
+        .collect();
+    construct_instructions_data(&borrowed_ixs)
+}
+
+fn accounts(ix: &ScannedSlumlordIx) -> Vec<AccountMeta> {
+    (0..ix.accounts_len())
+        .map(|i| ix.account(i).unwrap())
+        .collect()
+}
+
+#[test]
+fn scan_sysvar_and_message() {
+    let payer = Pubkey::new_unique();
+    let dst = Pubkey::new_unique();
+    let borrow = borrow_ix(BorrowFreeArgs { dst }).unwrap();
+    let borrow_split = borrow_split_ix_full(&[(dst, 1), (payer, 2)]).unwrap();
+    let check_repaid = check_repaid_ix_full().unwrap();
+    let message = Message::new(
+        &[
+            system_instruction::transfer(&payer, &dst, 1),
+            borrow.clone(),
+            system_instruction::transfer(&payer, &dst, 1),
+            borrow_split.clone(),
+            check_repaid.clone(),
+        ],
+        Some(&payer),
+    );
+    let sysvar_data = instructions_sysvar_data(&message);
+
+    let from_message: Vec<ScannedSlumlordIx> = SlumlordIxScanner::from_message(&message).collect();
+    let from_sysvar: Vec<ScannedSlumlordIx> =
+        SlumlordIxScanner::from_instructions_sysvar(&sysvar_data)
+            .unwrap()
+            .collect();
+
+    for scanned in [&from_message, &from_sysvar] {
+        let indices: Vec<u16> = scanned.iter().map(|ix| ix.index).collect();
+        assert_eq!(indices, [1, 3, 4]);
+        for (ix, expected) in scanned.iter().zip([&borrow, &borrow_split, &check_repaid]) {
+            assert_eq!(ix.data, expected.data);
+            assert_eq!(
+                ix.keys().collect::<Vec<_>>(),
+                expected
+                    .accounts
+                    .iter()
+                    .map(|meta| meta.pubkey)
+                    .collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(scanned[0].key(1), Some(dst));
+        assert!(matches!(
+            scanned[0].ix().unwrap(),
+            SlumlordProgramIx::Borrow
+        ));
+        assert_eq!(
+            scanned[1].ix().unwrap(),
+            SlumlordProgramIx::BorrowSplit(BorrowSplitIxArgs {
+                amounts: vec![1, 2]
+            })
+        );
+        assert!(!scanned[0].is_check_repaid());
+        assert!(scanned[2].is_check_repaid());
+        assert!(scanned[2].is_check_repaid_for(&SLUMLORD_ID));
+        assert!(!scanned[2].is_check_repaid_for(&payer));
+    }
+    for (a, b) in from_message.iter().zip(from_sysvar.iter()) {
+        assert_eq!(accounts(a), accounts(b));
+    }
+}
+
+#[test]
+fn scan_starting_at() {
+    let payer = Pubkey::new_unique();
+    let message = Message::new(
+        &[
+            check_repaid_ix_full().unwrap(),
+            borrow_ix(BorrowFreeArgs { dst: payer }).unwrap(),
+            check_repaid_ix_full().unwrap(),
+        ],
+        Some(&payer),
+    );
+    let sysvar_data = instructions_sysvar_data(&message);
+    let indices: Vec<u16> = SlumlordIxScanner::from_instructions_sysvar(&sysvar_data)
+        .unwrap()
+        .starting_at(1)
+        .map(|ix| ix.index)
+        .collect();
+    assert_eq!(indices, [1, 2]);
+    assert_eq!(
+        SlumlordIxScanner::from_message(&message)
+            .starting_at(3)
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn scan_malformed_sysvar() {
+    assert!(SlumlordIxScanner::from_instructions_sysvar(&[1]).is_err());
+    let payer = Pubkey::new_unique();
+    let message = Message::new(&[check_repaid_ix_full().unwrap()], Some(&payer));
+    let sysvar_data = instructions_sysvar_data(&message);
+    let truncated = &sysvar_data[..sysvar_data.len() - 4];
+    assert_eq!(
+        SlumlordIxScanner::from_instructions_sysvar(truncated)
+            .unwrap()
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,325 @@
+//! Tests of [`Simulator`] on the instructions the fuzz tests don't generate
+
+use slumlord_interface::{
+    add_borrower_ix, borrow_ix, borrow_with_min_ix, migrate_ix, remove_borrower_ix, repay_ix,
+    set_admin_ix, set_allowlist_ix, set_pool_limits_ix, BorrowWithMinIxArgs,
+    FlashLoanWithCallbackIxArgs, OnFlashLoanIxData, SetAllowlistIxArgs, SetPoolLimitsIxArgs,
+    SlumlordError,
+};
+use slumlord_lib::{
+    borrower_account_metas, check_repaid_ix_full, find_borrower_entry_address,
+    flash_loan_with_callback_ix_full, program, program_data_address, repay_direct,
+    simulator::{SimAccount, SimulationError, Simulator},
+    AddBorrowerFreeArgs, BorrowFreeArgs, FlashLoanWithCallbackFreeArgs, MigrateFreeArgs,
+    RemoveBorrowerFreeArgs, RepayDirectAccounts, RepayFreeArgs, SetAdminFreeArgs,
+    SetAllowlistFreeArgs, SetPoolLimitsFreeArgs,
+};
+use slumlord_test_utils::{program_data_account, SlumlordAccountBuilder};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    message::Message,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+// 1 SOL
+const SLUMLORD_LAMPORTS: u64 = 1_000_000_000;
+
+const PAYER_LAMPORTS: u64 = 10_000_000_000;
+
+const RECEIVER_LAMPORTS: u64 = 1_000_000_000;
+
+const RECEIVER_PROGRAM: Pubkey = Pubkey::new_from_array([1; 32]);
+
+const RECEIVER: Pubkey = Pubkey::new_from_array([2; 32]);
+
+const REPAY: u8 = 0;
+
+const NO_REPAY: u8 = 1;
+
+/// `OnFlashLoan` of a receiver program that repays directly from `receiver` if `data` is `[REPAY]`,
+/// requiring `initiator` to sign
+fn process_on_flash_loan(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let args = OnFlashLoanIxData::deserialize(instruction_data)?.0;
+    let [slumlord, receiver, initiator, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if !initiator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if args.data == [REPAY] {
+        repay_direct(RepayDirectAccounts {
+            slumlord,
+            src: receiver,
+        })?;
+    }
+    Ok(())
+}
+
+fn simulator(payer: Pubkey, admin: Pubkey) -> Simulator {
+    let mut simulator = Simulator::new();
+    simulator.set_account(
+        program::SLUMLORD_ID,
+        SlumlordAccountBuilder::unmigrated(SLUMLORD_LAMPORTS)
+            .into_account()
+            .into(),
+    );
+    simulator.set_account(
+        payer,
+        SimAccount {
+            lamports: PAYER_LAMPORTS,
+            ..Default::default()
+        },
+    );
+    simulator.set_account(program_data_address(), program_data_account(admin).into());
+    simulator.set_account(
+        RECEIVER,
+        SimAccount {
+            lamports: RECEIVER_LAMPORTS,
+            data: Vec::new(),
+            owner: RECEIVER_PROGRAM,
+        },
+    );
+    simulator
+}
+
+/// Migrates the slumlord account and makes `admin` its admin
+fn setup_admin_ixs(payer: Pubkey, admin: Pubkey) -> [Instruction; 2] {
+    [
+        migrate_ix(MigrateFreeArgs { payer }).unwrap(),
+        set_admin_ix(SetAdminFreeArgs {
+            authority: admin,
+            new_admin: admin,
+        })
+        .unwrap(),
+    ]
+}
+
+fn borrow_repay_check_repaid_ixs(payer: Pubkey, borrower: Option<Pubkey>) -> [Instruction; 3] {
+    let mut borrow = borrow_ix(BorrowFreeArgs { dst: payer }).unwrap();
+    if let Some(borrower) = borrower {
+        borrow.accounts.extend(borrower_account_metas(borrower));
+    }
+    [
+        borrow,
+        repay_ix(RepayFreeArgs { src: payer }).unwrap(),
+        check_repaid_ix_full().unwrap(),
+    ]
+}
+
+fn custom_err(ix_idx: u8, err: SlumlordError) -> SimulationError {
+    SimulationError::InstructionError(ix_idx, InstructionError::Custom(err as u32))
+}
+
+#[test]
+fn allowlist_admin_instructions() {
+    let payer = Pubkey::new_unique();
+    let admin = Pubkey::new_unique();
+    let borrower = Pubkey::new_unique();
+    let mut simulator = simulator(payer, admin);
+
+    let mut ixs = setup_admin_ixs(payer, admin).to_vec();
+    ixs.push(
+        set_allowlist_ix(
+            SetAllowlistFreeArgs { admin },
+            SetAllowlistIxArgs { enabled: true },
+        )
+        .unwrap(),
+    );
+    ixs.push(
+        add_borrower_ix(AddBorrowerFreeArgs {
+            admin,
+            payer,
+            borrower,
+        })
+        .unwrap(),
+    );
+    simulator
+        .process_message(&Message::new(&ixs, Some(&payer)))
+        .unwrap();
+    let (borrower_entry, _bump) = find_borrower_entry_address(&borrower);
+    let entry_lamports = simulator.account(&borrower_entry).unwrap().lamports;
+    assert_eq!(
+        simulator.account(&borrower_entry).unwrap().owner,
+        program::ID
+    );
+
+    let ixs = borrow_repay_check_repaid_ixs(payer, None);
+    assert_eq!(
+        simulator.simulate_message(&Message::new(&ixs, Some(&payer))),
+        Err(custom_err(0, SlumlordError::BorrowerNotAllowed))
+    );
+    let ixs = borrow_repay_check_repaid_ixs(payer, Some(borrower));
+    simulator
+        .simulate_message(&Message::new(&ixs, Some(&payer)))
+        .unwrap();
+
+    let remove_borrower = remove_borrower_ix(RemoveBorrowerFreeArgs {
+        admin,
+        borrower,
+        refund_rent_to: payer,
+    })
+    .unwrap();
+    let res = simulator
+        .process_message(&Message::new(&[remove_borrower], Some(&payer)))
+        .unwrap();
+    assert!(!res.accounts.contains_key(&borrower_entry));
+    assert_eq!(res.lamport_deltas[&payer], i128::from(entry_lamports));
+    assert!(simulator.account(&borrower_entry).is_none());
+
+    let ixs = borrow_repay_check_repaid_ixs(payer, Some(borrower));
+    assert_eq!(
+        simulator.simulate_message(&Message::new(&ixs, Some(&payer))),
+        Err(custom_err(0, SlumlordError::BorrowerNotAllowed))
+    );
+}
+
+#[test]
+fn admin_instructions_not_admin_fail() {
+    let payer = Pubkey::new_unique();
+    let admin = Pubkey::new_unique();
+    let simulator = simulator(payer, admin);
+
+    let mut ixs = setup_admin_ixs(payer, payer).to_vec();
+    assert_eq!(
+        simulator.simulate_message(&Message::new(&ixs, Some(&payer))),
+        Err(custom_err(1, SlumlordError::NotAdmin))
+    );
+
+    ixs = setup_admin_ixs(payer, admin).to_vec();
+    ixs.push(
+        set_pool_limits_ix(
+            SetPoolLimitsFreeArgs { admin: payer },
+            SetPoolLimitsIxArgs {
+                max_borrow_lamports: 1,
+                reserve_lamports: 0,
+            },
+        )
+        .unwrap(),
+    );
+    assert_eq!(
+        simulator.simulate_message(&Message::new(&ixs, Some(&payer))),
+        Err(custom_err(2, SlumlordError::NotAdmin))
+    );
+}
+
+#[test]
+fn pool_limits_cap_borrow() {
+    let payer = Pubkey::new_unique();
+    let admin = Pubkey::new_unique();
+    let max_borrow_lamports = SLUMLORD_LAMPORTS / 2;
+    let mut simulator = simulator(payer, admin);
+
+    let mut ixs = setup_admin_ixs(payer, admin).to_vec();
+    ixs.push(
+        set_pool_limits_ix(
+            SetPoolLimitsFreeArgs { admin },
+            SetPoolLimitsIxArgs {
+                max_borrow_lamports,
+                reserve_lamports: 0,
+            },
+        )
+        .unwrap(),
+    );
+    simulator
+        .process_message(&Message::new(&ixs, Some(&payer)))
+        .unwrap();
+
+    for (min_lamports, expected) in [
+        (max_borrow_lamports, Ok(())),
+        (
+            max_borrow_lamports + 1,
+            Err(custom_err(0, SlumlordError::InsufficientLiquidity)),
+        ),
+    ] {
+        let ixs = [
+            borrow_with_min_ix(
+                BorrowFreeArgs { dst: payer },
+                BorrowWithMinIxArgs { min_lamports },
+            )
+            .unwrap(),
+            repay_ix(RepayFreeArgs { src: payer }).unwrap(),
+            check_repaid_ix_full().unwrap(),
+        ];
+        assert_eq!(
+            simulator
+                .simulate_message(&Message::new(&ixs, Some(&payer)))
+                .map(|_res| ()),
+            expected
+        );
+    }
+}
+
+fn flash_loan_ix(initiator: Pubkey, mode: u8) -> Instruction {
+    flash_loan_with_callback_ix_full(
+        FlashLoanWithCallbackFreeArgs {
+            receiver: RECEIVER,
+            receiver_program: RECEIVER_PROGRAM,
+            initiator,
+        },
+        FlashLoanWithCallbackIxArgs {
+            amount: SLUMLORD_LAMPORTS - 1,
+            data: vec![mode],
+        },
+        &[],
+    )
+    .unwrap()
+}
+
+#[test]
+fn flash_loan_with_callback() {
+    let payer = Pubkey::new_unique();
+    let mut simulator = simulator(payer, Pubkey::new_unique());
+    let repay = Message::new(&[flash_loan_ix(payer, REPAY)], Some(&payer));
+    assert_eq!(
+        simulator.simulate_message(&repay),
+        Err(SimulationError::UnsupportedInstruction(0))
+    );
+
+    simulator.add_program(RECEIVER_PROGRAM, process_on_flash_loan);
+    let res = simulator.simulate_message(&repay).unwrap();
+    assert_eq!(res.lamport_deltas[&program::SLUMLORD_ID], 0);
+    assert_eq!(res.lamport_deltas[&RECEIVER], 0);
+    assert!(res.accounts[&program::SLUMLORD_ID].data.is_empty());
+
+    let no_repay = Message::new(&[flash_loan_ix(payer, NO_REPAY)], Some(&payer));
+    assert_eq!(
+        simulator.simulate_message(&no_repay),
+        Err(custom_err(0, SlumlordError::InsufficientRepay))
+    );
+}
+
+#[test]
+fn program_ix_external_account_spend_fail() {
+    let payer = Pubkey::new_unique();
+    let mut simulator = simulator(payer, Pubkey::new_unique());
+    simulator.add_program(
+        RECEIVER_PROGRAM,
+        |_program_id, accounts, _instruction_data| {
+            **accounts[0].try_borrow_mut_lamports()? -= 1;
+            **accounts[1].try_borrow_mut_lamports()? += 1;
+            Ok(())
+        },
+    );
+    let ix = Instruction::new_with_bytes(
+        RECEIVER_PROGRAM,
+        &[],
+        vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new(RECEIVER, false),
+        ],
+    );
+    assert_eq!(
+        simulator.simulate_message(&Message::new(&[ix], Some(&payer))),
+        Err(SimulationError::InstructionError(
+            0,
+            InstructionError::ExternalAccountLamportSpend
+        ))
+    );
+}
@@ -0,0 +1,206 @@
+//! A lightweight alternative to [`ProgramTest`](solana_program_test::ProgramTest)
+//! that runs transactions synchronously against an in-process [`Bank`]
+//! without starting a banks server.
+//!
+//! The slumlord program is added with its native processor unless
+//! `BPF_OUT_DIR` or `SBF_OUT_DIR` is set, e.g. by `cargo test-sbf`,
+//! in which case `slumlord.so` is loaded instead.
+//! Other programs must be added with their native processors.
+
+use std::sync::Once;
+
+use async_trait::async_trait;
+use solana_program_runtime::{
+    invoke_context::ProcessInstructionWithContext, loaded_programs::LoadedProgram,
+};
+use solana_program_test::{find_file, processor, read_file, BanksClientError, ProgramTest};
+use solana_runtime::{bank::Bank, genesis_utils::create_genesis_config};
+use solana_sdk::{
+    account::Account,
+    bpf_loader,
+    feature_set::native_programs_consume_cu,
+    hash::Hash,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    transaction::{SanitizedTransaction, Transaction, TransactionError},
+};
+
+use crate::{SlumlordProgramTest, SlumlordTestClient, SlumlordTestHarness};
+
+pub const SLUMLORD_SO_FILENAME: &str = "slumlord.so";
+
+// 1M SOL
+const PAYER_LAMPORTS: u64 = 1_000_000_000_000_000;
+
+#[derive(Default)]
+pub struct BankTest {
+    accounts: Vec<(Pubkey, Account)>,
+    native_programs: Vec<(&'static str, Pubkey, ProcessInstructionWithContext)>,
+}
+
+impl BankTest {
+    pub fn add_account(mut self, pubkey: Pubkey, account: Account) -> Self {
+        self.accounts.push((pubkey, account));
+        self
+    }
+
+    pub fn start(self) -> (BankTestClient, Keypair, Hash) {
+        install_syscall_stubs();
+        let mut genesis = create_genesis_config(PAYER_LAMPORTS);
+        // same as ProgramTest, rent is charged and
+        // native processors don't consume compute units
+        genesis.genesis_config.rent = Rent::default();
+        genesis
+            .genesis_config
+            .accounts
+            .remove(&native_programs_consume_cu::id());
+        genesis.genesis_config.accounts.extend(self.accounts);
+        let mut bank = Bank::new_for_tests(&genesis.genesis_config);
+        for (program_name, program_id, processor) in self.native_programs {
+            bank.add_builtin(
+                program_id,
+                program_name.to_owned(),
+                LoadedProgram::new_builtin(0, program_name.len(), processor),
+            );
+        }
+        let last_blockhash = bank.last_blockhash();
+        (
+            BankTestClient { bank },
+            genesis.mint_keypair,
+            last_blockhash,
+        )
+    }
+}
+
+/// Native processors rely on [`ProgramTest`]'s syscall stubs for CPIs,
+/// sysvars and return data. These are private and only installed
+/// when a [`ProgramTest`] bank is first set up, so set one up and discard it.
+fn install_syscall_stubs() {
+    static ONCE: Once = Once::new();
+
+    ONCE.call_once(|| {
+        // on a separate thread since the caller may already be in a tokio runtime
+        std::thread::spawn(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(ProgramTest::default().start());
+        })
+        .join()
+        .unwrap();
+    });
+}
+
+/// Same as [`ProgramTest`]'s default preference
+fn prefer_bpf() -> bool {
+    std::env::var("BPF_OUT_DIR").is_ok() || std::env::var("SBF_OUT_DIR").is_ok()
+}
+
+impl SlumlordProgramTest for BankTest {
+    fn add_slumlord_program(self) -> Self {
+        if prefer_bpf() {
+            return self.add_slumlord_program_bpf();
+        }
+        self.add_native_program(
+            "slumlord",
+            slumlord_lib::program::ID,
+            processor!(slumlord::process_instruction),
+        )
+    }
+
+    fn add_slumlord_program_bpf(self) -> Self {
+        let path = find_file(SLUMLORD_SO_FILENAME)
+            .unwrap_or_else(|| panic!("{SLUMLORD_SO_FILENAME} not found"));
+        let data = read_file(path);
+        let account = Account {
+            lamports: Rent::default().minimum_balance(data.len()).max(1),
+            data,
+            owner: bpf_loader::ID,
+            executable: true,
+            rent_epoch: 0,
+        };
+        self.add_account(slumlord_lib::program::ID, account)
+    }
+
+    fn add_slumlord(self, lamports: u64) -> Self {
+        self.add_account(
+            slumlord_lib::program::SLUMLORD_ID,
+            crate::slumlord_account(lamports),
+        )
+    }
+
+    fn add_uninitialized_slumlord(self, lamports: u64) -> Self {
+        self.add_account(
+            slumlord_lib::program::SLUMLORD_ID,
+            crate::uninitialized_slumlord_account(lamports),
+        )
+    }
+
+    fn add_slumlord_program_data(self, upgrade_authority: Pubkey) -> Self {
+        self.add_account(
+            slumlord_lib::program_data_address(),
+            crate::program_data_account(upgrade_authority),
+        )
+    }
+
+    fn add_native_program(
+        mut self,
+        program_name: &'static str,
+        program_id: Pubkey,
+        processor: Option<ProcessInstructionWithContext>,
+    ) -> Self {
+        let processor =
+            processor.unwrap_or_else(|| panic!("{program_name} has no native processor"));
+        self.native_programs
+            .push((program_name, program_id, processor));
+        self
+    }
+
+    fn add_test_account(self, pubkey: Pubkey, account: Account) -> Self {
+        self.add_account(pubkey, account)
+    }
+}
+
+#[async_trait(?Send)]
+impl SlumlordTestHarness for BankTest {
+    type Client = BankTestClient;
+
+    async fn start_harness(self) -> (Self::Client, Keypair, Hash) {
+        self.start()
+    }
+}
+
+pub struct BankTestClient {
+    bank: Bank,
+}
+
+impl BankTestClient {
+    pub fn bank(&self) -> &Bank {
+        &self.bank
+    }
+}
+
+#[async_trait]
+impl SlumlordTestClient for BankTestClient {
+    async fn process_transaction(&mut self, tx: Transaction) -> Result<(), BanksClientError> {
+        self.bank
+            .process_transaction(&tx)
+            .map_err(BanksClientError::TransactionError)
+    }
+
+    async fn get_account(&mut self, pubkey: Pubkey) -> Option<Account> {
+        self.bank.get_account(&pubkey).map(Account::from)
+    }
+
+    async fn simulate_transaction(
+        &mut self,
+        tx: Transaction,
+    ) -> (Result<(), TransactionError>, Vec<String>) {
+        let res = self
+            .bank
+            .simulate_transaction_unchecked(SanitizedTransaction::from_transaction_for_tests(tx));
+        (res.result, res.logs)
+    }
+}
@@ -0,0 +1,97 @@
+//! Slumlord account fixtures for testing `slumlord_lib`'s
+//! account data functions without running the program.
+
+use slumlord_interface::PoolLimits;
+use slumlord_lib::{
+    migrate_slumlord_data, program, record_loan, start_loan, try_pool_limits_mut,
+    LEGACY_SLUMLORD_ACCOUNT_LEN, SLUMLORD_ACCOUNT_LEN,
+};
+use solana_sdk::{account::Account, account_info::AccountInfo, pubkey::Pubkey};
+
+/// Builds an initialized slumlord account in any supported layout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlumlordAccountBuilder {
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+impl SlumlordAccountBuilder {
+    /// Slumlord account with arbitrary `data`, e.g. of a previous version
+    pub fn new(lamports: u64, data: Vec<u8>) -> Self {
+        Self { lamports, data }
+    }
+
+    /// Slumlord account that has not been migrated,
+    /// with the empty data `Init` leaves it with
+    pub fn unmigrated(lamports: u64) -> Self {
+        Self::new(lamports, Vec::new())
+    }
+
+    /// Current version slumlord account with no active loan,
+    /// no admin, allowlist mode disabled and no pool limits
+    pub fn migrated(lamports: u64) -> Self {
+        let mut data = vec![0; SLUMLORD_ACCOUNT_LEN];
+        migrate_slumlord_data(&mut data, 0).unwrap();
+        Self::new(lamports, data)
+    }
+
+    /// Sets the pool limits of a migrated slumlord account
+    pub fn pool_limits(mut self, max_borrow_lamports: u64, reserve_lamports: u64) -> Self {
+        *try_pool_limits_mut(&mut self.data).unwrap().unwrap() = PoolLimits {
+            max_borrow_lamports,
+            reserve_lamports,
+        };
+        self
+    }
+
+    /// Starts a loan as the borrow instructions do,
+    /// reallocating unmigrated data to the legacy loan state
+    pub fn loan(
+        mut self,
+        old_lamports: u64,
+        lent_lamports: u64,
+        dst: &Pubkey,
+        borrow_ix_index: u16,
+    ) -> Self {
+        if self.data.is_empty() {
+            self.data.resize(LEGACY_SLUMLORD_ACCOUNT_LEN, 0);
+        }
+        start_loan(&mut self.data, old_lamports).unwrap();
+        record_loan(&mut self.data, lent_lamports, dst, borrow_ix_index).unwrap();
+        self
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut Vec<u8> {
+        &mut self.data
+    }
+
+    /// Calls `f` with the slumlord [`AccountInfo`], which shares this builder's data
+    pub fn with_account_info<T>(&mut self, f: impl FnOnce(&AccountInfo) -> T) -> T {
+        let mut lamports = self.lamports;
+        let account = AccountInfo::new(
+            &program::SLUMLORD_ID,
+            false,
+            true,
+            &mut lamports,
+            &mut self.data,
+            &program::ID,
+            false,
+            0,
+        );
+        f(&account)
+    }
+
+    pub fn into_account(self) -> Account {
+        Account {
+            lamports: self.lamports,
+            data: self.data,
+            owner: program::ID,
+            executable: false,
+            rent_epoch: u64::MAX,
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use async_trait::async_trait;
+use sanctum_solana_test_utils::ExtendedProgramTest;
+use slumlord_lib::{
+    is_loan_active, program::SLUMLORD_ID, program_data_address, slumlord_account_version,
+    try_slumlord,
+};
+use solana_program_runtime::invoke_context::ProcessInstructionWithContext;
+use solana_program_test::{processor, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    hash::Hash,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+mod bank;
+mod data;
+
+pub use bank::*;
+pub use data::*;
+
+pub trait SlumlordProgramTest {
+    /// Adds the slumlord program with its native processor.
+    ///
+    /// `slumlord.so` is loaded instead if `prefer_bpf` is set,
+    /// e.g. when running with `cargo test-sbf`
+    fn add_slumlord_program(self) -> Self;
+
+    /// Adds the slumlord program from `slumlord.so` in the
+    /// [`ProgramTest`] search paths, e.g. `tests/fixtures`.
+    /// Panics if the file can't be found
+    fn add_slumlord_program_bpf(self) -> Self;
+
+    /// Adds an initialized slumlord account with no active loan
+    fn add_slumlord(self, lamports: u64) -> Self;
+
+    /// Adds a funded slumlord account that `Init` has not been run on
+    fn add_uninitialized_slumlord(self, lamports: u64) -> Self;
+
+    /// Adds the slumlord program's program data account recording `upgrade_authority`,
+    /// which `SetAdmin` checks when no admin has been set
+    fn add_slumlord_program_data(self, upgrade_authority: Pubkey) -> Self;
+
+    /// Adds a test program with its native processor, e.g. a CPI caller.
+    ///
+    /// Always native since there is no `.so` built for test programs
+    fn add_native_program(
+        self,
+        program_name: &'static str,
+        program_id: Pubkey,
+        processor: Option<ProcessInstructionWithContext>,
+    ) -> Self;
+
+    fn add_test_account(self, pubkey: Pubkey, account: Account) -> Self;
+}
+
+impl SlumlordProgramTest for ProgramTest {
+    fn add_slumlord_program(mut self) -> Self {
+        self.add_program(
+            "slumlord",
+            slumlord_lib::program::ID,
+            processor!(slumlord::process_instruction),
+        );
+        self
+    }
+
+    fn add_slumlord_program_bpf(mut self) -> Self {
+        self.add_program("slumlord", slumlord_lib::program::ID, None);
+        self
+    }
+
+    fn add_slumlord(self, lamports: u64) -> Self {
+        self.add_account_chained(SLUMLORD_ID, slumlord_account(lamports))
+    }
+
+    fn add_uninitialized_slumlord(self, lamports: u64) -> Self {
+        self.add_account_chained(SLUMLORD_ID, uninitialized_slumlord_account(lamports))
+    }
+
+    fn add_slumlord_program_data(self, upgrade_authority: Pubkey) -> Self {
+        self.add_account_chained(
+            program_data_address(),
+            program_data_account(upgrade_authority),
+        )
+    }
+
+    fn add_native_program(
+        mut self,
+        program_name: &'static str,
+        program_id: Pubkey,
+        processor: Option<ProcessInstructionWithContext>,
+    ) -> Self {
+        // This line needs to come before add_program() to take effect
+        self.prefer_bpf(false);
+        self.add_program(program_name, program_id, processor);
+        self
+    }
+
+    fn add_test_account(self, pubkey: Pubkey, account: Account) -> Self {
+        self.add_account_chained(pubkey, account)
+    }
+}
+
+fn slumlord_account(lamports: u64) -> Account {
+    SlumlordAccountBuilder::unmigrated(lamports).into_account()
+}
+
+/// The slumlord program's program data account, recording `upgrade_authority`
+pub fn program_data_account(upgrade_authority: Pubkey) -> Account {
+    let state = UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(upgrade_authority),
+    };
+    let lamports =
+        Rent::default().minimum_balance(UpgradeableLoaderState::size_of_programdata_metadata());
+    Account::new_data(lamports, &state, &bpf_loader_upgradeable::ID).unwrap()
+}
+
+fn uninitialized_slumlord_account(lamports: u64) -> Account {
+    Account {
+        owner: system_program::ID,
+        ..slumlord_account(lamports)
+    }
+}
+
+/// Asserts that `err` is the transaction failing at instruction `ix_index` with `expected`,
+/// for errors that aren't slumlord's custom errors
+pub fn assert_instruction_err(err: BanksClientError, ix_index: u8, expected: InstructionError) {
+    let actual = match err {
+        BanksClientError::TransactionError(e)
+        | BanksClientError::SimulationError { err: e, .. } => e,
+        e => panic!("unexpected error {e:?}"),
+    };
+    assert_eq!(
+        actual,
+        TransactionError::InstructionError(ix_index, expected)
+    );
+}
+
+/// A test environment that can be set up with [`SlumlordProgramTest`]
+/// and started to run transactions against.
+///
+/// Allows the same test to run under both [`ProgramTest`] and [`BankTest`]
+#[async_trait(?Send)]
+pub trait SlumlordTestHarness: SlumlordProgramTest + Default {
+    type Client: SlumlordTestClient;
+
+    async fn start_harness(self) -> (Self::Client, Keypair, Hash);
+}
+
+#[async_trait(?Send)]
+impl SlumlordTestHarness for ProgramTest {
+    type Client = BanksClient;
+
+    async fn start_harness(self) -> (Self::Client, Keypair, Hash) {
+        self.start().await
+    }
+}
+
+#[async_trait]
+pub trait SlumlordTestClient: Send {
+    async fn process_transaction(&mut self, tx: Transaction) -> Result<(), BanksClientError>;
+
+    async fn get_account(&mut self, pubkey: Pubkey) -> Option<Account>;
+
+    /// Simulates `tx`, returning its result and program logs
+    async fn simulate_transaction(
+        &mut self,
+        tx: Transaction,
+    ) -> (Result<(), TransactionError>, Vec<String>);
+}
+
+#[async_trait]
+impl SlumlordTestClient for BanksClient {
+    async fn process_transaction(&mut self, tx: Transaction) -> Result<(), BanksClientError> {
+        BanksClient::process_transaction(self, tx).await
+    }
+
+    async fn get_account(&mut self, pubkey: Pubkey) -> Option<Account> {
+        BanksClient::get_account(self, pubkey).await.unwrap()
+    }
+
+    async fn simulate_transaction(
+        &mut self,
+        tx: Transaction,
+    ) -> (Result<(), TransactionError>, Vec<String>) {
+        let res = BanksClient::simulate_transaction(self, tx).await.unwrap();
+        let logs = res
+            .simulation_details
+            .map(|details| details.logs)
+            .unwrap_or_default();
+        (res.result.unwrap(), logs)
+    }
+}
+
+#[async_trait]
+pub trait SlumlordBanksClient {
+    async fn get_slumlord_acc(&mut self) -> Account;
+
+    /// Returns the `old_lamports` recorded for the active loan,
+    /// `None` if there is no active loan
+    async fn get_slumlord_loan_old_lamports(&mut self) -> Option<u64>;
+
+    async fn assert_slumlord_not_exist(&mut self);
+
+    async fn assert_slumlord_data_empty(&mut self);
+
+    /// Asserts the slumlord account's layout version,
+    /// `None` if it has not been migrated
+    async fn assert_slumlord_version(&mut self, expected_version: Option<u8>);
+
+    async fn assert_slumlord_initialized(&mut self);
+
+    async fn assert_slumlord_balance(&mut self, expected_lamports: u64);
+
+    async fn assert_slumlord_loan_active(&mut self, expected_old_lamports: u64);
+}
+
+#[async_trait]
+impl<T: SlumlordTestClient> SlumlordBanksClient for T {
+    async fn get_slumlord_acc(&mut self) -> Account {
+        self.get_account(SLUMLORD_ID)
+            .await
+            .expect("slumlord account not found")
+    }
+
+    async fn get_slumlord_loan_old_lamports(&mut self) -> Option<u64> {
+        let slumlord = self.get_slumlord_acc().await;
+        if !is_loan_active(&slumlord.data).unwrap() {
+            return None;
+        }
+        Some(try_slumlord(&slumlord.data).unwrap().old_lamports)
+    }
+
+    async fn assert_slumlord_not_exist(&mut self) {
+        assert!(self.get_account(SLUMLORD_ID).await.is_none());
+    }
+
+    async fn assert_slumlord_data_empty(&mut self) {
+        let slumlord = self.get_slumlord_acc().await;
+        assert!(slumlord.data.is_empty());
+    }
+
+    async fn assert_slumlord_version(&mut self, expected_version: Option<u8>) {
+        let slumlord = self.get_slumlord_acc().await;
+        let actual_version = slumlord_account_version(&slumlord.data).unwrap();
+        assert_eq!(
+            actual_version, expected_version,
+            "expected version {expected_version:?}, got {actual_version:?}",
+        );
+    }
+
+    async fn assert_slumlord_initialized(&mut self) {
+        let slumlord = self.get_slumlord_acc().await;
+        assert_eq!(slumlord.owner, slumlord_lib::program::ID);
+    }
+
+    async fn assert_slumlord_balance(&mut self, expected_lamports: u64) {
+        let slumlord = self.get_slumlord_acc().await;
+        let actual_lamports = slumlord.lamports;
+        assert_eq!(
+            actual_lamports, expected_lamports,
+            "expected {expected_lamports}, got {actual_lamports}",
+        );
+    }
+
+    async fn assert_slumlord_loan_active(&mut self, expected_old_lamports: u64) {
+        let actual_old_lamports = self
+            .get_slumlord_loan_old_lamports()
+            .await
+            .expect("no active loan");
+        assert_eq!(
+            actual_old_lamports, expected_old_lamports,
+            "expected old_lamports {expected_old_lamports}, got {actual_old_lamports}",
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Entrypoint that only deserializes the accounts each instruction uses
+//! and does not log the deserialized instruction.
+//!
+//! Enabled with the `lazy-entrypoint` feature.
+//!
+//! Adapted from [`solana_program::entrypoint::deserialize`],
+//! see there for the serialized input layout.
+
+use std::{
+    cell::RefCell,
+    mem::size_of,
+    rc::Rc,
+    slice::{from_raw_parts, from_raw_parts_mut},
+};
+
+use slumlord_interface::{
+    SlumlordProgramIx, ADD_BORROWER_IX_ACCOUNTS_LEN, BORROW_IX_ACCOUNTS_LEN,
+    BORROW_SPLIT_IX_ACCOUNTS_LEN, BORROW_WITH_MIN_IX_ACCOUNTS_LEN, CHECK_REPAID_IX_ACCOUNTS_LEN,
+    INIT_IX_ACCOUNTS_LEN, MIGRATE_IX_ACCOUNTS_LEN, QUOTE_LOAN_IX_ACCOUNTS_LEN,
+    REMOVE_BORROWER_IX_ACCOUNTS_LEN, REPAY_IX_ACCOUNTS_LEN, REPAY_WITH_MAX_IX_ACCOUNTS_LEN,
+    SET_ADMIN_IX_ACCOUNTS_LEN, SET_ALLOWLIST_IX_ACCOUNTS_LEN, SET_POOL_LIMITS_IX_ACCOUNTS_LEN,
+};
+use slumlord_lib::BORROWER_ACCOUNTS_LEN;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{
+        ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE, NON_DUP_MARKER, SUCCESS,
+    },
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// # Safety
+/// Must only be called by the runtime with its serialized input buffer
+#[no_mangle]
+pub unsafe extern "C" fn entrypoint(input: *mut u8) -> u64 {
+    match process_input(input) {
+        Ok(()) => SUCCESS,
+        Err(error) => error.into(),
+    }
+}
+
+solana_program::custom_heap_default!();
+solana_program::custom_panic_default!();
+
+unsafe fn process_input(input: *mut u8) -> ProgramResult {
+    let (program_id, instruction_data) = load_program_id_and_instruction_data(input);
+    if *program_id != slumlord_lib::program::ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let ix = SlumlordProgramIx::deserialize(instruction_data)?;
+    let accounts = load_accounts_prefix(input, accounts_len(&ix));
+    crate::process_ix(&accounts, ix)
+}
+
+/// Number of accounts `ix` reads, any accounts after these are not deserialized.
+///
+/// Borrow instructions include the borrower accounts read in allowlist mode
+fn accounts_len(ix: &SlumlordProgramIx) -> usize {
+    match ix {
+        SlumlordProgramIx::Init => INIT_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::Borrow => BORROW_IX_ACCOUNTS_LEN + BORROWER_ACCOUNTS_LEN,
+        SlumlordProgramIx::Repay => REPAY_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::CheckRepaid => CHECK_REPAID_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::BorrowSplit(args) => BORROW_SPLIT_IX_ACCOUNTS_LEN
+            .saturating_add(args.amounts.len())
+            .saturating_add(BORROWER_ACCOUNTS_LEN),
+        SlumlordProgramIx::RepayWithMax(_) => REPAY_WITH_MAX_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::BorrowWithMin(_) => {
+            BORROW_WITH_MIN_IX_ACCOUNTS_LEN + BORROWER_ACCOUNTS_LEN
+        }
+        // all remaining accounts are passed to the callback
+        SlumlordProgramIx::FlashLoanWithCallback(_) => usize::MAX,
+        SlumlordProgramIx::QuoteLoan => QUOTE_LOAN_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::Migrate => MIGRATE_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::SetAdmin => SET_ADMIN_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::SetAllowlist(_) => SET_ALLOWLIST_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::AddBorrower => ADD_BORROWER_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::RemoveBorrower => REMOVE_BORROWER_IX_ACCOUNTS_LEN,
+        SlumlordProgramIx::SetPoolLimits(_) => SET_POOL_LIMITS_IX_ACCOUNTS_LEN,
+    }
+}
+
+/// Skips over all accounts without creating any [`AccountInfo`]s
+/// to read the program id and instruction data
+unsafe fn load_program_id_and_instruction_data<'a>(input: *mut u8) -> (&'a Pubkey, &'a [u8]) {
+    let mut offset: usize = 0;
+
+    #[allow(clippy::cast_ptr_alignment)]
+    let num_accounts = *(input.add(offset) as *const u64) as usize;
+    offset += size_of::<u64>();
+
+    for _ in 0..num_accounts {
+        offset = skip_account(input, offset);
+    }
+
+    #[allow(clippy::cast_ptr_alignment)]
+    let instruction_data_len = *(input.add(offset) as *const u64) as usize;
+    offset += size_of::<u64>();
+
+    let instruction_data = from_raw_parts(input.add(offset), instruction_data_len);
+    offset += instruction_data_len;
+
+    let program_id: &Pubkey = &*(input.add(offset) as *const Pubkey);
+
+    (program_id, instruction_data)
+}
+
+/// Returns the offset of the account following the one at `offset`
+unsafe fn skip_account(input: *mut u8, mut offset: usize) -> usize {
+    let dup_info = *(input.add(offset) as *const u8);
+    offset += size_of::<u8>();
+    if dup_info != NON_DUP_MARKER {
+        return offset + 7; // padding
+    }
+
+    // is_signer, is_writable, executable, original_data_len, key, owner, lamports
+    offset += 3 * size_of::<u8>() + size_of::<u32>() + 2 * size_of::<Pubkey>() + size_of::<u64>();
+
+    #[allow(clippy::cast_ptr_alignment)]
+    let data_len = *(input.add(offset) as *const u64) as usize;
+    offset += size_of::<u64>();
+
+    offset += data_len + MAX_PERMITTED_DATA_INCREASE;
+    offset += (offset as *const u8).align_offset(BPF_ALIGN_OF_U128); // padding
+
+    offset + size_of::<u64>() // rent_epoch
+}
+
+/// Deserializes only the first `len` accounts,
+/// or all of them if fewer than `len` were passed
+unsafe fn load_accounts_prefix<'a>(input: *mut u8, len: usize) -> Vec<AccountInfo<'a>> {
+    let mut offset: usize = 0;
+
+    #[allow(clippy::cast_ptr_alignment)]
+    let num_accounts = *(input.add(offset) as *const u64) as usize;
+    offset += size_of::<u64>();
+
+    let len = len.min(num_accounts);
+    let mut accounts = Vec::with_capacity(len);
+    for _ in 0..len {
+        let dup_info = *(input.add(offset) as *const u8);
+        offset += size_of::<u8>();
+        if dup_info == NON_DUP_MARKER {
+            let is_signer = *(input.add(offset) as *const u8) != 0;
+            offset += size_of::<u8>();
+
+            let is_writable = *(input.add(offset) as *const u8) != 0;
+            offset += size_of::<u8>();
+
+            let executable = *(input.add(offset) as *const u8) != 0;
+            offset += size_of::<u8>();
+
+            let original_data_len_offset = offset;
+            offset += size_of::<u32>();
+
+            let key: &Pubkey = &*(input.add(offset) as *const Pubkey);
+            offset += size_of::<Pubkey>();
+
+            let owner: &Pubkey = &*(input.add(offset) as *const Pubkey);
+            offset += size_of::<Pubkey>();
+
+            #[allow(clippy::cast_ptr_alignment)]
+            let lamports = Rc::new(RefCell::new(&mut *(input.add(offset) as *mut u64)));
+            offset += size_of::<u64>();
+
+            #[allow(clippy::cast_ptr_alignment)]
+            let data_len = *(input.add(offset) as *const u64) as usize;
+            offset += size_of::<u64>();
+
+            // Store the original data length for the runtime to detect invalid reallocations
+            *(input.add(original_data_len_offset) as *mut u32) = data_len as u32;
+
+            let data = Rc::new(RefCell::new(from_raw_parts_mut(
+                input.add(offset),
+                data_len,
+            )));
+            offset += data_len + MAX_PERMITTED_DATA_INCREASE;
+            offset += (offset as *const u8).align_offset(BPF_ALIGN_OF_U128); // padding
+
+            #[allow(clippy::cast_ptr_alignment)]
+            let rent_epoch = *(input.add(offset) as *const u64);
+            offset += size_of::<u64>();
+
+            accounts.push(AccountInfo {
+                key,
+                is_signer,
+                is_writable,
+                lamports,
+                data,
+                owner,
+                executable,
+                rent_epoch,
+            });
+        } else {
+            offset += 7; // padding
+
+            // Duplicate account, clone the original
+            accounts.push(accounts[dup_info as usize].clone());
+        }
+    }
+
+    accounts
+}
@@ -1,3 +1,4 @@
+use borsh::BorshSerialize;
 use sanctum_misc_utils::{
     load_accounts, log_and_return_acc_privilege_err, log_and_return_wrong_acc_err,
 };
@@ -6,27 +7,61 @@ use sanctum_system_program_lib::{
     TransferAccounts,
 };
 use slumlord_interface::{
-    borrow_verify_account_keys, borrow_verify_account_privileges, check_repaid_verify_account_keys,
-    check_repaid_verify_account_privileges, init_verify_account_keys,
-    init_verify_account_privileges, repay_verify_account_keys, repay_verify_account_privileges,
-    BorrowAccounts, CheckRepaidAccounts, InitAccounts, RepayAccounts, SlumlordError,
-    SlumlordProgramIx, CHECK_REPAID_IX_DISCM,
+    add_borrower_verify_account_keys, add_borrower_verify_account_privileges,
+    borrow_split_verify_account_keys, borrow_split_verify_account_privileges,
+    borrow_verify_account_keys, borrow_verify_account_privileges,
+    borrow_with_min_verify_account_keys, borrow_with_min_verify_account_privileges,
+    check_repaid_verify_account_keys, check_repaid_verify_account_privileges,
+    flash_loan_with_callback_verify_account_keys,
+    flash_loan_with_callback_verify_account_privileges, init_verify_account_keys,
+    init_verify_account_privileges, migrate_verify_account_keys, migrate_verify_account_privileges,
+    on_flash_loan_ix, quote_loan_verify_account_keys, quote_loan_verify_account_privileges,
+    remove_borrower_verify_account_keys, remove_borrower_verify_account_privileges,
+    repay_verify_account_keys, repay_verify_account_privileges, repay_with_max_verify_account_keys,
+    repay_with_max_verify_account_privileges, set_admin_verify_account_keys,
+    set_admin_verify_account_privileges, set_allowlist_verify_account_keys,
+    set_allowlist_verify_account_privileges, set_pool_limits_verify_account_keys,
+    set_pool_limits_verify_account_privileges, AddBorrowerAccounts, BorrowAccounts,
+    BorrowSplitAccounts, BorrowSplitIxArgs, BorrowWithMinAccounts, BorrowWithMinIxArgs,
+    CheckRepaidAccounts, FlashLoanWithCallbackAccounts, FlashLoanWithCallbackIxArgs, InitAccounts,
+    MigrateAccounts, OnFlashLoanIxArgs, OnFlashLoanKeys, PoolLimits, QuoteLoanAccounts,
+    RemoveBorrowerAccounts, RepayAccounts, RepayWithMaxAccounts, RepayWithMaxIxArgs,
+    SetAdminAccounts, SetAllowlistAccounts, SetAllowlistIxArgs, SetPoolLimitsAccounts,
+    SetPoolLimitsIxArgs, SlumlordError, SlumlordProgramIx, BORROW_IX_ACCOUNTS_LEN,
+    BORROW_SPLIT_IX_ACCOUNTS_LEN, BORROW_WITH_MIN_IX_ACCOUNTS_LEN,
+    FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN,
 };
 use slumlord_lib::{
+    check_loan_repaid, clear_versioned_loan, is_loan_active, migrate_slumlord_data,
     program::{SLUMLORD_BUMP, SLUMLORD_SEED},
-    try_slumlord_mut, BorrowFreeArgs, LoanActiveSlumlordAccount, RepayFreeArgs, CHECK_REPAID_KEYS,
-    INIT_KEYS, SLUMLORD_ACCOUNT_LEN,
+    program_upgrade_authority, quote_loan, scan_instructions_sysvar, set_admin,
+    set_allowlist_enabled, set_pool_limits, slumlord_account_version, split_loan_dst,
+    split_loan_lamports, try_borrower_entry_mut, try_loan_context, unrepaid_loan_msg, verify_admin,
+    verify_borrower_entry, verify_loanable, verify_no_active_loan, verify_not_loan_to_self,
+    verify_slumlord_initialized, verify_split_loan_dsts, AddBorrowerFreeArgs, BorrowFreeArgs,
+    FlashLoanWithCallbackFreeArgs, LoanActiveSlumlordAccount, MigrateFreeArgs,
+    RemoveBorrowerFreeArgs, RepayFreeArgs, SetAdminFreeArgs, SetAllowlistFreeArgs,
+    SetPoolLimitsFreeArgs, BORROWER_ENTRY_LEN, BORROWER_ENTRY_SEED, BORROW_SPLIT_KEYS,
+    CHECK_REPAID_KEYS, INIT_KEYS, LEGACY_SLUMLORD_ACCOUNT_LEN, QUOTE_LOAN_KEYS,
+    SLUMLORD_ACCOUNT_LEN, SLUMLORD_ACCOUNT_VERSION, UNKNOWN_BORROW_IX_INDEX,
 };
 use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
-    instruction::Instruction,
+    instruction::AccountMeta,
+    msg,
+    program::{invoke, invoke_signed, set_return_data},
     program_error::{PrintProgramError, ProgramError},
     pubkey::Pubkey,
-    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::{instructions::load_current_index_checked, Sysvar},
 };
 
-#[cfg(not(feature = "no-entrypoint"))]
+#[cfg(all(feature = "lazy-entrypoint", not(feature = "no-entrypoint")))]
+mod lazy_entrypoint;
+
+#[cfg(not(any(feature = "lazy-entrypoint", feature = "no-entrypoint")))]
 solana_program::entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -41,11 +76,28 @@ pub fn process_instruction(
     let ix = SlumlordProgramIx::deserialize(instruction_data)?;
     solana_program::msg!("{:?}", ix);
 
+    process_ix(accounts, ix)
+}
+
+fn process_ix(accounts: &[AccountInfo], ix: SlumlordProgramIx) -> ProgramResult {
     let res: ProgramResult = match ix {
         SlumlordProgramIx::Init => process_init(accounts),
         SlumlordProgramIx::Borrow => process_borrow(accounts),
         SlumlordProgramIx::Repay => process_repay(accounts),
         SlumlordProgramIx::CheckRepaid => process_check_repaid(accounts),
+        SlumlordProgramIx::BorrowSplit(args) => process_borrow_split(accounts, args),
+        SlumlordProgramIx::RepayWithMax(args) => process_repay_with_max(accounts, args),
+        SlumlordProgramIx::BorrowWithMin(args) => process_borrow_with_min(accounts, args),
+        SlumlordProgramIx::FlashLoanWithCallback(args) => {
+            process_flash_loan_with_callback(accounts, args)
+        }
+        SlumlordProgramIx::QuoteLoan => process_quote_loan(accounts),
+        SlumlordProgramIx::Migrate => process_migrate(accounts),
+        SlumlordProgramIx::SetAdmin => process_set_admin(accounts),
+        SlumlordProgramIx::SetAllowlist(args) => process_set_allowlist(accounts, args),
+        SlumlordProgramIx::AddBorrower => process_add_borrower(accounts),
+        SlumlordProgramIx::RemoveBorrower => process_remove_borrower(accounts),
+        SlumlordProgramIx::SetPoolLimits(args) => process_set_pool_limits(accounts, args),
     };
     if let Err(e) = res.as_ref() {
         e.print::<SlumlordError>();
@@ -77,9 +129,10 @@ fn process_init(accounts: &[AccountInfo]) -> ProgramResult {
     Ok(())
 }
 
-/// Flash borrows `slumlord_balance - 1` lamports from slumlord account to
+/// Flash borrows all loanable lamports from slumlord account to
 /// specified `dst` account
 fn process_borrow(accounts: &[AccountInfo]) -> ProgramResult {
+    let borrower_accounts = accounts.get(BORROW_IX_ACCOUNTS_LEN..).unwrap_or_default();
     let accounts: BorrowAccounts = load_accounts(accounts)?;
 
     let free_args = BorrowFreeArgs {
@@ -89,35 +142,60 @@ fn process_borrow(accounts: &[AccountInfo]) -> ProgramResult {
         .map_err(log_and_return_wrong_acc_err)?;
     borrow_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
 
-    let curr_ix_idx: usize = load_current_index_checked(accounts.instructions)?.into();
-    let mut next_ix_idx = curr_ix_idx;
-    loop {
-        next_ix_idx = next_ix_idx
-            .checked_add(1)
-            .ok_or(ProgramError::InvalidInstructionData)?;
-        let next_ix = load_instruction_at_checked(next_ix_idx, accounts.instructions)
-            .map_err(|_| SlumlordError::NoSucceedingCheckRepaid)?;
-        if is_check_repaid_ix(&next_ix) {
-            break;
-        }
-    }
+    borrow(accounts, borrower_accounts, 0)
+}
 
-    if !accounts.slumlord.data_is_empty() {
-        return Err(SlumlordError::BorrowAlreadyActive.into());
-    }
+/// Same as [`process_borrow`], but fails before lending if the loan amount
+/// would be less than `min_lamports`
+fn process_borrow_with_min(accounts: &[AccountInfo], args: BorrowWithMinIxArgs) -> ProgramResult {
+    let borrower_accounts = accounts
+        .get(BORROW_WITH_MIN_IX_ACCOUNTS_LEN..)
+        .unwrap_or_default();
+    let accounts: BorrowWithMinAccounts = load_accounts(accounts)?;
+
+    let free_args = BorrowFreeArgs {
+        dst: *accounts.dst.key,
+    };
+    borrow_with_min_verify_account_keys(accounts, free_args.into())
+        .map_err(log_and_return_wrong_acc_err)?;
+    borrow_with_min_verify_account_privileges(accounts)
+        .map_err(log_and_return_acc_privilege_err)?;
 
-    let slumlord_lamports = accounts.slumlord.lamports();
+    borrow(
+        BorrowAccounts {
+            slumlord: accounts.slumlord,
+            dst: accounts.dst,
+            instructions: accounts.instructions,
+        },
+        borrower_accounts,
+        args.min_lamports,
+    )
+}
 
-    {
-        accounts.slumlord.extend_to(SLUMLORD_ACCOUNT_LEN)?;
-        let mut slumlord_data = accounts.slumlord.try_borrow_mut_data()?;
-        let slumlord = try_slumlord_mut(&mut slumlord_data)?;
-        slumlord.old_lamports = slumlord_lamports;
-    }
+/// Lends all loanable lamports to `dst` after
+/// account keys and privileges have been verified,
+/// setting the lent amount as little-endian u64 return data.
+///
+/// Fails with [`SlumlordError::InsufficientLiquidity`] if that is less than `min_lamports`
+fn borrow(
+    accounts: BorrowAccounts,
+    borrower_accounts: &[AccountInfo],
+    min_lamports: u64,
+) -> ProgramResult {
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+    verify_allowed_borrower(accounts.slumlord, borrower_accounts)?;
+    verify_not_loan_to_self(accounts.dst.key)?;
+    let borrow_ix_index =
+        verify_succeeding_check_repaid(accounts.instructions, accounts.slumlord.key)?;
 
-    let borrow_lamports = slumlord_lamports
-        .checked_sub(1)
-        .ok_or(ProgramError::InsufficientFunds)?;
+    let borrow_lamports = start_loan(accounts.slumlord)?;
+    verify_loanable(min_lamports, borrow_lamports)?;
+    record_loan(
+        accounts.slumlord,
+        borrow_lamports,
+        accounts.dst.key,
+        borrow_ix_index,
+    )?;
 
     transfer_direct_increment(
         TransferAccounts {
@@ -127,21 +205,204 @@ fn process_borrow(accounts: &[AccountInfo]) -> ProgramResult {
         borrow_lamports,
     )?;
 
+    set_return_data(&borrow_lamports.to_le_bytes());
+
     Ok(())
 }
 
-fn is_check_repaid_ix(ix: &Instruction) -> bool {
-    let discm = match ix.data.first() {
-        Some(d) => d,
-        None => return false,
-    };
-    if *discm != CHECK_REPAID_IX_DISCM {
-        return false;
+/// Flash borrows `amounts[i]` lamports from slumlord account to the
+/// i-th remaining account, recording the sum as the outstanding loan.
+///
+/// Fails with [`SlumlordError::InsufficientLiquidity`] if
+/// the sum of `amounts` exceeds the loanable lamports
+fn process_borrow_split(accounts: &[AccountInfo], args: BorrowSplitIxArgs) -> ProgramResult {
+    let (dsts, borrower_accounts) = accounts
+        .get(BORROW_SPLIT_IX_ACCOUNTS_LEN..)
+        .filter(|remaining| remaining.len() >= args.amounts.len())
+        .map(|remaining| remaining.split_at(args.amounts.len()))
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let accounts: BorrowSplitAccounts = load_accounts(accounts)?;
+
+    borrow_split_verify_account_keys(accounts, BORROW_SPLIT_KEYS)
+        .map_err(log_and_return_wrong_acc_err)?;
+    borrow_split_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+    verify_allowed_borrower(accounts.slumlord, borrower_accounts)?;
+    verify_split_loan_dsts(dsts)?;
+
+    let borrow_ix_index =
+        verify_succeeding_check_repaid(accounts.instructions, accounts.slumlord.key)?;
+
+    let max_borrow_lamports = start_loan(accounts.slumlord)?;
+    let total_borrow_lamports = split_loan_lamports(&args.amounts, max_borrow_lamports)?;
+    record_loan(
+        accounts.slumlord,
+        total_borrow_lamports,
+        &split_loan_dst(dsts.iter().map(|dst| dst.key)),
+        borrow_ix_index,
+    )?;
+
+    for (dst, amount) in dsts.iter().zip(args.amounts) {
+        transfer_direct_increment(
+            TransferAccounts {
+                from: accounts.slumlord,
+                to: dst,
+            },
+            amount,
+        )?;
     }
-    if ix.program_id != slumlord_lib::program::ID {
-        return false;
+
+    Ok(())
+}
+
+/// Flash borrows `amount` lamports from slumlord account to `receiver`, then calls
+/// `receiver_program`'s `OnFlashLoan` with the remaining accounts,
+/// excluding the borrower accounts in allowlist mode.
+///
+/// The loan must be fully repaid by the time `OnFlashLoan` returns,
+/// so no succeeding CheckRepaid is required.
+/// Sets the same return data as CheckRepaid.
+fn process_flash_loan_with_callback(
+    accounts: &[AccountInfo],
+    args: FlashLoanWithCallbackIxArgs,
+) -> ProgramResult {
+    let remaining_accounts = accounts
+        .get(FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN..)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let accounts: FlashLoanWithCallbackAccounts = load_accounts(accounts)?;
+
+    let free_args = FlashLoanWithCallbackFreeArgs {
+        receiver: *accounts.receiver.key,
+        receiver_program: *accounts.receiver_program.key,
+        initiator: *accounts.initiator.key,
+    };
+    flash_loan_with_callback_verify_account_keys(accounts, free_args.resolve())
+        .map_err(log_and_return_wrong_acc_err)?;
+    flash_loan_with_callback_verify_account_privileges(accounts)
+        .map_err(log_and_return_acc_privilege_err)?;
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+    let callback_accounts = verify_allowed_borrower(accounts.slumlord, remaining_accounts)?;
+    verify_not_loan_to_self(accounts.receiver.key)?;
+
+    let max_borrow_lamports = start_loan(accounts.slumlord)?;
+    verify_loanable(args.amount, max_borrow_lamports)?;
+    record_loan(
+        accounts.slumlord,
+        args.amount,
+        accounts.receiver.key,
+        UNKNOWN_BORROW_IX_INDEX,
+    )?;
+
+    transfer_direct_increment(
+        TransferAccounts {
+            from: accounts.slumlord,
+            to: accounts.receiver,
+        },
+        args.amount,
+    )?;
+
+    invoke_on_flash_loan(accounts, callback_accounts, args)?;
+
+    end_loan(accounts.slumlord)
+}
+
+/// CPIs `OnFlashLoan` into `receiver_program`, passing through `initiator` as a signer,
+/// `receiver`'s signer privilege and `callback_accounts` with their privileges.
+///
+/// Not signed: the slumlord PDA must never be a signer for the receiver
+fn invoke_on_flash_loan<'info>(
+    accounts: FlashLoanWithCallbackAccounts<'_, 'info>,
+    callback_accounts: &[AccountInfo<'info>],
+    args: FlashLoanWithCallbackIxArgs,
+) -> ProgramResult {
+    let FlashLoanWithCallbackIxArgs { amount, data } = args;
+    let mut ix = on_flash_loan_ix(
+        *accounts.receiver_program.key,
+        OnFlashLoanKeys {
+            slumlord: *accounts.slumlord.key,
+            receiver: *accounts.receiver.key,
+            initiator: *accounts.initiator.key,
+        },
+        OnFlashLoanIxArgs { amount, data },
+    )?;
+    ix.accounts[1].is_signer = accounts.receiver.is_signer;
+    ix.accounts
+        .extend(callback_accounts.iter().map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        }));
+
+    let account_infos: Vec<AccountInfo> = [
+        accounts.slumlord,
+        accounts.receiver,
+        accounts.receiver_program,
+        accounts.initiator,
+    ]
+    .into_iter()
+    .chain(callback_accounts)
+    .cloned()
+    .collect();
+    invoke(&ix, &account_infos)
+}
+
+/// Checks that a top-level CheckRepaid instruction of `slumlord`
+/// follows the currently executing instruction, returning the current instruction's index
+fn verify_succeeding_check_repaid(
+    instructions: &AccountInfo,
+    slumlord: &Pubkey,
+) -> Result<u16, ProgramError> {
+    let curr_ix_idx = load_current_index_checked(instructions)?;
+    let res = scan_instructions_sysvar(instructions, |ixs| {
+        slumlord_lib::verify_succeeding_check_repaid(ixs, curr_ix_idx, slumlord)
+    })?;
+    Ok(res?)
+}
+
+/// See [`slumlord_lib::verify_allowed_borrower`]
+fn verify_allowed_borrower<'me, 'info>(
+    slumlord: &AccountInfo,
+    borrower_accounts: &'me [AccountInfo<'info>],
+) -> Result<&'me [AccountInfo<'info>], ProgramError> {
+    slumlord_lib::verify_allowed_borrower(
+        &slumlord.try_borrow_data()?,
+        borrower_accounts,
+        |borrower, borrower_entry| {
+            verify_borrower_entry(
+                borrower,
+                borrower_entry.owner,
+                &borrower_entry.try_borrow_data()?,
+            )
+            .map_err(Into::into)
+        },
+    )
+}
+
+/// Records the slumlord account's current lamports as the pre-loan balance,
+/// returning the most lamports the loan can lend, see [`loanable_lamports`].
+///
+/// Errors if a flash loan is already active
+fn start_loan(slumlord: &AccountInfo) -> Result<u64, ProgramError> {
+    verify_no_active_loan(&slumlord.try_borrow_data()?)?;
+
+    let slumlord_lamports = slumlord.lamports();
+    // unmigrated accounts only have data during a flash loan
+    if slumlord.data_is_empty() {
+        slumlord.extend_to(LEGACY_SLUMLORD_ACCOUNT_LEN)?;
     }
-    true
+    slumlord_lib::start_loan(&mut slumlord.try_borrow_mut_data()?, slumlord_lamports)
+}
+
+/// Records the total amount lent by the active loan, the `dst` lent to
+/// and the index of the top-level instruction that started it
+fn record_loan(
+    slumlord: &AccountInfo,
+    lent_lamports: u64,
+    dst: &Pubkey,
+    borrow_ix_index: u16,
+) -> ProgramResult {
+    let mut slumlord_data = slumlord.try_borrow_mut_data()?;
+    slumlord_lib::record_loan(&mut slumlord_data, lent_lamports, dst, borrow_ix_index)
 }
 
 /// Transfer the outstanding loan amount from the `src` system_account
@@ -170,8 +431,41 @@ fn process_repay(accounts: &[AccountInfo]) -> ProgramResult {
     Ok(())
 }
 
+/// Same as [`process_repay`], but fails if the outstanding loan amount
+/// exceeds `max_lamports`.
+///
+/// Allows borrowers to bound how much leaves `src` in case the slumlord
+/// account's balance was manipulated earlier in the transaction.
+fn process_repay_with_max(accounts: &[AccountInfo], args: RepayWithMaxIxArgs) -> ProgramResult {
+    let accounts: RepayWithMaxAccounts = load_accounts(accounts)?;
+
+    let free_args = RepayFreeArgs {
+        src: *accounts.src.key,
+    };
+    repay_with_max_verify_account_keys(accounts, free_args.into())
+        .map_err(log_and_return_wrong_acc_err)?;
+    repay_with_max_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
+
+    let outstanding_lamports = accounts.slumlord.curr_loan_lamports_outstanding()?;
+    if outstanding_lamports > args.max_lamports {
+        return Err(SlumlordError::RepayExceedsMax.into());
+    }
+
+    transfer_invoke(
+        TransferAccounts {
+            from: accounts.src,
+            to: accounts.slumlord,
+        },
+        outstanding_lamports,
+    )?;
+
+    Ok(())
+}
+
 /// Verifies that the flash loan has been completely repaid,
-/// ending the flash loan
+/// ending the flash loan.
+///
+/// Sets [`CheckRepaidReturn`] as return data, or clears it if no flash loan was active
 fn process_check_repaid(accounts: &[AccountInfo]) -> ProgramResult {
     let accounts: CheckRepaidAccounts = load_accounts(accounts)?;
 
@@ -180,18 +474,273 @@ fn process_check_repaid(accounts: &[AccountInfo]) -> ProgramResult {
     check_repaid_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
 
     // no flash loan active, successful no-op
-    if accounts.slumlord.data_is_empty() {
+    if !is_loan_active(&accounts.slumlord.try_borrow_data()?)? {
+        // clear any return data from a preceding CPI-ed Borrow
+        set_return_data(&[]);
         return Ok(());
     }
 
-    let slumlord_lamports = accounts.slumlord.lamports();
-    let min_expected_slumlord_lamports = accounts.slumlord.old_lamports()?;
+    end_loan(accounts.slumlord)
+}
 
-    if slumlord_lamports < min_expected_slumlord_lamports {
-        return Err(SlumlordError::InsufficientRepay.into());
+/// Verifies that the slumlord account's current lamports are at least
+/// the pre-loan balance, then clears the loan state and sets
+/// [`CheckRepaidReturn`] as return data
+fn end_loan(slumlord: &AccountInfo) -> ProgramResult {
+    let check_repaid_return =
+        match check_loan_repaid(&slumlord.try_borrow_data()?, slumlord.lamports()) {
+            Err(e) if e == SlumlordError::InsufficientRepay.into() => {
+                msg!(
+                    "{}",
+                    unrepaid_loan_msg(
+                        try_loan_context(&slumlord.try_borrow_data()?)?,
+                        slumlord.curr_loan_lamports_outstanding()?,
+                    )
+                );
+                return Err(e);
+            }
+            res => res?,
+        };
+
+    let is_cleared = clear_versioned_loan(&mut slumlord.try_borrow_mut_data()?)?;
+    if !is_cleared {
+        slumlord.shrink_to(0)?;
     }
 
-    accounts.slumlord.shrink_to(0)?;
+    set_return_data(&check_repaid_return.try_to_vec()?);
+
+    Ok(())
+}
+
+/// Sets [`QuoteLoanReturn`](slumlord_interface::QuoteLoanReturn) as return data.
+///
+/// Read-only, meant to be simulated by clients to get the current loanable amount
+fn process_quote_loan(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts: QuoteLoanAccounts = load_accounts(accounts)?;
+
+    quote_loan_verify_account_keys(accounts, QUOTE_LOAN_KEYS)
+        .map_err(log_and_return_wrong_acc_err)?;
+    quote_loan_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
+
+    set_return_data(&quote_loan(accounts.slumlord).try_to_vec()?);
 
     Ok(())
 }
+
+/// Migrates the slumlord account to the current versioned account layout,
+/// transferring any additional lamports required for its rent-exemption from `payer`.
+///
+/// Permissionless. No-op if the slumlord account is already of the current version.
+///
+/// Fails if a flash loan is active
+fn process_migrate(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts: MigrateAccounts = load_accounts(accounts)?;
+
+    let free_args = MigrateFreeArgs {
+        payer: *accounts.payer.key,
+    };
+    migrate_verify_account_keys(accounts, free_args.resolve())
+        .map_err(log_and_return_wrong_acc_err)?;
+    migrate_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
+
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+
+    {
+        let slumlord_data = accounts.slumlord.try_borrow_data()?;
+        verify_no_active_loan(&slumlord_data)?;
+        if slumlord_account_version(&slumlord_data)? == Some(SLUMLORD_ACCOUNT_VERSION) {
+            return Ok(());
+        }
+    }
+
+    let rent_shortfall = Rent::get()?
+        .minimum_balance(SLUMLORD_ACCOUNT_LEN)
+        .saturating_sub(accounts.slumlord.lamports());
+    if rent_shortfall > 0 {
+        transfer_invoke(
+            TransferAccounts {
+                from: accounts.payer,
+                to: accounts.slumlord,
+            },
+            rent_shortfall,
+        )?;
+    }
+
+    let old_len = accounts.slumlord.data_len();
+    accounts.slumlord.extend_to(SLUMLORD_ACCOUNT_LEN)?;
+    migrate_slumlord_data(&mut accounts.slumlord.try_borrow_mut_data()?, old_len)
+}
+
+/// Sets the slumlord admin, who manages the borrower allowlist, to `new_admin`.
+///
+/// `authority` must be the current admin, or the slumlord program's
+/// upgrade authority if no admin has been set.
+///
+/// Fails with [`SlumlordError::NoPoolConfig`] until `Migrate` is run
+fn process_set_admin(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts: SetAdminAccounts = load_accounts(accounts)?;
+
+    let free_args = SetAdminFreeArgs {
+        authority: *accounts.authority.key,
+        new_admin: *accounts.new_admin.key,
+    };
+    set_admin_verify_account_keys(accounts, free_args.resolve())
+        .map_err(log_and_return_wrong_acc_err)?;
+    set_admin_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
+
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+
+    set_admin(
+        &mut accounts.slumlord.try_borrow_mut_data()?,
+        accounts.authority.key,
+        accounts.new_admin.key,
+        || {
+            program_upgrade_authority(
+                accounts.program_data.owner,
+                &accounts.program_data.try_borrow_data()?,
+            )
+        },
+    )
+}
+
+/// Enables or disables allowlist mode, in which every borrow instruction
+/// must be signed by a borrower added with `AddBorrower`.
+///
+/// Admin only
+fn process_set_allowlist(accounts: &[AccountInfo], args: SetAllowlistIxArgs) -> ProgramResult {
+    let accounts: SetAllowlistAccounts = load_accounts(accounts)?;
+
+    let free_args = SetAllowlistFreeArgs {
+        admin: *accounts.admin.key,
+    };
+    set_allowlist_verify_account_keys(accounts, free_args.resolve())
+        .map_err(log_and_return_wrong_acc_err)?;
+    set_allowlist_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
+
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+
+    set_allowlist_enabled(
+        &mut accounts.slumlord.try_borrow_mut_data()?,
+        accounts.admin.key,
+        args.enabled,
+    )
+}
+
+/// Creates the allowlist entry PDA of `borrower`, paying any rent-exemption shortfall from `payer`.
+///
+/// Admin only. Fails if `borrower` has already been added
+fn process_add_borrower(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts: AddBorrowerAccounts = load_accounts(accounts)?;
+
+    let free_args = AddBorrowerFreeArgs {
+        admin: *accounts.admin.key,
+        payer: *accounts.payer.key,
+        borrower: *accounts.borrower.key,
+    };
+    let (keys, borrower_entry_bump) = free_args.resolve_with_bump();
+    add_borrower_verify_account_keys(accounts, keys).map_err(log_and_return_wrong_acc_err)?;
+    add_borrower_verify_account_privileges(accounts).map_err(log_and_return_acc_privilege_err)?;
+
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+    verify_admin(&accounts.slumlord.try_borrow_data()?, accounts.admin.key)?;
+
+    // Funds only the rent shortfall then allocates and assigns instead of create_account,
+    // which fails if anyone has already transferred lamports to the entry PDA
+    let rent_shortfall = Rent::get()?
+        .minimum_balance(BORROWER_ENTRY_LEN)
+        .saturating_sub(accounts.borrower_entry.lamports());
+    if rent_shortfall > 0 {
+        transfer_invoke(
+            TransferAccounts {
+                from: accounts.payer,
+                to: accounts.borrower_entry,
+            },
+            rent_shortfall,
+        )?;
+    }
+    let bump = [borrower_entry_bump];
+    let borrower_entry_seeds: &[&[u8]] =
+        &[BORROWER_ENTRY_SEED, accounts.borrower.key.as_ref(), &bump];
+    invoke_signed(
+        &system_instruction::allocate(accounts.borrower_entry.key, BORROWER_ENTRY_LEN as u64),
+        &[
+            accounts.borrower_entry.clone(),
+            accounts.system_program.clone(),
+        ],
+        &[borrower_entry_seeds],
+    )?;
+    assign_invoke_signed(
+        accounts.borrower_entry,
+        slumlord_lib::program::ID,
+        &[borrower_entry_seeds],
+    )?;
+
+    try_borrower_entry_mut(&mut accounts.borrower_entry.try_borrow_mut_data()?)?.borrower =
+        *accounts.borrower.key;
+
+    Ok(())
+}
+
+/// Closes the allowlist entry PDA of `borrower`, refunding its lamports to `refund_rent_to`.
+///
+/// Admin only. Fails with [`SlumlordError::BorrowerNotAllowed`] if `borrower` has not been added
+fn process_remove_borrower(accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts: RemoveBorrowerAccounts = load_accounts(accounts)?;
+
+    let free_args = RemoveBorrowerFreeArgs {
+        admin: *accounts.admin.key,
+        borrower: *accounts.borrower.key,
+        refund_rent_to: *accounts.refund_rent_to.key,
+    };
+    remove_borrower_verify_account_keys(accounts, free_args.resolve())
+        .map_err(log_and_return_wrong_acc_err)?;
+    remove_borrower_verify_account_privileges(accounts)
+        .map_err(log_and_return_acc_privilege_err)?;
+
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+    verify_admin(&accounts.slumlord.try_borrow_data()?, accounts.admin.key)?;
+    verify_borrower_entry(
+        accounts.borrower.key,
+        accounts.borrower_entry.owner,
+        &accounts.borrower_entry.try_borrow_data()?,
+    )?;
+
+    transfer_direct_increment(
+        TransferAccounts {
+            from: accounts.borrower_entry,
+            to: accounts.refund_rent_to,
+        },
+        accounts.borrower_entry.lamports(),
+    )?;
+    accounts.borrower_entry.shrink_to(0)?;
+    accounts.borrower_entry.assign(&system_program::ID);
+
+    Ok(())
+}
+
+/// Sets the most lamports a single loan can lend and the lamports
+/// every loan must leave in the slumlord account.
+///
+/// Admin only
+fn process_set_pool_limits(accounts: &[AccountInfo], args: SetPoolLimitsIxArgs) -> ProgramResult {
+    let accounts: SetPoolLimitsAccounts = load_accounts(accounts)?;
+
+    let free_args = SetPoolLimitsFreeArgs {
+        admin: *accounts.admin.key,
+    };
+    set_pool_limits_verify_account_keys(accounts, free_args.resolve())
+        .map_err(log_and_return_wrong_acc_err)?;
+    set_pool_limits_verify_account_privileges(accounts)
+        .map_err(log_and_return_acc_privilege_err)?;
+
+    verify_slumlord_initialized(accounts.slumlord.owner)?;
+
+    set_pool_limits(
+        &mut accounts.slumlord.try_borrow_mut_data()?,
+        accounts.admin.key,
+        PoolLimits {
+            max_borrow_lamports: args.max_borrow_lamports,
+            reserve_lamports: args.reserve_lamports,
+        },
+    )
+}
@@ -0,0 +1,464 @@
+//! Compute units consumed by each slumlord instruction,
+//! checked against the thresholds in `cu_thresholds.txt`, or
+//! `cu_thresholds_lazy_entrypoint.txt` with the `lazy-entrypoint` feature.
+//!
+//! Compute units are only measured when running against the BPF program, run with
+//! `cargo test-sbf -- --nocapture bench` and
+//! `cargo test-sbf --features lazy-entrypoint -- --nocapture bench`,
+//! which also reports each instruction's compute units before and after the lazy entrypoint.
+//! Every scenario instruction must have a threshold in both files in every run mode.
+//!
+//! To regenerate the thresholds after an intended change, run both with
+//! `UPDATE_CU_THRESHOLDS=1`
+
+use sanctum_system_program_lib::{transfer_ix, TransferKeys};
+use slumlord_interface::{
+    borrow_ix, borrow_with_min_ix, migrate_ix, repay_ix, repay_with_max_ix, set_allowlist_ix,
+    set_pool_limits_ix, BorrowWithMinIxArgs, RepayWithMaxIxArgs, SetAllowlistIxArgs,
+    SetPoolLimitsIxArgs,
+};
+use slumlord_lib::{
+    borrow_split_ix_full, borrower_account_metas, check_repaid_ix_full, program::SLUMLORD_ID,
+    quote_loan_ix_full, BorrowFreeArgs, MigrateFreeArgs, RepayFreeArgs, SetAllowlistFreeArgs,
+    SetPoolLimitsFreeArgs,
+};
+use std::{collections::BTreeMap, fmt::Write};
+
+use slumlord_test_utils::{SlumlordBanksClient, SlumlordTestClient, SlumlordTestHarness};
+use solana_program::instruction::Instruction;
+use solana_sdk::{
+    hash::Hash, pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction,
+};
+
+use crate::{
+    add_borrower_tx,
+    cpi::{
+        flash_borrower::{flash_borrower_program, flash_loan_ix, FlashBorrowerProgramTest},
+        good::{good_program, GoodProgramTest},
+    },
+    enable_allowlist_tx, SLUMLORD_LAMPORTS,
+};
+
+const CU_THRESHOLDS_PATH: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/bench/cu_thresholds.txt");
+
+const LAZY_ENTRYPOINT_CU_THRESHOLDS_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/bench/cu_thresholds_lazy_entrypoint.txt"
+);
+
+/// Thresholds file and command regenerating it for the entrypoint the program is built with
+const ENTRYPOINT_CU_THRESHOLDS: (&str, &str) = if cfg!(feature = "lazy-entrypoint") {
+    (
+        LAZY_ENTRYPOINT_CU_THRESHOLDS_PATH,
+        "cargo test-sbf --features lazy-entrypoint -- bench",
+    )
+} else {
+    (CU_THRESHOLDS_PATH, "cargo test-sbf -- bench")
+};
+
+const UPDATE_CU_THRESHOLDS_ENV_VAR: &str = "UPDATE_CU_THRESHOLDS";
+
+/// Loan cap of the pool limits scenarios, less than what the pool could otherwise lend
+const MAX_BORROW_LAMPORTS: u64 = SLUMLORD_LAMPORTS / 2;
+
+/// A transaction and the names of the slumlord instructions in it, in order
+struct Scenario {
+    name: &'static str,
+    ixs: Vec<Instruction>,
+    slumlord_ix_names: &'static [&'static str],
+}
+
+fn donate_ix(from: Pubkey, lamports: u64) -> Instruction {
+    transfer_ix(
+        TransferKeys {
+            from,
+            to: SLUMLORD_ID,
+        },
+        lamports,
+    )
+}
+
+fn scenarios(payer: Pubkey) -> Vec<Scenario> {
+    vec![
+        Scenario {
+            name: "borrow_donate_check_repaid",
+            ixs: vec![
+                borrow_ix(BorrowFreeArgs { dst: payer }).unwrap(),
+                donate_ix(payer, SLUMLORD_LAMPORTS - 1),
+                check_repaid_ix_full().unwrap(),
+            ],
+            slumlord_ix_names: &["Borrow", "CheckRepaid"],
+        },
+        Scenario {
+            name: "borrow_repay_check_repaid",
+            ixs: vec![
+                borrow_ix(BorrowFreeArgs { dst: payer }).unwrap(),
+                repay_ix(RepayFreeArgs { src: payer }).unwrap(),
+                check_repaid_ix_full().unwrap(),
+            ],
+            slumlord_ix_names: &["Borrow", "Repay", "CheckRepaid"],
+        },
+        Scenario {
+            name: "borrow_with_min_repay_with_max_check_repaid",
+            ixs: vec![
+                borrow_with_min_ix(
+                    BorrowFreeArgs { dst: payer },
+                    BorrowWithMinIxArgs {
+                        min_lamports: SLUMLORD_LAMPORTS - 1,
+                    },
+                )
+                .unwrap(),
+                repay_with_max_ix(
+                    RepayFreeArgs { src: payer },
+                    RepayWithMaxIxArgs {
+                        max_lamports: SLUMLORD_LAMPORTS - 1,
+                    },
+                )
+                .unwrap(),
+                check_repaid_ix_full().unwrap(),
+            ],
+            slumlord_ix_names: &["BorrowWithMin", "RepayWithMax", "CheckRepaid"],
+        },
+        Scenario {
+            name: "borrow_split_donate_check_repaid",
+            ixs: vec![
+                borrow_split_ix_full(&[(payer, 1), (payer, 2)]).unwrap(),
+                donate_ix(payer, 3),
+                check_repaid_ix_full().unwrap(),
+            ],
+            slumlord_ix_names: &["BorrowSplit", "CheckRepaid"],
+        },
+        Scenario {
+            name: "cpi_good_program_check_repaid",
+            ixs: vec![
+                good_program::good_ix(payer),
+                check_repaid_ix_full().unwrap(),
+            ],
+            slumlord_ix_names: &["Borrow", "Repay", "CheckRepaid", "CheckRepaid"],
+        },
+        Scenario {
+            name: "flash_loan_with_callback",
+            ixs: vec![flash_loan_ix(
+                payer,
+                SLUMLORD_LAMPORTS - 1,
+                flash_borrower_program::REPAY,
+                &[],
+            )],
+            slumlord_ix_names: &["FlashLoanWithCallback"],
+        },
+        Scenario {
+            name: "check_repaid_no_loan",
+            ixs: vec![check_repaid_ix_full().unwrap()],
+            slumlord_ix_names: &["CheckRepaid"],
+        },
+        Scenario {
+            name: "quote_loan",
+            ixs: vec![quote_loan_ix_full().unwrap()],
+            slumlord_ix_names: &["QuoteLoan"],
+        },
+        Scenario {
+            name: "migrate",
+            ixs: vec![migrate_ix(MigrateFreeArgs { payer }).unwrap()],
+            slumlord_ix_names: &["Migrate"],
+        },
+    ]
+}
+
+/// Scenarios run after [`enable_allowlist_tx`] and [`add_borrower_tx`] of `payer`
+fn allowlist_scenarios(payer: Pubkey) -> Vec<Scenario> {
+    let mut borrow_ix = borrow_ix(BorrowFreeArgs { dst: payer }).unwrap();
+    borrow_ix.accounts.extend(borrower_account_metas(payer));
+    vec![Scenario {
+        name: "allowlist_borrow_donate_check_repaid",
+        ixs: vec![
+            borrow_ix,
+            donate_ix(payer, SLUMLORD_LAMPORTS - 1),
+            check_repaid_ix_full().unwrap(),
+        ],
+        slumlord_ix_names: &["Borrow", "CheckRepaid"],
+    }]
+}
+
+/// Disables allowlist mode and caps loans at [`MAX_BORROW_LAMPORTS`]
+fn limit_pool_tx(payer: &Keypair, admin: &Keypair, last_blockhash: Hash) -> Transaction {
+    let set_allowlist_ix = set_allowlist_ix(
+        SetAllowlistFreeArgs {
+            admin: admin.pubkey(),
+        },
+        SetAllowlistIxArgs { enabled: false },
+    )
+    .unwrap();
+    let set_pool_limits_ix = set_pool_limits_ix(
+        SetPoolLimitsFreeArgs {
+            admin: admin.pubkey(),
+        },
+        SetPoolLimitsIxArgs {
+            max_borrow_lamports: MAX_BORROW_LAMPORTS,
+            reserve_lamports: 0,
+        },
+    )
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[set_allowlist_ix, set_pool_limits_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[payer, admin], last_blockhash);
+    tx
+}
+
+/// Scenarios run after [`limit_pool_tx`]
+fn pool_limits_scenarios(payer: Pubkey) -> Vec<Scenario> {
+    vec![
+        Scenario {
+            name: "capped_borrow_donate_check_repaid",
+            ixs: vec![
+                borrow_ix(BorrowFreeArgs { dst: payer }).unwrap(),
+                donate_ix(payer, MAX_BORROW_LAMPORTS),
+                check_repaid_ix_full().unwrap(),
+            ],
+            slumlord_ix_names: &["Borrow", "CheckRepaid"],
+        },
+        Scenario {
+            name: "capped_borrow_split_donate_check_repaid",
+            ixs: vec![
+                borrow_split_ix_full(&[(payer, 1), (payer, MAX_BORROW_LAMPORTS - 1)]).unwrap(),
+                donate_ix(payer, MAX_BORROW_LAMPORTS),
+                check_repaid_ix_full().unwrap(),
+            ],
+            slumlord_ix_names: &["BorrowSplit", "CheckRepaid"],
+        },
+        Scenario {
+            name: "capped_flash_loan_with_callback",
+            ixs: vec![flash_loan_ix(
+                payer,
+                MAX_BORROW_LAMPORTS,
+                flash_borrower_program::REPAY,
+                &[],
+            )],
+            slumlord_ix_names: &["FlashLoanWithCallback"],
+        },
+    ]
+}
+
+/// Simulates `tx`, returning the compute units consumed by
+/// each top-level slumlord instruction parsed from the program logs.
+///
+/// Empty if not running against the BPF program since
+/// native programs do not log compute units consumed.
+async fn simulate_slumlord_cus<C: SlumlordTestClient>(
+    banks_client: &mut C,
+    tx: Transaction,
+) -> Vec<u64> {
+    let (res, logs) = banks_client.simulate_transaction(tx).await;
+    res.unwrap();
+    let prefix = format!("Program {} consumed ", slumlord_lib::program::ID);
+    logs.iter()
+        .filter_map(|log| log.strip_prefix(&prefix))
+        .map(|rest| rest.split_whitespace().next().unwrap().parse().unwrap())
+        .collect()
+}
+
+/// (scenario name, index of slumlord instruction in scenario) -> (instruction name, compute units)
+type CuTable = BTreeMap<(String, usize), (String, u64)>;
+
+/// Each non-comment line is `<scenario> <index> <instruction> <max compute units>`
+fn parse_cu_thresholds(s: &str) -> CuTable {
+    s.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                [scenario, index, ix_name, cus] => (
+                    (scenario.to_string(), index.parse().unwrap()),
+                    (ix_name.to_string(), cus.parse().unwrap()),
+                ),
+                _ => panic!("malformed cu threshold line: {line}"),
+            }
+        })
+        .collect()
+}
+
+fn read_cu_thresholds(path: &str) -> CuTable {
+    parse_cu_thresholds(&std::fs::read_to_string(path).unwrap())
+}
+
+fn serialize_cu_thresholds(table: &CuTable, regenerate_cmd: &str) -> String {
+    let mut res = format!(
+        "# Generated by {UPDATE_CU_THRESHOLDS_ENV_VAR}=1 {regenerate_cmd}\n\
+         # <scenario> <index> <instruction> <max compute units>\n"
+    );
+    for ((scenario, index), (ix_name, cus)) in table {
+        writeln!(res, "{scenario} {index} {ix_name} {cus}").unwrap();
+    }
+    res
+}
+
+/// (scenario name, index of slumlord instruction in scenario) -> instruction name
+type ScenarioIxs = BTreeMap<(String, usize), String>;
+
+/// Simulates each of `scenarios` against the current state,
+/// recording their slumlord instructions in `expected`
+/// and the compute units they consumed in `actual`
+async fn record_scenario_cus<C: SlumlordTestClient>(
+    banks_client: &mut C,
+    payer: &Keypair,
+    last_blockhash: Hash,
+    scenarios: Vec<Scenario>,
+    expected: &mut ScenarioIxs,
+    actual: &mut CuTable,
+) {
+    for Scenario {
+        name,
+        ixs,
+        slumlord_ix_names,
+    } in scenarios
+    {
+        for (index, ix_name) in slumlord_ix_names.iter().enumerate() {
+            expected.insert((name.to_owned(), index), ix_name.to_string());
+        }
+        let mut tx = Transaction::new_with_payer(&ixs, Some(&payer.pubkey()));
+        tx.sign(&[payer], last_blockhash);
+        let cus = simulate_slumlord_cus(banks_client, tx).await;
+        if cus.is_empty() {
+            println!("{name}: no compute units logged, not running against BPF program");
+            continue;
+        }
+        assert_eq!(cus.len(), slumlord_ix_names.len(), "{name}");
+        for (index, (ix_name, cu)) in slumlord_ix_names.iter().zip(cus).enumerate() {
+            println!("{name}: {ix_name} consumed {cu} CUs");
+            actual.insert((name.to_owned(), index), (ix_name.to_string(), cu));
+        }
+    }
+}
+
+harness_tests!(cu_regression);
+
+async fn cu_regression<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey())
+        .add_good_program()
+        .add_flash_borrower_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let mut expected = ScenarioIxs::new();
+    let mut actual = CuTable::new();
+    record_scenario_cus(
+        &mut banks_client,
+        &payer,
+        last_blockhash,
+        scenarios(payer.pubkey()),
+        &mut expected,
+        &mut actual,
+    )
+    .await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+    banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &admin,
+            payer.pubkey(),
+            last_blockhash,
+        ))
+        .await
+        .unwrap();
+    record_scenario_cus(
+        &mut banks_client,
+        &payer,
+        last_blockhash,
+        allowlist_scenarios(payer.pubkey()),
+        &mut expected,
+        &mut actual,
+    )
+    .await;
+
+    banks_client
+        .process_transaction(limit_pool_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+    record_scenario_cus(
+        &mut banks_client,
+        &payer,
+        last_blockhash,
+        pool_limits_scenarios(payer.pubkey()),
+        &mut expected,
+        &mut actual,
+    )
+    .await;
+
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    assert_eq!(banks_client.get_slumlord_loan_old_lamports().await, None);
+
+    let (thresholds_path, regenerate_cmd) = ENTRYPOINT_CU_THRESHOLDS;
+    if std::env::var_os(UPDATE_CU_THRESHOLDS_ENV_VAR).is_some() {
+        assert!(
+            !actual.is_empty(),
+            "{UPDATE_CU_THRESHOLDS_ENV_VAR} requires cargo test-sbf"
+        );
+        std::fs::write(
+            thresholds_path,
+            serialize_cu_thresholds(&actual, regenerate_cmd),
+        )
+        .unwrap();
+        return;
+    }
+
+    // checked even when compute units aren't measured so that new scenarios can't go unchecked
+    let standard = read_cu_thresholds(CU_THRESHOLDS_PATH);
+    let lazy = read_cu_thresholds(LAZY_ENTRYPOINT_CU_THRESHOLDS_PATH);
+    for (path, thresholds) in [
+        (CU_THRESHOLDS_PATH, &standard),
+        (LAZY_ENTRYPOINT_CU_THRESHOLDS_PATH, &lazy),
+    ] {
+        assert_thresholds_match_scenarios(path, thresholds, &expected);
+    }
+    for ((scenario, index), (ix_name, lazy_cus)) in &lazy {
+        let (_, standard_cus) = &standard[&(scenario.clone(), *index)];
+        println!("{scenario}: {ix_name} {standard_cus} -> {lazy_cus} CUs with lazy entrypoint");
+        assert!(
+            lazy_cus <= standard_cus,
+            "{scenario} {index} {ix_name} threshold is higher with the lazy entrypoint",
+        );
+    }
+
+    let thresholds = if cfg!(feature = "lazy-entrypoint") {
+        &lazy
+    } else {
+        &standard
+    };
+    for ((scenario, index), (ix_name, cus)) in actual {
+        let (_, max_cus) = &thresholds[&(scenario.clone(), index)];
+        assert!(
+            cus <= *max_cus,
+            "{scenario} {index} {ix_name} consumed {cus} CUs, exceeding threshold of {max_cus}",
+        );
+    }
+}
+
+fn assert_thresholds_match_scenarios(path: &str, thresholds: &CuTable, expected: &ScenarioIxs) {
+    for ((scenario, index), ix_name) in expected {
+        let Some((threshold_ix_name, _)) = thresholds.get(&(scenario.clone(), *index)) else {
+            panic!(
+                "no threshold for {scenario} {index} {ix_name} in {path}, \
+                rerun with {UPDATE_CU_THRESHOLDS_ENV_VAR}=1"
+            )
+        };
+        assert_eq!(threshold_ix_name, ix_name, "{path} {scenario} {index}");
+    }
+    for (scenario, index) in thresholds.keys() {
+        assert!(
+            expected.contains_key(&(scenario.clone(), *index)),
+            "threshold for removed scenario instruction {scenario} {index} in {path}",
+        );
+    }
+}
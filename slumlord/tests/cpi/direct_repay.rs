@@ -0,0 +1,107 @@
+use slumlord_lib::check_repaid_ix_full;
+use slumlord_test_utils::{
+    SlumlordBanksClient, SlumlordProgramTest, SlumlordTestClient, SlumlordTestHarness,
+};
+use solana_program_test::processor;
+use solana_sdk::{account::Account, signer::Signer, transaction::Transaction};
+
+use crate::SLUMLORD_LAMPORTS;
+
+// 1 SOL
+const VAULT_LAMPORTS: u64 = 1_000_000_000;
+
+mod direct_repay_program {
+    use sanctum_misc_utils::load_accounts;
+    use slumlord_interface::{borrow_invoke, borrow_ix, BorrowAccounts};
+    use slumlord_lib::{repay_direct, BorrowFreeArgs, RepayDirectAccounts};
+    use solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    };
+
+    sanctum_macros::declare_program_keys!(
+        "ATEywDctNaqu58pMrYTiZH5uTjTcz5kPBcNyXpuv7GWA",
+        [("vault", b"vault")]
+    );
+
+    pub fn process_instruction(
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        _instruction_data: &[u8],
+    ) -> ProgramResult {
+        let borrow_accounts: BorrowAccounts = load_accounts(accounts)?;
+        // borrows into the program-owned vault, then repays from it
+        // without going through the system program
+        borrow_invoke(borrow_accounts)?;
+        repay_direct(RepayDirectAccounts {
+            slumlord: borrow_accounts.slumlord,
+            src: borrow_accounts.dst,
+        })?;
+        Ok(())
+    }
+
+    pub fn direct_repay_ix() -> Instruction {
+        let mut ix = borrow_ix(BorrowFreeArgs { dst: VAULT_ID }).unwrap();
+        ix.accounts.push(AccountMeta {
+            pubkey: slumlord_lib::program::ID,
+            is_signer: false,
+            is_writable: false,
+        });
+        ix.program_id = ID;
+        ix
+    }
+}
+
+trait DirectRepayProgramTest {
+    fn add_direct_repay_program(self) -> Self;
+}
+
+impl<T: SlumlordProgramTest> DirectRepayProgramTest for T {
+    fn add_direct_repay_program(self) -> Self {
+        let pt = self.add_native_program(
+            "direct_repay_program",
+            direct_repay_program::ID,
+            processor!(direct_repay_program::process_instruction),
+        );
+        pt.add_test_account(
+            direct_repay_program::VAULT_ID,
+            Account {
+                lamports: VAULT_LAMPORTS,
+                data: Vec::new(),
+                owner: direct_repay_program::ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        )
+    }
+}
+
+harness_tests!(direct_repay_program_success,);
+
+async fn direct_repay_program_success<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_direct_repay_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+    let mut tx = Transaction::new_with_payer(
+        &[
+            direct_repay_program::direct_repay_ix(),
+            check_repaid_ix_full().unwrap(),
+        ],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+    let vault = banks_client
+        .get_account(direct_repay_program::VAULT_ID)
+        .await
+        .unwrap();
+    assert_eq!(vault.lamports, VAULT_LAMPORTS);
+}
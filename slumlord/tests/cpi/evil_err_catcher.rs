@@ -1,13 +1,13 @@
 use sanctum_solana_test_utils::assert_custom_err;
 use slumlord_interface::{borrow_ix, SlumlordError};
 use slumlord_lib::{check_repaid_ix_full, BorrowFreeArgs};
-use solana_program_test::{processor, ProgramTest};
+use slumlord_test_utils::{
+    SlumlordBanksClient, SlumlordProgramTest, SlumlordTestClient, SlumlordTestHarness,
+};
+use solana_program_test::processor;
 use solana_sdk::{signer::Signer, transaction::Transaction};
 
-use crate::{
-    common::{SlumlordBanksClient, SlumlordProgramTest},
-    SLUMLORD_LAMPORTS,
-};
+use crate::SLUMLORD_LAMPORTS;
 
 mod evil_err_catcher_program {
     use sanctum_misc_utils::load_accounts;
@@ -55,28 +55,28 @@ trait EvilErrCatcherProgramTest {
     fn add_evil_err_catcher_program(self) -> Self;
 }
 
-impl EvilErrCatcherProgramTest for ProgramTest {
-    fn add_evil_err_catcher_program(mut self) -> Self {
-        // cant cargo-test-sbf since we dont actually build a
-        // evil_err_catcher.so
-        // This line needs to come before add_program() to take effect
-        self.prefer_bpf(false);
-        self.add_program(
+impl<T: SlumlordProgramTest> EvilErrCatcherProgramTest for T {
+    fn add_evil_err_catcher_program(self) -> Self {
+        self.add_native_program(
             "evil_err_catcher",
             evil_err_catcher_program::ID,
             processor!(evil_err_catcher_program::process_instruction),
-        );
-        self
+        )
     }
 }
 
-#[tokio::test]
-async fn evil_err_catcher_fails_with_no_top_level_checkrepaid() {
-    let pt = ProgramTest::default()
+harness_tests!(
+    evil_err_catcher_fails_with_no_top_level_checkrepaid,
+    evil_err_catcher_fails,
+    evil_err_catcher_blocks_future_borrows,
+);
+
+async fn evil_err_catcher_fails_with_no_top_level_checkrepaid<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS)
         .add_evil_err_catcher_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let ix = evil_err_catcher_program::evil_err_catcher_ix(payer.pubkey());
     let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
     tx.sign(&[&payer], last_blockhash);
@@ -88,13 +88,12 @@ async fn evil_err_catcher_fails_with_no_top_level_checkrepaid() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn evil_err_catcher_fails() {
-    let pt = ProgramTest::default()
+async fn evil_err_catcher_fails<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS)
         .add_evil_err_catcher_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let ix = evil_err_catcher_program::evil_err_catcher_ix(payer.pubkey());
     let mut tx = Transaction::new_with_payer(
         &[ix, check_repaid_ix_full().unwrap()],
@@ -109,13 +108,12 @@ async fn evil_err_catcher_fails() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn evil_err_catcher_blocks_future_borrows() {
-    let pt = ProgramTest::default()
+async fn evil_err_catcher_blocks_future_borrows<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS)
         .add_evil_err_catcher_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let ix = evil_err_catcher_program::evil_err_catcher_ix(payer.pubkey());
     let mut tx = Transaction::new_with_payer(
         &[
@@ -0,0 +1,315 @@
+use sanctum_solana_test_utils::assert_custom_err;
+use slumlord_interface::{
+    FlashLoanWithCallbackIxArgs, SetPoolLimitsIxArgs, SlumlordError,
+    FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN,
+};
+use slumlord_lib::{
+    borrower_account_metas, flash_loan_with_callback_ix_full, FlashLoanWithCallbackFreeArgs,
+};
+use slumlord_test_utils::{
+    assert_instruction_err, SlumlordBanksClient, SlumlordProgramTest, SlumlordTestClient,
+    SlumlordTestHarness,
+};
+use solana_program_test::processor;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::Transaction,
+};
+
+use crate::{add_borrower_tx, enable_allowlist_tx, set_pool_limits_tx, SLUMLORD_LAMPORTS};
+
+// 1 SOL
+const VAULT_LAMPORTS: u64 = 1_000_000_000;
+
+const LOAN_LAMPORTS: u64 = SLUMLORD_LAMPORTS - 1;
+
+pub mod flash_borrower_program {
+    use sanctum_misc_utils::load_accounts;
+    use slumlord_interface::{
+        on_flash_loan_verify_account_privileges, OnFlashLoanAccounts, OnFlashLoanIxData,
+    };
+    use slumlord_lib::{repay_direct, RepayDirectAccounts};
+    use solana_program::{
+        account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    sanctum_macros::declare_program_keys!(
+        "7A4mpszrJVMVpyvfN3oKgCAPHZ9GGTijWttt4WHiZgRB",
+        [("vault", b"vault")]
+    );
+
+    pub const REPAY: u8 = 0;
+
+    pub const NO_REPAY: u8 = 1;
+
+    /// Expects the vault as receiver, a signing initiator and a single writable remaining account.
+    /// `data` is `[REPAY]` or `[NO_REPAY]`
+    pub fn process_instruction(
+        _program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        instruction_data: &[u8],
+    ) -> ProgramResult {
+        let args = OnFlashLoanIxData::deserialize(instruction_data)?.0;
+        let on_flash_loan_accounts: OnFlashLoanAccounts = load_accounts(accounts)?;
+        on_flash_loan_verify_account_privileges(on_flash_loan_accounts).map_err(|(_, e)| e)?;
+        let [_, _, _, extra] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let receiver = on_flash_loan_accounts.receiver;
+        if *receiver.key != VAULT_ID
+            || receiver.lamports() != super::VAULT_LAMPORTS + args.amount
+            || !extra.is_writable
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+        match args.data.as_slice() {
+            [REPAY] => {
+                repay_direct(RepayDirectAccounts {
+                    slumlord: on_flash_loan_accounts.slumlord,
+                    src: on_flash_loan_accounts.receiver,
+                })?;
+                Ok(())
+            }
+            [NO_REPAY] => Ok(()),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub trait FlashBorrowerProgramTest {
+    fn add_flash_borrower_program(self) -> Self;
+}
+
+impl<T: SlumlordProgramTest> FlashBorrowerProgramTest for T {
+    fn add_flash_borrower_program(self) -> Self {
+        let pt = self.add_native_program(
+            "flash_borrower_program",
+            flash_borrower_program::ID,
+            processor!(flash_borrower_program::process_instruction),
+        );
+        pt.add_test_account(
+            flash_borrower_program::VAULT_ID,
+            Account {
+                lamports: VAULT_LAMPORTS,
+                data: Vec::new(),
+                owner: flash_borrower_program::ID,
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        )
+    }
+}
+
+/// `FlashLoanWithCallback` of `amount` to the vault, initiated by `payer` and with `payer`
+/// as the flash borrower program's remaining account after `borrower_accounts`
+pub fn flash_loan_ix(
+    payer: Pubkey,
+    amount: u64,
+    mode: u8,
+    borrower_accounts: &[AccountMeta],
+) -> Instruction {
+    let mut remaining_accounts = borrower_accounts.to_vec();
+    remaining_accounts.push(AccountMeta::new(payer, true));
+    flash_loan_with_callback_ix_full(
+        FlashLoanWithCallbackFreeArgs {
+            receiver: flash_borrower_program::VAULT_ID,
+            receiver_program: flash_borrower_program::ID,
+            initiator: payer,
+        },
+        FlashLoanWithCallbackIxArgs {
+            amount,
+            data: vec![mode],
+        },
+        &remaining_accounts,
+    )
+    .unwrap()
+}
+
+fn flash_loan_tx(payer: &dyn Signer, mode: u8) -> Transaction {
+    let ix = flash_loan_ix(payer.pubkey(), LOAN_LAMPORTS, mode, &[]);
+    Transaction::new_with_payer(&[ix], Some(&payer.pubkey()))
+}
+
+harness_tests!(
+    flash_loan_with_callback_success,
+    flash_loan_with_callback_not_repaid_fail,
+    flash_loan_with_callback_initiator_not_signer_fail,
+    allowlist_flash_loan_with_callback,
+    pool_limits_flash_loan_with_callback,
+);
+
+async fn flash_loan_with_callback_success<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_flash_borrower_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+    // no CheckRepaid required
+    let mut tx = flash_loan_tx(&payer, flash_borrower_program::REPAY);
+    tx.sign(&[&payer], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+    let vault = banks_client
+        .get_account(flash_borrower_program::VAULT_ID)
+        .await
+        .unwrap();
+    assert_eq!(vault.lamports, VAULT_LAMPORTS);
+}
+
+async fn flash_loan_with_callback_not_repaid_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_flash_borrower_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+    let mut tx = flash_loan_tx(&payer, flash_borrower_program::NO_REPAY);
+    tx.sign(&[&payer], last_blockhash);
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+    assert_custom_err(err, SlumlordError::InsufficientRepay);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn flash_loan_with_callback_initiator_not_signer_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_flash_borrower_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+    let initiator = Pubkey::new_unique();
+    let mut ix = flash_loan_ix(
+        payer.pubkey(),
+        LOAN_LAMPORTS,
+        flash_borrower_program::REPAY,
+        &[],
+    );
+    ix.accounts[FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN - 1] =
+        AccountMeta::new_readonly(initiator, false);
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+    assert_instruction_err(err, 0, InstructionError::MissingRequiredSignature);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+}
+
+async fn allowlist_flash_loan_with_callback<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey())
+        .add_flash_borrower_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+    banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &admin,
+            payer.pubkey(),
+            last_blockhash,
+        ))
+        .await
+        .unwrap();
+    let slumlord_lamports = banks_client.get_slumlord_acc().await.lamports;
+
+    // no borrower accounts
+    let mut tx = flash_loan_tx(&payer, flash_borrower_program::REPAY);
+    tx.sign(&[&payer], last_blockhash);
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+    assert_custom_err(err, SlumlordError::BorrowerNotAllowed);
+
+    // borrower accounts go before the callback accounts and are not passed to the callback
+    let ix = flash_loan_ix(
+        payer.pubkey(),
+        LOAN_LAMPORTS,
+        flash_borrower_program::REPAY,
+        &borrower_account_metas(payer.pubkey()),
+    );
+    let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+}
+
+async fn pool_limits_flash_loan_with_callback<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey())
+        .add_flash_borrower_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let flash_loan_tx = |amount: u64| {
+        let ix = flash_loan_ix(payer.pubkey(), amount, flash_borrower_program::REPAY, &[]);
+        let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], last_blockhash);
+        tx
+    };
+
+    // amount above the cap
+    banks_client
+        .process_transaction(set_pool_limits_tx(
+            &payer,
+            &admin,
+            last_blockhash,
+            SetPoolLimitsIxArgs {
+                max_borrow_lamports: LOAN_LAMPORTS - 1,
+                reserve_lamports: 0,
+            },
+        ))
+        .await
+        .unwrap();
+    let slumlord_lamports = banks_client.get_slumlord_acc().await.lamports;
+    banks_client
+        .process_transaction(flash_loan_tx(LOAN_LAMPORTS - 1))
+        .await
+        .unwrap();
+    let err = banks_client
+        .process_transaction(flash_loan_tx(LOAN_LAMPORTS))
+        .await
+        .unwrap_err();
+    assert_custom_err(err, SlumlordError::InsufficientLiquidity);
+
+    // amount eating into the reserve,
+    // not LOAN_LAMPORTS since resending an identical tx fails with AlreadyProcessed
+    let reserve_lamports = slumlord_lamports - LOAN_LAMPORTS;
+    banks_client
+        .process_transaction(set_pool_limits_tx(
+            &payer,
+            &admin,
+            last_blockhash,
+            SetPoolLimitsIxArgs {
+                max_borrow_lamports: 0,
+                reserve_lamports,
+            },
+        ))
+        .await
+        .unwrap();
+    let err = banks_client
+        .process_transaction(flash_loan_tx(LOAN_LAMPORTS + 1))
+        .await
+        .unwrap_err();
+    assert_custom_err(err, SlumlordError::InsufficientLiquidity);
+
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+}
@@ -1,25 +1,29 @@
 use sanctum_solana_test_utils::assert_custom_err;
 use slumlord_interface::{borrow_ix, repay_ix, SlumlordError};
 use slumlord_lib::{check_repaid_ix_full, BorrowFreeArgs, RepayFreeArgs};
-use solana_program_test::{processor, ProgramTest};
+use slumlord_test_utils::{
+    SlumlordBanksClient, SlumlordProgramTest, SlumlordTestClient, SlumlordTestHarness,
+};
+use solana_program_test::processor;
 use solana_sdk::{signer::Signer, transaction::Transaction};
 
-use crate::{
-    common::{SlumlordBanksClient, SlumlordProgramTest},
-    SLUMLORD_LAMPORTS,
-};
+use crate::SLUMLORD_LAMPORTS;
 
-mod good_program {
+pub mod good_program {
     use sanctum_misc_utils::load_accounts;
     use slumlord_interface::{
-        borrow_invoke, borrow_ix, check_repaid_invoke, repay_invoke, BorrowAccounts,
-        CheckRepaidAccounts, RepayAccounts, BORROW_IX_ACCOUNTS_LEN,
+        borrow_ix, repay_invoke, BorrowAccounts, CheckRepaidAccounts, CheckRepaidReturn,
+        RepayAccounts, BORROW_IX_ACCOUNTS_LEN,
+    };
+    use slumlord_lib::{
+        borrow_invoke_with_return, check_repaid_invoke_with_return, try_loan_context,
+        BorrowFreeArgs,
     };
-    use slumlord_lib::BorrowFreeArgs;
     use solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
         instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
         pubkey::Pubkey,
         system_program,
     };
@@ -33,16 +37,32 @@ mod good_program {
     ) -> ProgramResult {
         let borrow_accounts: BorrowAccounts = load_accounts(accounts)?;
         let system_program = &accounts[BORROW_IX_ACCOUNTS_LEN];
+        let slumlord_lamports = borrow_accounts.slumlord.lamports();
         // just borrows, then immediately repays + checkrepaid
-        borrow_invoke(borrow_accounts)?;
+        let lent_lamports = borrow_invoke_with_return(borrow_accounts)?;
+        if lent_lamports != slumlord_lamports - 1 {
+            return Err(ProgramError::InvalidArgument);
+        }
+        // only recorded by migrated slumlord accounts
+        let records_lent_lamports =
+            try_loan_context(&borrow_accounts.slumlord.try_borrow_data()?)?.is_some();
         repay_invoke(RepayAccounts {
             slumlord: borrow_accounts.slumlord,
             src: borrow_accounts.dst,
             system_program,
         })?;
-        check_repaid_invoke(CheckRepaidAccounts {
+        let loan = check_repaid_invoke_with_return(CheckRepaidAccounts {
             slumlord: borrow_accounts.slumlord,
-        })
+        })?;
+        let expected_loan = CheckRepaidReturn {
+            old_lamports: slumlord_lamports,
+            repaid: records_lent_lamports.then_some(lent_lamports),
+            surplus: 0,
+        };
+        if loan != Some(expected_loan) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
     }
 
     pub fn good_ix(dst: Pubkey) -> Instruction {
@@ -62,32 +82,32 @@ mod good_program {
     }
 }
 
-trait GoodProgramTest {
+pub trait GoodProgramTest {
     fn add_good_program(self) -> Self;
 }
 
-impl GoodProgramTest for ProgramTest {
-    fn add_good_program(mut self) -> Self {
-        // cant cargo-test-sbf since we dont actually build a
-        // good_program.so
-        // This line needs to come before add_program() to take effect
-        self.prefer_bpf(false);
-        self.add_program(
+impl<T: SlumlordProgramTest> GoodProgramTest for T {
+    fn add_good_program(self) -> Self {
+        self.add_native_program(
             "good_program",
             good_program::ID,
             processor!(good_program::process_instruction),
-        );
-        self
+        )
     }
 }
 
-#[tokio::test]
-async fn good_program_fails_with_no_top_level_checkrepaid() {
-    let pt = ProgramTest::default()
+harness_tests!(
+    good_program_fails_with_no_top_level_checkrepaid,
+    good_program_success,
+    good_program_does_not_block_future_borrows,
+);
+
+async fn good_program_fails_with_no_top_level_checkrepaid<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS)
         .add_good_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let ix = good_program::good_ix(payer.pubkey());
     let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
     tx.sign(&[&payer], last_blockhash);
@@ -99,13 +119,12 @@ async fn good_program_fails_with_no_top_level_checkrepaid() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn good_program_success() {
-    let pt = ProgramTest::default()
+async fn good_program_success<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS)
         .add_good_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let ix = good_program::good_ix(payer.pubkey());
     let mut tx = Transaction::new_with_payer(
         &[ix, check_repaid_ix_full().unwrap()],
@@ -119,13 +138,12 @@ async fn good_program_success() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn good_program_does_not_block_future_borrows() {
-    let pt = ProgramTest::default()
+async fn good_program_does_not_block_future_borrows<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS)
         .add_good_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let ix = good_program::good_ix(payer.pubkey());
     let mut tx = Transaction::new_with_payer(
         &[
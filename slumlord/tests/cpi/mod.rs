@@ -1,2 +1,4 @@
+mod direct_repay;
 mod evil_err_catcher;
-mod good;
+pub mod flash_borrower;
+pub mod good;
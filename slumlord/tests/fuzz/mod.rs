@@ -0,0 +1,172 @@
+//! Executes random sequences of instructions in a single transaction
+//! and compares the results against [`model::Model`] and
+//! [`slumlord_lib::simulator::Simulator`]
+
+mod model;
+
+use model::{Model, Op};
+use proptest::prelude::*;
+use sanctum_system_program_lib::{transfer_ix, TransferKeys};
+use slumlord_interface::{borrow_ix, repay_ix};
+use slumlord_lib::{
+    check_repaid_ix_full, init_ix_full,
+    program::SLUMLORD_ID,
+    simulator::{SimAccount, SimulationError, Simulator},
+    BorrowFreeArgs, RepayFreeArgs,
+};
+use slumlord_test_utils::{
+    BankTest, SlumlordAccountBuilder, SlumlordBanksClient, SlumlordTestClient, SlumlordTestHarness,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::{BanksClientError, ProgramTest};
+use solana_sdk::{signer::Signer, transaction::Transaction, transaction::TransactionError};
+
+use crate::{
+    cpi::good::{good_program, GoodProgramTest},
+    SLUMLORD_LAMPORTS,
+};
+
+const MAX_OPS_PER_TX: usize = 8;
+
+fn op_strategy() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        Just(Op::Init),
+        Just(Op::Borrow),
+        Just(Op::Repay),
+        Just(Op::CheckRepaid),
+        Just(Op::GoodCpi),
+        prop_oneof![
+            Just(SLUMLORD_LAMPORTS - 1),
+            Just(SLUMLORD_LAMPORTS - 2),
+            0..SLUMLORD_LAMPORTS,
+        ]
+        .prop_map(Op::Donate),
+    ]
+}
+
+fn op_ix(op: Op, payer: Pubkey) -> Instruction {
+    match op {
+        Op::Init => init_ix_full().unwrap(),
+        Op::Borrow => borrow_ix(BorrowFreeArgs { dst: payer }).unwrap(),
+        Op::Repay => repay_ix(RepayFreeArgs { src: payer }).unwrap(),
+        Op::CheckRepaid => check_repaid_ix_full().unwrap(),
+        Op::Donate(lamports) => transfer_ix(
+            TransferKeys {
+                from: payer,
+                to: SLUMLORD_ID,
+            },
+            lamports,
+        ),
+        Op::GoodCpi => good_program::good_ix(payer),
+    }
+}
+
+async fn execute_and_compare<H: SlumlordTestHarness>(ops: Vec<Op>) {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_good_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let ixs: Vec<Instruction> = ops.iter().map(|op| op_ix(*op, payer.pubkey())).collect();
+    let mut tx = Transaction::new_with_payer(&ixs, Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let mut simulator = Simulator::new();
+    simulator.set_account(
+        SLUMLORD_ID,
+        SlumlordAccountBuilder::unmigrated(SLUMLORD_LAMPORTS)
+            .into_account()
+            .into(),
+    );
+    simulator.set_account(
+        payer.pubkey(),
+        SimAccount {
+            lamports: banks_client
+                .get_account(payer.pubkey())
+                .await
+                .unwrap()
+                .lamports,
+            ..Default::default()
+        },
+    );
+    let simulated = simulator.simulate_message(&tx.message);
+
+    let actual = banks_client.process_transaction(tx).await;
+    let expected = Model::new(SLUMLORD_LAMPORTS).apply_tx(&ops);
+
+    match (&simulated, &expected) {
+        // simulator can't execute good_program's CPIs
+        (Err(SimulationError::UnsupportedInstruction(idx)), _) => {
+            assert_eq!(ops[usize::from(*idx)], Op::GoodCpi, "{ops:?}");
+        }
+        (Ok(res), Ok(model)) => {
+            assert_eq!(
+                res.accounts[&SLUMLORD_ID].lamports, model.slumlord_lamports,
+                "{ops:?}"
+            );
+        }
+        (
+            Err(SimulationError::InstructionError(simulated_idx, simulated_err)),
+            Err((expected_idx, expected_err)),
+        ) => {
+            assert_eq!(
+                (simulated_idx, simulated_err),
+                (expected_idx, expected_err),
+                "{ops:?}"
+            );
+        }
+        (simulated, expected) => {
+            panic!("{ops:?}: simulated {simulated:?}, expected {expected:?}")
+        }
+    }
+
+    match (actual, expected) {
+        (Ok(()), Ok(model)) => {
+            assert!(model.old_lamports.is_none(), "{ops:?}");
+            assert!(model.slumlord_lamports >= SLUMLORD_LAMPORTS, "{ops:?}");
+            banks_client
+                .assert_slumlord_balance(model.slumlord_lamports)
+                .await;
+        }
+        (Err(err), Err((expected_idx, expected_err))) => {
+            let actual_err = match err {
+                BanksClientError::TransactionError(e)
+                | BanksClientError::SimulationError { err: e, .. } => e,
+                e => panic!("{ops:?}: unexpected error {e:?}"),
+            };
+            assert_eq!(
+                actual_err,
+                TransactionError::InstructionError(expected_idx, expected_err),
+                "{ops:?}"
+            );
+            banks_client
+                .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+                .await;
+        }
+        (actual, expected) => panic!("{ops:?}: actual {actual:?}, expected {expected:?}"),
+    }
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn random_tx_matches_model(ops in proptest::collection::vec(op_strategy(), 1..=MAX_OPS_PER_TX)) {
+        block_on(execute_and_compare::<ProgramTest>(ops));
+    }
+
+    #[test]
+    fn random_tx_matches_model_bank_test(ops in proptest::collection::vec(op_strategy(), 1..=MAX_OPS_PER_TX)) {
+        block_on(execute_and_compare::<BankTest>(ops));
+    }
+}
+
+fn block_on(fut: impl std::future::Future<Output = ()>) {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(fut)
+}
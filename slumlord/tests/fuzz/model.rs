@@ -0,0 +1,99 @@
+//! Pure rust reference model of the slumlord program's behaviour
+//! over a sequence of instructions in a single transaction
+
+use slumlord_interface::SlumlordError;
+use solana_program::instruction::InstructionError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Init,
+    Borrow,
+    Repay,
+    CheckRepaid,
+    /// System transfer of the given lamports from payer to slumlord
+    Donate(u64),
+    /// `good_program`'s borrow, repay, check repaid via CPI
+    GoodCpi,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Model {
+    pub slumlord_lamports: u64,
+    /// `Some` while a flash loan is active
+    pub old_lamports: Option<u64>,
+}
+
+impl Model {
+    pub fn new(slumlord_lamports: u64) -> Self {
+        Self {
+            slumlord_lamports,
+            old_lamports: None,
+        }
+    }
+
+    /// Applies all `ops` as a single transaction, returning
+    /// the final state or the index and error of the first failing op
+    pub fn apply_tx(mut self, ops: &[Op]) -> Result<Self, (u8, InstructionError)> {
+        for (i, op) in ops.iter().enumerate() {
+            let has_succeeding_check_repaid = ops[i + 1..].contains(&Op::CheckRepaid);
+            self.apply(*op, has_succeeding_check_repaid)
+                .map_err(|e| (i.try_into().unwrap(), e))?;
+        }
+        Ok(self)
+    }
+
+    fn apply(&mut self, op: Op, has_succeeding_check_repaid: bool) -> Result<(), InstructionError> {
+        match op {
+            Op::Init => Ok(()),
+            Op::Borrow => {
+                self.borrow(has_succeeding_check_repaid)?;
+                Ok(())
+            }
+            Op::Repay => {
+                let old_lamports = self
+                    .old_lamports
+                    .ok_or_else(|| custom_err(SlumlordError::NoActiveLoan))?;
+                self.slumlord_lamports = self.slumlord_lamports.max(old_lamports);
+                Ok(())
+            }
+            Op::CheckRepaid => self.check_repaid(),
+            Op::Donate(lamports) => {
+                self.slumlord_lamports += lamports;
+                Ok(())
+            }
+            Op::GoodCpi => {
+                let old_lamports = self.borrow(has_succeeding_check_repaid)?;
+                self.slumlord_lamports = old_lamports;
+                self.check_repaid()
+            }
+        }
+    }
+
+    /// Returns the pre-loan lamports
+    fn borrow(&mut self, has_succeeding_check_repaid: bool) -> Result<u64, InstructionError> {
+        if !has_succeeding_check_repaid {
+            return Err(custom_err(SlumlordError::NoSucceedingCheckRepaid));
+        }
+        if self.old_lamports.is_some() {
+            return Err(custom_err(SlumlordError::BorrowAlreadyActive));
+        }
+        let old_lamports = self.slumlord_lamports;
+        self.old_lamports = Some(old_lamports);
+        self.slumlord_lamports = 1;
+        Ok(old_lamports)
+    }
+
+    fn check_repaid(&mut self) -> Result<(), InstructionError> {
+        if let Some(old_lamports) = self.old_lamports {
+            if self.slumlord_lamports < old_lamports {
+                return Err(custom_err(SlumlordError::InsufficientRepay));
+            }
+            self.old_lamports = None;
+        }
+        Ok(())
+    }
+}
+
+fn custom_err(e: SlumlordError) -> InstructionError {
+    InstructionError::Custom(e as u32)
+}
@@ -1,17 +1,96 @@
-mod common;
-mod cpi;
-
-use common::*;
+use slumlord_test_utils::*;
 
-use sanctum_solana_test_utils::{assert_custom_err, ExtendedBanksClient};
+use sanctum_solana_test_utils::assert_custom_err;
 use sanctum_system_program_lib::{transfer_ix, TransferKeys};
-use slumlord_interface::{borrow_ix, repay_ix, SlumlordError};
+use slumlord_interface::{
+    add_borrower_ix, borrow_ix, borrow_with_min_ix, migrate_ix, remove_borrower_ix, repay_ix,
+    repay_with_max_ix, set_admin_ix, set_allowlist_ix, set_pool_limits_ix, BorrowWithMinIxArgs,
+    QuoteLoanReturn, RepayWithMaxIxArgs, SetAllowlistIxArgs, SetPoolLimitsIxArgs, SlumlordError,
+};
 use slumlord_lib::{
-    check_repaid_ix_full, init_ix_full, program::SLUMLORD_ID, BorrowFreeArgs, RepayFreeArgs,
+    borrow_split_ix_full, borrower_account_metas, check_repaid_ix_full,
+    find_borrower_entry_address, init_ix_full, program::SLUMLORD_ID, quote_loan_ix_full,
+    try_quote_loan_return, try_slumlord_err, verify_borrower_entry, AddBorrowerFreeArgs,
+    BorrowFreeArgs, MigrateFreeArgs, RemoveBorrowerFreeArgs, RepayFreeArgs, SetAdminFreeArgs,
+    SetAllowlistFreeArgs, SetPoolLimitsFreeArgs, BORROWER_ENTRY_LEN, SLUMLORD_ACCOUNT_LEN,
+    SLUMLORD_ACCOUNT_VERSION,
 };
-use solana_program::hash::Hash;
+use solana_program::rent::Rent;
+use solana_program::{hash::Hash, instruction::InstructionError};
 use solana_program_test::ProgramTest;
-use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+
+/// Runs each test under both [`ProgramTest`] and [`BankTest`]
+macro_rules! harness_tests {
+    ($($test:ident),* $(,)?) => {
+        mod program_test {
+            use solana_program_test::ProgramTest;
+
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test::<ProgramTest>().await
+                }
+            )*
+        }
+
+        mod bank_test {
+            use slumlord_test_utils::BankTest;
+
+            $(
+                #[tokio::test]
+                async fn $test() {
+                    super::$test::<BankTest>().await
+                }
+            )*
+        }
+    };
+}
+
+mod bench;
+mod cpi;
+mod fuzz;
+
+harness_tests!(
+    init,
+    init_twice_ok,
+    init_fail_insufficient_funds,
+    init_fail_no_funds,
+    basic,
+    basic_repay,
+    basic_repay_with_max,
+    repay_exceeds_max_fail,
+    repay_no_active_loan_fail,
+    basic_borrow_with_min,
+    borrow_below_min_fail,
+    borrow_fail_no_check_repaid,
+    borrow_fail_check_repaid_wrong_slumlord,
+    borrow_not_initialized_fail,
+    borrow_twice_fail,
+    insufficient_repay_fail,
+    loan_to_self_fail,
+    basic_borrow_split,
+    borrow_split_insufficient_repay_fail,
+    borrow_split_exceeds_pool_fail,
+    borrow_split_missing_dst_fail,
+    migrate,
+    migrate_twice_ok,
+    migrate_tops_up_rent,
+    migrate_not_initialized_fail,
+    migrate_during_loan_fail,
+    allowlist_borrow,
+    allowlist_borrow_split,
+    allowlist_other_borrowers_entry_fail,
+    add_borrower_prefunded_entry,
+    add_remove_borrower_not_admin_fail,
+    set_admin_not_upgrade_authority_fail,
+    set_admin_not_migrated_fail,
+    set_allowlist_not_admin_fail,
+    pool_limits_borrow,
+    pool_limits_borrow_split,
+    pool_limits_below_reserve_fail,
+    set_pool_limits_not_admin_fail,
+);
 
 // 0.1 SOL
 const SLUMLORD_LAMPORTS: u64 = 100_000_000;
@@ -37,6 +116,16 @@ fn borrow_donate_check_repaid_tx(payer: &Keypair, last_blockhash: Hash) -> Trans
     tx
 }
 
+fn migrate_tx(payer: &Keypair, last_blockhash: Hash) -> Transaction {
+    let migrate_ix = migrate_ix(MigrateFreeArgs {
+        payer: payer.pubkey(),
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[migrate_ix], Some(&payer.pubkey()));
+    tx.sign(&[payer], last_blockhash);
+    tx
+}
+
 fn fund_and_init_tx(payer: &Keypair, last_blockhash: Hash) -> Transaction {
     let fund_ix = transfer_ix(
         TransferKeys {
@@ -51,14 +140,14 @@ fn fund_and_init_tx(payer: &Keypair, last_blockhash: Hash) -> Transaction {
     tx
 }
 
-#[tokio::test]
-async fn init() {
-    let pt = ProgramTest::default().add_slumlord_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+async fn init<H: SlumlordTestHarness>() {
+    let pt = H::default().add_slumlord_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     banks_client
         .process_transaction(fund_and_init_tx(&payer, last_blockhash))
         .await
         .unwrap();
+    banks_client.assert_slumlord_initialized().await;
     banks_client
         .assert_slumlord_balance(SLUMLORD_LAMPORTS)
         .await;
@@ -73,10 +162,9 @@ async fn init() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn init_twice_ok() {
-    let pt = ProgramTest::default().add_slumlord_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+async fn init_twice_ok<H: SlumlordTestHarness>() {
+    let pt = H::default().add_slumlord_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     banks_client
         .process_transaction(fund_and_init_tx(&payer, last_blockhash))
         .await
@@ -100,10 +188,9 @@ async fn init_twice_ok() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn init_fail_insufficient_funds() {
-    let pt = ProgramTest::default().add_slumlord_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+async fn init_fail_insufficient_funds<H: SlumlordTestHarness>() {
+    let pt = H::default().add_slumlord_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let insufficient_fund_ix = transfer_ix(
         TransferKeys {
             from: payer.pubkey(),
@@ -119,28 +206,26 @@ async fn init_fail_insufficient_funds() {
     // The program logs will end with "success", but the tx actly failed
     // TODO: assert == `TransactionError(InsufficientFundsForRent { account_index: 1 })`
     banks_client.process_transaction(tx).await.unwrap_err();
-    banks_client.assert_account_not_exist(SLUMLORD_ID).await;
+    banks_client.assert_slumlord_not_exist().await;
 }
 
-#[tokio::test]
-async fn init_fail_no_funds() {
-    let pt = ProgramTest::default().add_slumlord_program();
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+async fn init_fail_no_funds<H: SlumlordTestHarness>() {
+    let pt = H::default().add_slumlord_program();
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
     let mut tx = Transaction::new_with_payer(&[init_ix_full().unwrap()], Some(&payer.pubkey()));
     tx.sign(&[&payer], last_blockhash);
     // if slumlord has no lamports at all then the tx succeeds,
     // but the account is not created
     banks_client.process_transaction(tx).await.unwrap();
-    banks_client.assert_account_not_exist(SLUMLORD_ID).await;
+    banks_client.assert_slumlord_not_exist().await;
 }
 
-#[tokio::test]
-async fn basic() {
-    let pt = ProgramTest::default()
+async fn basic<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS);
 
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
 
     let tx = borrow_donate_check_repaid_tx(&payer, last_blockhash);
 
@@ -152,13 +237,12 @@ async fn basic() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn basic_repay() {
-    let pt = ProgramTest::default()
+async fn basic_repay<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS);
 
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
 
     let borrow_ix = borrow_ix(BorrowFreeArgs {
         dst: payer.pubkey(),
@@ -184,13 +268,158 @@ async fn basic_repay() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn borrow_fail_no_check_repaid() {
-    let pt = ProgramTest::default()
+fn borrow_repay_with_max_check_repaid_tx(
+    payer: &Keypair,
+    last_blockhash: Hash,
+    max_lamports: u64,
+) -> Transaction {
+    let borrow_ix = borrow_ix(BorrowFreeArgs {
+        dst: payer.pubkey(),
+    })
+    .unwrap();
+    let repay_with_max_ix = repay_with_max_ix(
+        RepayFreeArgs {
+            src: payer.pubkey(),
+        },
+        RepayWithMaxIxArgs { max_lamports },
+    )
+    .unwrap();
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_ix, repay_with_max_ix, check_repaid_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[payer], last_blockhash);
+    tx
+}
+
+async fn basic_repay_with_max<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS);
 
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let tx = borrow_repay_with_max_check_repaid_tx(&payer, last_blockhash, SLUMLORD_LAMPORTS - 1);
+
+    banks_client.process_transaction(tx).await.unwrap();
+
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn repay_exceeds_max_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let tx = borrow_repay_with_max_check_repaid_tx(&payer, last_blockhash, SLUMLORD_LAMPORTS - 2);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::RepayExceedsMax);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn repay_no_active_loan_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let repay_ix = repay_ix(RepayFreeArgs {
+        src: payer.pubkey(),
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[repay_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NoActiveLoan);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+fn borrow_with_min_donate_check_repaid_tx(
+    payer: &Keypair,
+    last_blockhash: Hash,
+    min_lamports: u64,
+) -> Transaction {
+    let borrow_with_min_ix = borrow_with_min_ix(
+        BorrowFreeArgs {
+            dst: payer.pubkey(),
+        },
+        BorrowWithMinIxArgs { min_lamports },
+    )
+    .unwrap();
+    let donate_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        SLUMLORD_LAMPORTS - 1,
+    );
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_with_min_ix, donate_ix, check_repaid_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[payer], last_blockhash);
+    tx
+}
+
+async fn basic_borrow_with_min<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let tx = borrow_with_min_donate_check_repaid_tx(&payer, last_blockhash, SLUMLORD_LAMPORTS - 1);
+
+    banks_client.process_transaction(tx).await.unwrap();
+
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn borrow_below_min_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let tx = borrow_with_min_donate_check_repaid_tx(&payer, last_blockhash, SLUMLORD_LAMPORTS);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::InsufficientLiquidity);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn borrow_fail_no_check_repaid<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
 
     let borrow_ix = borrow_ix(BorrowFreeArgs {
         dst: payer.pubkey(),
@@ -215,13 +444,57 @@ async fn borrow_fail_no_check_repaid() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn borrow_twice_fail() {
-    let pt = ProgramTest::default()
+/// The CheckRepaid has the right discriminant but references the wrong account,
+/// so it would fail if it were ever executed and can't end the loan
+async fn borrow_fail_check_repaid_wrong_slumlord<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS);
 
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let borrow_ix = borrow_ix(BorrowFreeArgs {
+        dst: payer.pubkey(),
+    })
+    .unwrap();
+    let mut check_repaid_ix = check_repaid_ix_full().unwrap();
+    check_repaid_ix.accounts[0].pubkey = payer.pubkey();
+    let mut tx = Transaction::new_with_payer(&[borrow_ix, check_repaid_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NoSucceedingCheckRepaid);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn borrow_not_initialized_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_uninitialized_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let tx = borrow_donate_check_repaid_tx(&payer, last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NotInitialized);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn borrow_twice_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
 
     let borrow_ix = borrow_ix(BorrowFreeArgs {
         dst: payer.pubkey(),
@@ -250,13 +523,12 @@ async fn borrow_twice_fail() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn insufficient_repay_fail() {
-    let pt = ProgramTest::default()
+async fn insufficient_repay_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS);
 
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
 
     let borrow_ix = borrow_ix(BorrowFreeArgs {
         dst: payer.pubkey(),
@@ -285,25 +557,1094 @@ async fn insufficient_repay_fail() {
     banks_client.assert_slumlord_data_empty().await;
 }
 
-#[tokio::test]
-async fn loan_to_self_fail() {
-    let pt = ProgramTest::default()
+async fn loan_to_self_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
         .add_slumlord_program()
         .add_slumlord(SLUMLORD_LAMPORTS);
 
-    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
 
     let borrow_ix = borrow_ix(BorrowFreeArgs { dst: SLUMLORD_ID }).unwrap();
     let check_repaid_ix = check_repaid_ix_full().unwrap();
     let mut tx = Transaction::new_with_payer(&[borrow_ix, check_repaid_ix], Some(&payer.pubkey()));
     tx.sign(&[&payer], last_blockhash);
 
-    // The program logs will end with "success", but the tx actly failed
-    // TODO: assert == TransactionError(InstructionError(0, UnbalancedInstruction))
-    banks_client.process_transaction(tx).await.unwrap_err();
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::LoanToSelf);
+
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn basic_borrow_split<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let other = Keypair::new();
+    let payer_amount = SLUMLORD_LAMPORTS / 2;
+    let other_amount = SLUMLORD_LAMPORTS - payer_amount - 1;
+
+    let borrow_split_ix = borrow_split_ix_full(&[
+        (payer.pubkey(), payer_amount),
+        (other.pubkey(), other_amount),
+    ])
+    .unwrap();
+    let payer_donate_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        payer_amount,
+    );
+    let other_donate_ix = transfer_ix(
+        TransferKeys {
+            from: other.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        other_amount,
+    );
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[
+            borrow_split_ix,
+            payer_donate_ix,
+            other_donate_ix,
+            check_repaid_ix,
+        ],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer, &other], last_blockhash);
+
+    banks_client.process_transaction(tx).await.unwrap();
+
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn borrow_split_insufficient_repay_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let borrow_split_ix =
+        borrow_split_ix_full(&[(payer.pubkey(), 1), (payer.pubkey(), 2)]).unwrap();
+    let insufficient_donate_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        2,
+    );
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_split_ix, insufficient_donate_ix, check_repaid_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::InsufficientRepay);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn borrow_split_exceeds_pool_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let borrow_split_ix = borrow_split_ix_full(&[
+        (payer.pubkey(), SLUMLORD_LAMPORTS / 2),
+        (payer.pubkey(), SLUMLORD_LAMPORTS / 2),
+    ])
+    .unwrap();
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx =
+        Transaction::new_with_payer(&[borrow_split_ix, check_repaid_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::InsufficientLiquidity);
 
     banks_client
         .assert_slumlord_balance(SLUMLORD_LAMPORTS)
         .await;
     banks_client.assert_slumlord_data_empty().await;
 }
+
+async fn borrow_split_missing_dst_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let mut borrow_split_ix =
+        borrow_split_ix_full(&[(payer.pubkey(), 1), (payer.pubkey(), 1)]).unwrap();
+    borrow_split_ix.accounts.pop();
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx =
+        Transaction::new_with_payer(&[borrow_split_ix, check_repaid_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_instruction_err(err, 0, InstructionError::NotEnoughAccountKeys);
+
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+async fn migrate<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(migrate_tx(&payer, last_blockhash))
+        .await
+        .unwrap();
+    banks_client
+        .assert_slumlord_version(Some(SLUMLORD_ACCOUNT_VERSION))
+        .await;
+    // already rent-exempt
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+
+    // check functionality
+    let tx = borrow_donate_check_repaid_tx(&payer, last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client
+        .assert_slumlord_version(Some(SLUMLORD_ACCOUNT_VERSION))
+        .await;
+    assert_eq!(banks_client.get_slumlord_loan_old_lamports().await, None);
+}
+
+async fn migrate_twice_ok<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let migrate_ix = migrate_ix(MigrateFreeArgs {
+        payer: payer.pubkey(),
+    })
+    .unwrap();
+    let mut tx =
+        Transaction::new_with_payer(&[migrate_ix.clone(), migrate_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+    banks_client
+        .assert_slumlord_version(Some(SLUMLORD_ACCOUNT_VERSION))
+        .await;
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+}
+
+async fn migrate_tops_up_rent<H: SlumlordTestHarness>() {
+    let rent = Rent::default();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(rent.minimum_balance(0));
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(migrate_tx(&payer, last_blockhash))
+        .await
+        .unwrap();
+    banks_client
+        .assert_slumlord_version(Some(SLUMLORD_ACCOUNT_VERSION))
+        .await;
+    banks_client
+        .assert_slumlord_balance(rent.minimum_balance(SLUMLORD_ACCOUNT_LEN))
+        .await;
+}
+
+async fn migrate_not_initialized_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_uninitialized_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let err = banks_client
+        .process_transaction(migrate_tx(&payer, last_blockhash))
+        .await
+        .unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NotInitialized);
+    banks_client.assert_slumlord_version(None).await;
+}
+
+async fn migrate_during_loan_fail<H: SlumlordTestHarness>() {
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let borrow_ix = borrow_ix(BorrowFreeArgs {
+        dst: payer.pubkey(),
+    })
+    .unwrap();
+    let migrate_ix = migrate_ix(MigrateFreeArgs {
+        payer: payer.pubkey(),
+    })
+    .unwrap();
+    let donate_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        SLUMLORD_LAMPORTS - 1,
+    );
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_ix, migrate_ix, donate_ix, check_repaid_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::BorrowAlreadyActive);
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_version(None).await;
+}
+
+/// Migrates, claims admin as `admin`, the upgrade authority, and enables allowlist mode
+fn enable_allowlist_tx(payer: &Keypair, admin: &Keypair, last_blockhash: Hash) -> Transaction {
+    let migrate_ix = migrate_ix(MigrateFreeArgs {
+        payer: payer.pubkey(),
+    })
+    .unwrap();
+    let set_admin_ix = set_admin_ix(SetAdminFreeArgs {
+        authority: admin.pubkey(),
+        new_admin: admin.pubkey(),
+    })
+    .unwrap();
+    let set_allowlist_ix = set_allowlist_ix(
+        SetAllowlistFreeArgs {
+            admin: admin.pubkey(),
+        },
+        SetAllowlistIxArgs { enabled: true },
+    )
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[migrate_ix, set_admin_ix, set_allowlist_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[payer, admin], last_blockhash);
+    tx
+}
+
+fn add_borrower_tx(
+    payer: &Keypair,
+    admin: &Keypair,
+    borrower: Pubkey,
+    last_blockhash: Hash,
+) -> Transaction {
+    let add_borrower_ix = add_borrower_ix(AddBorrowerFreeArgs {
+        admin: admin.pubkey(),
+        payer: payer.pubkey(),
+        borrower,
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[add_borrower_ix], Some(&payer.pubkey()));
+    tx.sign(&[payer, admin], last_blockhash);
+    tx
+}
+
+async fn allowlist_borrow<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+    let slumlord_lamports = banks_client.get_slumlord_acc().await.lamports;
+
+    // payer not on allowlist
+    let err = banks_client
+        .process_transaction(borrow_donate_check_repaid_tx(&payer, last_blockhash))
+        .await
+        .unwrap_err();
+    assert_custom_err(err, SlumlordError::BorrowerNotAllowed);
+
+    banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &admin,
+            payer.pubkey(),
+            last_blockhash,
+        ))
+        .await
+        .unwrap();
+    let (borrower_entry, _bump) = find_borrower_entry_address(&payer.pubkey());
+    assert!(banks_client.get_account(borrower_entry).await.is_some());
+
+    // payer signs as borrower
+    let mut borrow_ix = borrow_ix(BorrowFreeArgs {
+        dst: payer.pubkey(),
+    })
+    .unwrap();
+    borrow_ix
+        .accounts
+        .extend(borrower_account_metas(payer.pubkey()));
+    let donate_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        slumlord_lamports - 1,
+    );
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_ix, donate_ix.clone(), check_repaid_ix.clone()],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+
+    let remove_borrower_ix = remove_borrower_ix(RemoveBorrowerFreeArgs {
+        admin: admin.pubkey(),
+        borrower: payer.pubkey(),
+        refund_rent_to: payer.pubkey(),
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[remove_borrower_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &admin], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+    assert!(banks_client.get_account(borrower_entry).await.is_none());
+
+    let mut borrow_with_min_ix = borrow_with_min_ix(
+        BorrowFreeArgs {
+            dst: payer.pubkey(),
+        },
+        BorrowWithMinIxArgs { min_lamports: 0 },
+    )
+    .unwrap();
+    borrow_with_min_ix
+        .accounts
+        .extend(borrower_account_metas(payer.pubkey()));
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_with_min_ix, donate_ix, check_repaid_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+    assert_custom_err(err, SlumlordError::BorrowerNotAllowed);
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+}
+
+async fn set_admin_not_upgrade_authority_fail<H: SlumlordTestHarness>() {
+    let upgrade_authority = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(upgrade_authority.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let err = banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &payer, last_blockhash))
+        .await
+        .unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NotAdmin);
+    banks_client.assert_slumlord_version(None).await;
+}
+
+async fn set_admin_not_migrated_fail<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    let set_admin_ix = set_admin_ix(SetAdminFreeArgs {
+        authority: admin.pubkey(),
+        new_admin: admin.pubkey(),
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[set_admin_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &admin], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NoPoolConfig);
+}
+
+async fn set_allowlist_not_admin_fail<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+
+    let set_allowlist_ix = set_allowlist_ix(
+        SetAllowlistFreeArgs {
+            admin: payer.pubkey(),
+        },
+        SetAllowlistIxArgs { enabled: false },
+    )
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[set_allowlist_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NotAdmin);
+}
+
+async fn allowlist_borrow_split<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+    banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &admin,
+            payer.pubkey(),
+            last_blockhash,
+        ))
+        .await
+        .unwrap();
+    let slumlord_lamports = banks_client.get_slumlord_acc().await.lamports;
+
+    // dst is a new account so it must be lent at least the rent-exempt minimum
+    let dst = Keypair::new().pubkey();
+    let dst_lamports = Rent::default().minimum_balance(0);
+    let borrow_split_ix =
+        borrow_split_ix_full(&[(payer.pubkey(), 1), (dst, dst_lamports)]).unwrap();
+    let donate_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        1 + dst_lamports,
+    );
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+
+    // no borrower accounts
+    let mut tx = Transaction::new_with_payer(
+        &[
+            borrow_split_ix.clone(),
+            donate_ix.clone(),
+            check_repaid_ix.clone(),
+        ],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+    assert_custom_err(err, SlumlordError::BorrowerNotAllowed);
+
+    // borrower accounts go after the dsts
+    let mut allowed_borrow_split_ix = borrow_split_ix;
+    allowed_borrow_split_ix
+        .accounts
+        .extend(borrower_account_metas(payer.pubkey()));
+    let mut tx = Transaction::new_with_payer(
+        &[allowed_borrow_split_ix, donate_ix, check_repaid_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+    assert_eq!(
+        banks_client.get_account(dst).await.unwrap().lamports,
+        dst_lamports
+    );
+}
+
+async fn allowlist_other_borrowers_entry_fail<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+    let other_borrower = Keypair::new().pubkey();
+    banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &admin,
+            other_borrower,
+            last_blockhash,
+        ))
+        .await
+        .unwrap();
+
+    // payer signs as borrower with other_borrower's entry
+    let mut borrower_accounts = borrower_account_metas(payer.pubkey());
+    borrower_accounts[1].pubkey = find_borrower_entry_address(&other_borrower).0;
+    let mut borrow_ix = borrow_ix(BorrowFreeArgs {
+        dst: payer.pubkey(),
+    })
+    .unwrap();
+    borrow_ix.accounts.extend(borrower_accounts);
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_ix, check_repaid_ix_full().unwrap()],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::BorrowerNotAllowed);
+}
+
+async fn add_borrower_prefunded_entry<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+
+    // anyone can transfer lamports to the entry PDA before it is created
+    let (borrower_entry, _bump) = find_borrower_entry_address(&payer.pubkey());
+    let prefund_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: borrower_entry,
+        },
+        Rent::default().minimum_balance(0),
+    );
+    let mut tx = Transaction::new_with_payer(&[prefund_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+
+    banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &admin,
+            payer.pubkey(),
+            last_blockhash,
+        ))
+        .await
+        .unwrap();
+
+    let entry = banks_client.get_account(borrower_entry).await.unwrap();
+    assert_eq!(
+        entry.lamports,
+        Rent::default().minimum_balance(BORROWER_ENTRY_LEN)
+    );
+    verify_borrower_entry(&payer.pubkey(), &entry.owner, &entry.data).unwrap();
+}
+
+async fn add_remove_borrower_not_admin_fail<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+
+    let err = banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &payer,
+            payer.pubkey(),
+            last_blockhash,
+        ))
+        .await
+        .unwrap_err();
+    assert_custom_err(err, SlumlordError::NotAdmin);
+
+    banks_client
+        .process_transaction(add_borrower_tx(
+            &payer,
+            &admin,
+            payer.pubkey(),
+            last_blockhash,
+        ))
+        .await
+        .unwrap();
+    let remove_borrower_ix = remove_borrower_ix(RemoveBorrowerFreeArgs {
+        admin: payer.pubkey(),
+        borrower: payer.pubkey(),
+        refund_rent_to: payer.pubkey(),
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[remove_borrower_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NotAdmin);
+    let (borrower_entry, _bump) = find_borrower_entry_address(&payer.pubkey());
+    assert!(banks_client.get_account(borrower_entry).await.is_some());
+}
+
+/// Migrates, claims admin as `admin`, the upgrade authority, and sets the pool limits
+fn set_pool_limits_tx(
+    payer: &Keypair,
+    admin: &Keypair,
+    last_blockhash: Hash,
+    limits: SetPoolLimitsIxArgs,
+) -> Transaction {
+    let migrate_ix = migrate_ix(MigrateFreeArgs {
+        payer: payer.pubkey(),
+    })
+    .unwrap();
+    let set_admin_ix = set_admin_ix(SetAdminFreeArgs {
+        authority: admin.pubkey(),
+        new_admin: admin.pubkey(),
+    })
+    .unwrap();
+    let set_pool_limits_ix = set_pool_limits_ix(
+        SetPoolLimitsFreeArgs {
+            admin: admin.pubkey(),
+        },
+        limits,
+    )
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[migrate_ix, set_admin_ix, set_pool_limits_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[payer, admin], last_blockhash);
+    tx
+}
+
+fn borrow_with_min_repay_check_repaid_tx(
+    payer: &Keypair,
+    last_blockhash: Hash,
+    min_lamports: u64,
+) -> Transaction {
+    let borrow_with_min_ix = borrow_with_min_ix(
+        BorrowFreeArgs {
+            dst: payer.pubkey(),
+        },
+        BorrowWithMinIxArgs { min_lamports },
+    )
+    .unwrap();
+    let repay_ix = repay_ix(RepayFreeArgs {
+        src: payer.pubkey(),
+    })
+    .unwrap();
+    let check_repaid_ix = check_repaid_ix_full().unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[borrow_with_min_ix, repay_ix, check_repaid_ix],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[payer], last_blockhash);
+    tx
+}
+
+async fn pool_limits_borrow<H: SlumlordTestHarness>() {
+    const MAX_BORROW_LAMPORTS: u64 = SLUMLORD_LAMPORTS / 2;
+
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(set_pool_limits_tx(
+            &payer,
+            &admin,
+            last_blockhash,
+            SetPoolLimitsIxArgs {
+                max_borrow_lamports: MAX_BORROW_LAMPORTS,
+                reserve_lamports: 0,
+            },
+        ))
+        .await
+        .unwrap();
+    let slumlord_lamports = banks_client.get_slumlord_acc().await.lamports;
+
+    // lends exactly the cap
+    banks_client
+        .process_transaction(borrow_with_min_repay_check_repaid_tx(
+            &payer,
+            last_blockhash,
+            MAX_BORROW_LAMPORTS,
+        ))
+        .await
+        .unwrap();
+    let err = banks_client
+        .process_transaction(borrow_with_min_repay_check_repaid_tx(
+            &payer,
+            last_blockhash,
+            MAX_BORROW_LAMPORTS + 1,
+        ))
+        .await
+        .unwrap_err();
+    assert_custom_err(err, SlumlordError::InsufficientLiquidity);
+
+    // reserve keeps all but 10 lamports in the pool
+    let set_pool_limits_ix = set_pool_limits_ix(
+        SetPoolLimitsFreeArgs {
+            admin: admin.pubkey(),
+        },
+        SetPoolLimitsIxArgs {
+            max_borrow_lamports: 0,
+            reserve_lamports: slumlord_lamports - 10,
+        },
+    )
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[set_pool_limits_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer, &admin], last_blockhash);
+    banks_client.process_transaction(tx).await.unwrap();
+
+    banks_client
+        .process_transaction(borrow_with_min_repay_check_repaid_tx(
+            &payer,
+            last_blockhash,
+            10,
+        ))
+        .await
+        .unwrap();
+    let err = banks_client
+        .process_transaction(borrow_with_min_repay_check_repaid_tx(
+            &payer,
+            last_blockhash,
+            11,
+        ))
+        .await
+        .unwrap_err();
+    assert_custom_err(err, SlumlordError::InsufficientLiquidity);
+
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+}
+
+async fn pool_limits_borrow_split<H: SlumlordTestHarness>() {
+    const MAX_BORROW_LAMPORTS: u64 = SLUMLORD_LAMPORTS / 2;
+
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(set_pool_limits_tx(
+            &payer,
+            &admin,
+            last_blockhash,
+            SetPoolLimitsIxArgs {
+                max_borrow_lamports: MAX_BORROW_LAMPORTS,
+                reserve_lamports: 0,
+            },
+        ))
+        .await
+        .unwrap();
+    let slumlord_lamports = banks_client.get_slumlord_acc().await.lamports;
+
+    let borrow_split_tx = |second_amount: u64| {
+        let first_amount = MAX_BORROW_LAMPORTS / 2;
+        let borrow_split_ix = borrow_split_ix_full(&[
+            (payer.pubkey(), first_amount),
+            (payer.pubkey(), second_amount),
+        ])
+        .unwrap();
+        let donate_ix = transfer_ix(
+            TransferKeys {
+                from: payer.pubkey(),
+                to: SLUMLORD_ID,
+            },
+            first_amount + second_amount,
+        );
+        let mut tx = Transaction::new_with_payer(
+            &[borrow_split_ix, donate_ix, check_repaid_ix_full().unwrap()],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], last_blockhash);
+        tx
+    };
+
+    // sum is exactly the cap
+    banks_client
+        .process_transaction(borrow_split_tx(
+            MAX_BORROW_LAMPORTS - MAX_BORROW_LAMPORTS / 2,
+        ))
+        .await
+        .unwrap();
+    let err = banks_client
+        .process_transaction(borrow_split_tx(
+            MAX_BORROW_LAMPORTS - MAX_BORROW_LAMPORTS / 2 + 1,
+        ))
+        .await
+        .unwrap_err();
+    assert_custom_err(err, SlumlordError::InsufficientLiquidity);
+
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+}
+
+async fn pool_limits_below_reserve_fail<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    // reserve exceeds the pool's balance, even after migrate tops up rent
+    banks_client
+        .process_transaction(set_pool_limits_tx(
+            &payer,
+            &admin,
+            last_blockhash,
+            SetPoolLimitsIxArgs {
+                max_borrow_lamports: 0,
+                reserve_lamports: 2 * SLUMLORD_LAMPORTS,
+            },
+        ))
+        .await
+        .unwrap();
+    let slumlord_lamports = banks_client.get_slumlord_acc().await.lamports;
+
+    let err = banks_client
+        .process_transaction(borrow_with_min_repay_check_repaid_tx(
+            &payer,
+            last_blockhash,
+            0,
+        ))
+        .await
+        .unwrap_err();
+
+    assert_custom_err(err, SlumlordError::PoolEmpty);
+    banks_client
+        .assert_slumlord_balance(slumlord_lamports)
+        .await;
+}
+
+async fn set_pool_limits_not_admin_fail<H: SlumlordTestHarness>() {
+    let admin = Keypair::new();
+    let pt = H::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS)
+        .add_slumlord_program_data(admin.pubkey());
+
+    let (mut banks_client, payer, last_blockhash) = pt.start_harness().await;
+
+    banks_client
+        .process_transaction(enable_allowlist_tx(&payer, &admin, last_blockhash))
+        .await
+        .unwrap();
+
+    let set_pool_limits_ix = set_pool_limits_ix(
+        SetPoolLimitsFreeArgs {
+            admin: payer.pubkey(),
+        },
+        SetPoolLimitsIxArgs {
+            max_borrow_lamports: 1,
+            reserve_lamports: 0,
+        },
+    )
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[set_pool_limits_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+
+    let err = banks_client.process_transaction(tx).await.unwrap_err();
+
+    assert_custom_err(err, SlumlordError::NotAdmin);
+}
+
+#[tokio::test]
+async fn decode_slumlord_err() {
+    let pt = ProgramTest::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+
+    let borrow_ix = borrow_ix(BorrowFreeArgs {
+        dst: payer.pubkey(),
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(&[borrow_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+    let res = banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    let err = res.result.unwrap_err();
+    let logs = res.metadata.unwrap().log_messages;
+    assert_eq!(
+        try_slumlord_err(&err, Some(&logs)),
+        Some((0, SlumlordError::NoSucceedingCheckRepaid))
+    );
+
+    // system program's ResultWithNegativeLamports = Custom(1)
+    let overdraw_ix = transfer_ix(
+        TransferKeys {
+            from: payer.pubkey(),
+            to: SLUMLORD_ID,
+        },
+        u64::MAX,
+    );
+    let mut tx = Transaction::new_with_payer(&[overdraw_ix], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+    let res = banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    let err = res.result.unwrap_err();
+    let logs = res.metadata.unwrap().log_messages;
+    assert_eq!(try_slumlord_err(&err, Some(&logs)), None);
+    // without logs, the error can't be attributed to the system program
+    assert_eq!(
+        try_slumlord_err(&err, None),
+        Some((0, SlumlordError::BorrowAlreadyActive))
+    );
+}
+
+#[tokio::test]
+async fn quote_loan() {
+    let pt = ProgramTest::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+
+    let mut tx =
+        Transaction::new_with_payer(&[quote_loan_ix_full().unwrap()], Some(&payer.pubkey()));
+    tx.sign(&[&payer], last_blockhash);
+    let res = banks_client.simulate_transaction(tx).await.unwrap();
+    res.result.unwrap().unwrap();
+    let return_data = res.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(
+        try_quote_loan_return(&return_data.program_id, &return_data.data).unwrap(),
+        QuoteLoanReturn {
+            loanable_lamports: SLUMLORD_LAMPORTS - 1,
+            fee_lamports: 0,
+            loan_active: false,
+        }
+    );
+    // read-only
+    banks_client
+        .assert_slumlord_balance(SLUMLORD_LAMPORTS)
+        .await;
+    banks_client.assert_slumlord_data_empty().await;
+}
+
+#[tokio::test]
+async fn check_repaid_fail_logs_loan_context() {
+    let pt = ProgramTest::default()
+        .add_slumlord_program()
+        .add_slumlord(SLUMLORD_LAMPORTS);
+    let (mut banks_client, payer, last_blockhash) = pt.start().await;
+
+    let migrate_ix = migrate_ix(MigrateFreeArgs {
+        payer: payer.pubkey(),
+    })
+    .unwrap();
+    let borrow_ix = borrow_ix(BorrowFreeArgs {
+        dst: payer.pubkey(),
+    })
+    .unwrap();
+    let mut tx = Transaction::new_with_payer(
+        &[migrate_ix, borrow_ix, check_repaid_ix_full().unwrap()],
+        Some(&payer.pubkey()),
+    );
+    tx.sign(&[&payer], last_blockhash);
+    let res = banks_client
+        .process_transaction_with_metadata(tx)
+        .await
+        .unwrap();
+    let err = res.result.unwrap_err();
+    let logs = res.metadata.unwrap().log_messages;
+    assert_eq!(
+        try_slumlord_err(&err, Some(&logs)),
+        Some((2, SlumlordError::InsufficientRepay))
+    );
+    let expected_log = format!(
+        "Program log: Loan from instruction 1 to {} not repaid, short by {} lamports",
+        payer.pubkey(),
+        SLUMLORD_LAMPORTS - 1
+    );
+    assert!(
+        logs.contains(&expected_log),
+        "{expected_log} not in {logs:#?}"
+    );
+}
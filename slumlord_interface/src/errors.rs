@@ -12,6 +12,28 @@ pub enum SlumlordError {
     BorrowAlreadyActive = 1,
     #[error("Outstanding loan was not fully repaid")]
     InsufficientRepay = 2,
+    #[error("Outstanding loan exceeds max repay amount")]
+    RepayExceedsMax = 3,
+    #[error("Loanable amount is below the requested minimum")]
+    InsufficientLiquidity = 4,
+    #[error("Slumlord account has no lamports to lend")]
+    PoolEmpty = 5,
+    #[error("Slumlord account data is not a valid loan state")]
+    CorruptLoanState = 6,
+    #[error("Cannot lend to the slumlord account itself")]
+    LoanToSelf = 7,
+    #[error("No flash loan is active")]
+    NoActiveLoan = 8,
+    #[error("Slumlord account has not been initialized with Init")]
+    NotInitialized = 9,
+    #[error("Slumlord account layout does not record loan context, run Migrate")]
+    NoLoanContext = 10,
+    #[error("Slumlord account layout does not record pool config, run Migrate")]
+    NoPoolConfig = 11,
+    #[error("Signer is not the slumlord admin")]
+    NotAdmin = 12,
+    #[error("Borrower is not on the slumlord allowlist")]
+    BorrowerNotAllowed = 13,
 }
 impl From<SlumlordError> for ProgramError {
     fn from(e: SlumlordError) -> Self {
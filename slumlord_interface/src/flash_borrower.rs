@@ -0,0 +1,193 @@
+//! The `OnFlashLoan` instruction that programs receiving a `FlashLoanWithCallback`
+//! flash loan must implement.
+//!
+//! Not generated from `idl.json` since it is not a slumlord instruction.
+//! The discriminant is the Anchor sighash of `on_flash_loan` so Anchor programs
+//! can implement it as a regular instruction:
+//!
+//! `pub fn on_flash_loan(ctx: Context<OnFlashLoan>, amount: u64, data: Vec<u8>) -> Result<()>`
+//!
+//! where `OnFlashLoan` has the mutable `slumlord` and `receiver` accounts
+//! followed by `initiator: Signer<'info>`.
+//!
+//! Accounts are `[slumlord, receiver, initiator]` followed by the remaining accounts passed to
+//! `FlashLoanWithCallback`, with their privileges preserved.
+//! `slumlord`'s lamports must be restored before returning.
+//!
+//! Anyone can make slumlord call `OnFlashLoan` with any `data`, so receivers
+//! should check that `initiator`, the signer of `FlashLoanWithCallback`, is an account they trust.
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::io::Read;
+pub const ON_FLASH_LOAN_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug)]
+pub struct OnFlashLoanAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The account the SOL was lent to
+    pub receiver: &'me AccountInfo<'info>,
+    ///The signer that started the flash loan
+    pub initiator: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct OnFlashLoanKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The account the SOL was lent to
+    pub receiver: Pubkey,
+    ///The signer that started the flash loan
+    pub initiator: Pubkey,
+}
+impl From<OnFlashLoanAccounts<'_, '_>> for OnFlashLoanKeys {
+    fn from(accounts: OnFlashLoanAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            receiver: *accounts.receiver.key,
+            initiator: *accounts.initiator.key,
+        }
+    }
+}
+impl From<OnFlashLoanKeys> for [AccountMeta; ON_FLASH_LOAN_IX_ACCOUNTS_LEN] {
+    fn from(keys: OnFlashLoanKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.receiver,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.initiator,
+                is_signer: true,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; ON_FLASH_LOAN_IX_ACCOUNTS_LEN]> for OnFlashLoanKeys {
+    fn from(pubkeys: [Pubkey; ON_FLASH_LOAN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            receiver: pubkeys[1],
+            initiator: pubkeys[2],
+        }
+    }
+}
+impl<'info> From<OnFlashLoanAccounts<'_, 'info>>
+    for [AccountInfo<'info>; ON_FLASH_LOAN_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: OnFlashLoanAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.receiver.clone(),
+            accounts.initiator.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; ON_FLASH_LOAN_IX_ACCOUNTS_LEN]>
+    for OnFlashLoanAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; ON_FLASH_LOAN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            receiver: &arr[1],
+            initiator: &arr[2],
+        }
+    }
+}
+/// sha256("global:on_flash_loan")[..8]
+pub const ON_FLASH_LOAN_IX_DISCM: [u8; 8] = [195, 212, 238, 236, 80, 204, 73, 167];
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OnFlashLoanIxArgs {
+    pub amount: u64,
+    pub data: Vec<u8>,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct OnFlashLoanIxData(pub OnFlashLoanIxArgs);
+impl From<OnFlashLoanIxArgs> for OnFlashLoanIxData {
+    fn from(args: OnFlashLoanIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl OnFlashLoanIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm = [0u8; 8];
+        reader.read_exact(&mut maybe_discm)?;
+        if maybe_discm != ON_FLASH_LOAN_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    ON_FLASH_LOAN_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(OnFlashLoanIxArgs::deserialize(&mut reader)?))
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&ON_FLASH_LOAN_IX_DISCM)?;
+        self.0.serialize(&mut writer)
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+/// Creates the `OnFlashLoan` instruction for the FlashBorrower program `program_id`
+/// without any remaining accounts
+pub fn on_flash_loan_ix<K: Into<OnFlashLoanKeys>, A: Into<OnFlashLoanIxArgs>>(
+    program_id: Pubkey,
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: OnFlashLoanKeys = accounts.into();
+    let metas: [AccountMeta; ON_FLASH_LOAN_IX_ACCOUNTS_LEN] = keys.into();
+    let args_full: OnFlashLoanIxArgs = args.into();
+    Ok(Instruction {
+        program_id,
+        accounts: Vec::from(metas),
+        data: OnFlashLoanIxData(args_full).try_to_vec()?,
+    })
+}
+pub fn on_flash_loan_verify_account_keys(
+    accounts: OnFlashLoanAccounts<'_, '_>,
+    keys: OnFlashLoanKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.receiver.key, &keys.receiver),
+        (accounts.initiator.key, &keys.initiator),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn on_flash_loan_verify_account_privileges<'me, 'info>(
+    accounts: OnFlashLoanAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord, accounts.receiver] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.initiator] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
@@ -13,6 +14,17 @@ pub enum SlumlordProgramIx {
     Borrow,
     Repay,
     CheckRepaid,
+    BorrowSplit(BorrowSplitIxArgs),
+    RepayWithMax(RepayWithMaxIxArgs),
+    BorrowWithMin(BorrowWithMinIxArgs),
+    FlashLoanWithCallback(FlashLoanWithCallbackIxArgs),
+    QuoteLoan,
+    Migrate,
+    SetAdmin,
+    SetAllowlist(SetAllowlistIxArgs),
+    AddBorrower,
+    RemoveBorrower,
+    SetPoolLimits(SetPoolLimitsIxArgs),
 }
 impl SlumlordProgramIx {
     pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
@@ -25,6 +37,29 @@ impl SlumlordProgramIx {
             BORROW_IX_DISCM => Ok(Self::Borrow),
             REPAY_IX_DISCM => Ok(Self::Repay),
             CHECK_REPAID_IX_DISCM => Ok(Self::CheckRepaid),
+            BORROW_SPLIT_IX_DISCM => Ok(Self::BorrowSplit(BorrowSplitIxArgs::deserialize(
+                &mut reader,
+            )?)),
+            REPAY_WITH_MAX_IX_DISCM => Ok(Self::RepayWithMax(RepayWithMaxIxArgs::deserialize(
+                &mut reader,
+            )?)),
+            BORROW_WITH_MIN_IX_DISCM => Ok(Self::BorrowWithMin(BorrowWithMinIxArgs::deserialize(
+                &mut reader,
+            )?)),
+            FLASH_LOAN_WITH_CALLBACK_IX_DISCM => Ok(Self::FlashLoanWithCallback(
+                FlashLoanWithCallbackIxArgs::deserialize(&mut reader)?,
+            )),
+            QUOTE_LOAN_IX_DISCM => Ok(Self::QuoteLoan),
+            MIGRATE_IX_DISCM => Ok(Self::Migrate),
+            SET_ADMIN_IX_DISCM => Ok(Self::SetAdmin),
+            SET_ALLOWLIST_IX_DISCM => Ok(Self::SetAllowlist(SetAllowlistIxArgs::deserialize(
+                &mut reader,
+            )?)),
+            ADD_BORROWER_IX_DISCM => Ok(Self::AddBorrower),
+            REMOVE_BORROWER_IX_DISCM => Ok(Self::RemoveBorrower),
+            SET_POOL_LIMITS_IX_DISCM => Ok(Self::SetPoolLimits(SetPoolLimitsIxArgs::deserialize(
+                &mut reader,
+            )?)),
             _ => Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
                 format!("discm {:?} not found", maybe_discm),
@@ -37,6 +72,35 @@ impl SlumlordProgramIx {
             Self::Borrow => writer.write_all(&[BORROW_IX_DISCM]),
             Self::Repay => writer.write_all(&[REPAY_IX_DISCM]),
             Self::CheckRepaid => writer.write_all(&[CHECK_REPAID_IX_DISCM]),
+            Self::BorrowSplit(args) => {
+                writer.write_all(&[BORROW_SPLIT_IX_DISCM])?;
+                args.serialize(&mut writer)
+            }
+            Self::RepayWithMax(args) => {
+                writer.write_all(&[REPAY_WITH_MAX_IX_DISCM])?;
+                args.serialize(&mut writer)
+            }
+            Self::BorrowWithMin(args) => {
+                writer.write_all(&[BORROW_WITH_MIN_IX_DISCM])?;
+                args.serialize(&mut writer)
+            }
+            Self::FlashLoanWithCallback(args) => {
+                writer.write_all(&[FLASH_LOAN_WITH_CALLBACK_IX_DISCM])?;
+                args.serialize(&mut writer)
+            }
+            Self::QuoteLoan => writer.write_all(&[QUOTE_LOAN_IX_DISCM]),
+            Self::Migrate => writer.write_all(&[MIGRATE_IX_DISCM]),
+            Self::SetAdmin => writer.write_all(&[SET_ADMIN_IX_DISCM]),
+            Self::SetAllowlist(args) => {
+                writer.write_all(&[SET_ALLOWLIST_IX_DISCM])?;
+                args.serialize(&mut writer)
+            }
+            Self::AddBorrower => writer.write_all(&[ADD_BORROWER_IX_DISCM]),
+            Self::RemoveBorrower => writer.write_all(&[REMOVE_BORROWER_IX_DISCM]),
+            Self::SetPoolLimits(args) => {
+                writer.write_all(&[SET_POOL_LIMITS_IX_DISCM])?;
+                args.serialize(&mut writer)
+            }
         }
     }
     pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
@@ -615,3 +679,1884 @@ pub fn check_repaid_verify_account_privileges<'me, 'info>(
     }
     Ok(())
 }
+pub const BORROW_SPLIT_IX_ACCOUNTS_LEN: usize = 2;
+#[derive(Copy, Clone, Debug)]
+pub struct BorrowSplitAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///Instructions sysvar
+    pub instructions: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct BorrowSplitKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///Instructions sysvar
+    pub instructions: Pubkey,
+}
+impl From<BorrowSplitAccounts<'_, '_>> for BorrowSplitKeys {
+    fn from(accounts: BorrowSplitAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            instructions: *accounts.instructions.key,
+        }
+    }
+}
+impl From<BorrowSplitKeys> for [AccountMeta; BORROW_SPLIT_IX_ACCOUNTS_LEN] {
+    fn from(keys: BorrowSplitKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.instructions,
+                is_signer: false,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; BORROW_SPLIT_IX_ACCOUNTS_LEN]> for BorrowSplitKeys {
+    fn from(pubkeys: [Pubkey; BORROW_SPLIT_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            instructions: pubkeys[1],
+        }
+    }
+}
+impl<'info> From<BorrowSplitAccounts<'_, 'info>>
+    for [AccountInfo<'info>; BORROW_SPLIT_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: BorrowSplitAccounts<'_, 'info>) -> Self {
+        [accounts.slumlord.clone(), accounts.instructions.clone()]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; BORROW_SPLIT_IX_ACCOUNTS_LEN]>
+    for BorrowSplitAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; BORROW_SPLIT_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            instructions: &arr[1],
+        }
+    }
+}
+pub const BORROW_SPLIT_IX_DISCM: u8 = 4u8;
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorrowSplitIxArgs {
+    pub amounts: Vec<u64>,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowSplitIxData(pub BorrowSplitIxArgs);
+impl From<BorrowSplitIxArgs> for BorrowSplitIxData {
+    fn from(args: BorrowSplitIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl BorrowSplitIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != BORROW_SPLIT_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    BORROW_SPLIT_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(BorrowSplitIxArgs::deserialize(&mut reader)?))
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[BORROW_SPLIT_IX_DISCM])?;
+        self.0.serialize(&mut writer)
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn borrow_split_ix<K: Into<BorrowSplitKeys>, A: Into<BorrowSplitIxArgs>>(
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: BorrowSplitKeys = accounts.into();
+    let metas: [AccountMeta; BORROW_SPLIT_IX_ACCOUNTS_LEN] = keys.into();
+    let args_full: BorrowSplitIxArgs = args.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: BorrowSplitIxData(args_full).try_to_vec()?,
+    })
+}
+pub fn borrow_split_invoke<'info, A: Into<BorrowSplitIxArgs>>(
+    accounts: BorrowSplitAccounts<'_, 'info>,
+    args: A,
+) -> ProgramResult {
+    let ix = borrow_split_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; BORROW_SPLIT_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn borrow_split_invoke_signed<'info, A: Into<BorrowSplitIxArgs>>(
+    accounts: BorrowSplitAccounts<'_, 'info>,
+    args: A,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = borrow_split_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; BORROW_SPLIT_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn borrow_split_verify_account_keys(
+    accounts: BorrowSplitAccounts<'_, '_>,
+    keys: BorrowSplitKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.instructions.key, &keys.instructions),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn borrow_split_verify_account_privileges<'me, 'info>(
+    accounts: BorrowSplitAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    Ok(())
+}
+pub const REPAY_WITH_MAX_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug)]
+pub struct RepayWithMaxAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The system account paying the outstanding flash loan
+    pub src: &'me AccountInfo<'info>,
+    ///System Program
+    pub system_program: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct RepayWithMaxKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The system account paying the outstanding flash loan
+    pub src: Pubkey,
+    ///System Program
+    pub system_program: Pubkey,
+}
+impl From<RepayWithMaxAccounts<'_, '_>> for RepayWithMaxKeys {
+    fn from(accounts: RepayWithMaxAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            src: *accounts.src.key,
+            system_program: *accounts.system_program.key,
+        }
+    }
+}
+impl From<RepayWithMaxKeys> for [AccountMeta; REPAY_WITH_MAX_IX_ACCOUNTS_LEN] {
+    fn from(keys: RepayWithMaxKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.src,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.system_program,
+                is_signer: false,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; REPAY_WITH_MAX_IX_ACCOUNTS_LEN]> for RepayWithMaxKeys {
+    fn from(pubkeys: [Pubkey; REPAY_WITH_MAX_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            src: pubkeys[1],
+            system_program: pubkeys[2],
+        }
+    }
+}
+impl<'info> From<RepayWithMaxAccounts<'_, 'info>>
+    for [AccountInfo<'info>; REPAY_WITH_MAX_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: RepayWithMaxAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.src.clone(),
+            accounts.system_program.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; REPAY_WITH_MAX_IX_ACCOUNTS_LEN]>
+    for RepayWithMaxAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; REPAY_WITH_MAX_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            src: &arr[1],
+            system_program: &arr[2],
+        }
+    }
+}
+pub const REPAY_WITH_MAX_IX_DISCM: u8 = 5u8;
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RepayWithMaxIxArgs {
+    pub max_lamports: u64,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepayWithMaxIxData(pub RepayWithMaxIxArgs);
+impl From<RepayWithMaxIxArgs> for RepayWithMaxIxData {
+    fn from(args: RepayWithMaxIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl RepayWithMaxIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != REPAY_WITH_MAX_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    REPAY_WITH_MAX_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(RepayWithMaxIxArgs::deserialize(&mut reader)?))
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[REPAY_WITH_MAX_IX_DISCM])?;
+        self.0.serialize(&mut writer)
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn repay_with_max_ix<K: Into<RepayWithMaxKeys>, A: Into<RepayWithMaxIxArgs>>(
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: RepayWithMaxKeys = accounts.into();
+    let metas: [AccountMeta; REPAY_WITH_MAX_IX_ACCOUNTS_LEN] = keys.into();
+    let args_full: RepayWithMaxIxArgs = args.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: RepayWithMaxIxData(args_full).try_to_vec()?,
+    })
+}
+pub fn repay_with_max_invoke<'info, A: Into<RepayWithMaxIxArgs>>(
+    accounts: RepayWithMaxAccounts<'_, 'info>,
+    args: A,
+) -> ProgramResult {
+    let ix = repay_with_max_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; REPAY_WITH_MAX_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn repay_with_max_invoke_signed<'info, A: Into<RepayWithMaxIxArgs>>(
+    accounts: RepayWithMaxAccounts<'_, 'info>,
+    args: A,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = repay_with_max_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; REPAY_WITH_MAX_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn repay_with_max_verify_account_keys(
+    accounts: RepayWithMaxAccounts<'_, '_>,
+    keys: RepayWithMaxKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.src.key, &keys.src),
+        (accounts.system_program.key, &keys.system_program),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn repay_with_max_verify_account_privileges<'me, 'info>(
+    accounts: RepayWithMaxAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord, accounts.src] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.src] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
+pub const BORROW_WITH_MIN_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug)]
+pub struct BorrowWithMinAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The destination account to lend SOL to
+    pub dst: &'me AccountInfo<'info>,
+    ///Instructions sysvar
+    pub instructions: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct BorrowWithMinKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The destination account to lend SOL to
+    pub dst: Pubkey,
+    ///Instructions sysvar
+    pub instructions: Pubkey,
+}
+impl From<BorrowWithMinAccounts<'_, '_>> for BorrowWithMinKeys {
+    fn from(accounts: BorrowWithMinAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            dst: *accounts.dst.key,
+            instructions: *accounts.instructions.key,
+        }
+    }
+}
+impl From<BorrowWithMinKeys> for [AccountMeta; BORROW_WITH_MIN_IX_ACCOUNTS_LEN] {
+    fn from(keys: BorrowWithMinKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.dst,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.instructions,
+                is_signer: false,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; BORROW_WITH_MIN_IX_ACCOUNTS_LEN]> for BorrowWithMinKeys {
+    fn from(pubkeys: [Pubkey; BORROW_WITH_MIN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            dst: pubkeys[1],
+            instructions: pubkeys[2],
+        }
+    }
+}
+impl<'info> From<BorrowWithMinAccounts<'_, 'info>>
+    for [AccountInfo<'info>; BORROW_WITH_MIN_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: BorrowWithMinAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.dst.clone(),
+            accounts.instructions.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; BORROW_WITH_MIN_IX_ACCOUNTS_LEN]>
+    for BorrowWithMinAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; BORROW_WITH_MIN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            dst: &arr[1],
+            instructions: &arr[2],
+        }
+    }
+}
+pub const BORROW_WITH_MIN_IX_DISCM: u8 = 6u8;
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorrowWithMinIxArgs {
+    pub min_lamports: u64,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowWithMinIxData(pub BorrowWithMinIxArgs);
+impl From<BorrowWithMinIxArgs> for BorrowWithMinIxData {
+    fn from(args: BorrowWithMinIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl BorrowWithMinIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != BORROW_WITH_MIN_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    BORROW_WITH_MIN_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(BorrowWithMinIxArgs::deserialize(&mut reader)?))
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[BORROW_WITH_MIN_IX_DISCM])?;
+        self.0.serialize(&mut writer)
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn borrow_with_min_ix<K: Into<BorrowWithMinKeys>, A: Into<BorrowWithMinIxArgs>>(
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: BorrowWithMinKeys = accounts.into();
+    let metas: [AccountMeta; BORROW_WITH_MIN_IX_ACCOUNTS_LEN] = keys.into();
+    let args_full: BorrowWithMinIxArgs = args.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: BorrowWithMinIxData(args_full).try_to_vec()?,
+    })
+}
+pub fn borrow_with_min_invoke<'info, A: Into<BorrowWithMinIxArgs>>(
+    accounts: BorrowWithMinAccounts<'_, 'info>,
+    args: A,
+) -> ProgramResult {
+    let ix = borrow_with_min_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; BORROW_WITH_MIN_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn borrow_with_min_invoke_signed<'info, A: Into<BorrowWithMinIxArgs>>(
+    accounts: BorrowWithMinAccounts<'_, 'info>,
+    args: A,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = borrow_with_min_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; BORROW_WITH_MIN_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn borrow_with_min_verify_account_keys(
+    accounts: BorrowWithMinAccounts<'_, '_>,
+    keys: BorrowWithMinKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.dst.key, &keys.dst),
+        (accounts.instructions.key, &keys.instructions),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn borrow_with_min_verify_account_privileges<'me, 'info>(
+    accounts: BorrowWithMinAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord, accounts.dst] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    Ok(())
+}
+pub const FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN: usize = 4;
+#[derive(Copy, Clone, Debug)]
+pub struct FlashLoanWithCallbackAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The account to lend SOL to
+    pub receiver: &'me AccountInfo<'info>,
+    ///The FlashBorrower program to call back after lending
+    pub receiver_program: &'me AccountInfo<'info>,
+    ///The account starting the flash loan, passed to the FlashBorrower program's OnFlashLoan as a signer
+    pub initiator: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct FlashLoanWithCallbackKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The account to lend SOL to
+    pub receiver: Pubkey,
+    ///The FlashBorrower program to call back after lending
+    pub receiver_program: Pubkey,
+    ///The account starting the flash loan, passed to the FlashBorrower program's OnFlashLoan as a signer
+    pub initiator: Pubkey,
+}
+impl From<FlashLoanWithCallbackAccounts<'_, '_>> for FlashLoanWithCallbackKeys {
+    fn from(accounts: FlashLoanWithCallbackAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            receiver: *accounts.receiver.key,
+            receiver_program: *accounts.receiver_program.key,
+            initiator: *accounts.initiator.key,
+        }
+    }
+}
+impl From<FlashLoanWithCallbackKeys> for [AccountMeta; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN] {
+    fn from(keys: FlashLoanWithCallbackKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.receiver,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.receiver_program,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.initiator,
+                is_signer: true,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN]> for FlashLoanWithCallbackKeys {
+    fn from(pubkeys: [Pubkey; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            receiver: pubkeys[1],
+            receiver_program: pubkeys[2],
+            initiator: pubkeys[3],
+        }
+    }
+}
+impl<'info> From<FlashLoanWithCallbackAccounts<'_, 'info>>
+    for [AccountInfo<'info>; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: FlashLoanWithCallbackAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.receiver.clone(),
+            accounts.receiver_program.clone(),
+            accounts.initiator.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN]>
+    for FlashLoanWithCallbackAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            receiver: &arr[1],
+            receiver_program: &arr[2],
+            initiator: &arr[3],
+        }
+    }
+}
+pub const FLASH_LOAN_WITH_CALLBACK_IX_DISCM: u8 = 7u8;
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlashLoanWithCallbackIxArgs {
+    pub amount: u64,
+    pub data: Vec<u8>,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlashLoanWithCallbackIxData(pub FlashLoanWithCallbackIxArgs);
+impl From<FlashLoanWithCallbackIxArgs> for FlashLoanWithCallbackIxData {
+    fn from(args: FlashLoanWithCallbackIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl FlashLoanWithCallbackIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != FLASH_LOAN_WITH_CALLBACK_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    FLASH_LOAN_WITH_CALLBACK_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(FlashLoanWithCallbackIxArgs::deserialize(&mut reader)?))
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[FLASH_LOAN_WITH_CALLBACK_IX_DISCM])?;
+        self.0.serialize(&mut writer)
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn flash_loan_with_callback_ix<
+    K: Into<FlashLoanWithCallbackKeys>,
+    A: Into<FlashLoanWithCallbackIxArgs>,
+>(
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: FlashLoanWithCallbackKeys = accounts.into();
+    let metas: [AccountMeta; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN] = keys.into();
+    let args_full: FlashLoanWithCallbackIxArgs = args.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: FlashLoanWithCallbackIxData(args_full).try_to_vec()?,
+    })
+}
+pub fn flash_loan_with_callback_invoke<'info, A: Into<FlashLoanWithCallbackIxArgs>>(
+    accounts: FlashLoanWithCallbackAccounts<'_, 'info>,
+    args: A,
+) -> ProgramResult {
+    let ix = flash_loan_with_callback_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN] =
+        accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn flash_loan_with_callback_invoke_signed<'info, A: Into<FlashLoanWithCallbackIxArgs>>(
+    accounts: FlashLoanWithCallbackAccounts<'_, 'info>,
+    args: A,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = flash_loan_with_callback_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; FLASH_LOAN_WITH_CALLBACK_IX_ACCOUNTS_LEN] =
+        accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn flash_loan_with_callback_verify_account_keys(
+    accounts: FlashLoanWithCallbackAccounts<'_, '_>,
+    keys: FlashLoanWithCallbackKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.receiver.key, &keys.receiver),
+        (accounts.receiver_program.key, &keys.receiver_program),
+        (accounts.initiator.key, &keys.initiator),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn flash_loan_with_callback_verify_account_privileges<'me, 'info>(
+    accounts: FlashLoanWithCallbackAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord, accounts.receiver] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.initiator] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
+pub const QUOTE_LOAN_IX_ACCOUNTS_LEN: usize = 1;
+#[derive(Copy, Clone, Debug)]
+pub struct QuoteLoanAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct QuoteLoanKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+}
+impl From<QuoteLoanAccounts<'_, '_>> for QuoteLoanKeys {
+    fn from(accounts: QuoteLoanAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+        }
+    }
+}
+impl From<QuoteLoanKeys> for [AccountMeta; QUOTE_LOAN_IX_ACCOUNTS_LEN] {
+    fn from(keys: QuoteLoanKeys) -> Self {
+        [AccountMeta {
+            pubkey: keys.slumlord,
+            is_signer: false,
+            is_writable: false,
+        }]
+    }
+}
+impl From<[Pubkey; QUOTE_LOAN_IX_ACCOUNTS_LEN]> for QuoteLoanKeys {
+    fn from(pubkeys: [Pubkey; QUOTE_LOAN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+        }
+    }
+}
+impl<'info> From<QuoteLoanAccounts<'_, 'info>>
+    for [AccountInfo<'info>; QUOTE_LOAN_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: QuoteLoanAccounts<'_, 'info>) -> Self {
+        [accounts.slumlord.clone()]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; QUOTE_LOAN_IX_ACCOUNTS_LEN]>
+    for QuoteLoanAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; QUOTE_LOAN_IX_ACCOUNTS_LEN]) -> Self {
+        Self { slumlord: &arr[0] }
+    }
+}
+pub const QUOTE_LOAN_IX_DISCM: u8 = 8u8;
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuoteLoanIxData;
+impl QuoteLoanIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != QUOTE_LOAN_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    QUOTE_LOAN_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self)
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[QUOTE_LOAN_IX_DISCM])
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn quote_loan_ix<K: Into<QuoteLoanKeys>>(accounts: K) -> std::io::Result<Instruction> {
+    let keys: QuoteLoanKeys = accounts.into();
+    let metas: [AccountMeta; QUOTE_LOAN_IX_ACCOUNTS_LEN] = keys.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: QuoteLoanIxData.try_to_vec()?,
+    })
+}
+pub fn quote_loan_invoke<'info>(accounts: QuoteLoanAccounts<'_, 'info>) -> ProgramResult {
+    let ix = quote_loan_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; QUOTE_LOAN_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn quote_loan_invoke_signed<'info>(
+    accounts: QuoteLoanAccounts<'_, 'info>,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = quote_loan_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; QUOTE_LOAN_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn quote_loan_verify_account_keys(
+    accounts: QuoteLoanAccounts<'_, '_>,
+    keys: QuoteLoanKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [(accounts.slumlord.key, &keys.slumlord)] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn quote_loan_verify_account_privileges<'me, 'info>(
+    _accounts: QuoteLoanAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    Ok(())
+}
+pub const MIGRATE_IX_ACCOUNTS_LEN: usize = 3;
+#[derive(Copy, Clone, Debug)]
+pub struct MigrateAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///Account paying for any additional rent-exemption of the migrated slumlord account
+    pub payer: &'me AccountInfo<'info>,
+    ///System Program
+    pub system_program: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct MigrateKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///Account paying for any additional rent-exemption of the migrated slumlord account
+    pub payer: Pubkey,
+    ///System Program
+    pub system_program: Pubkey,
+}
+impl From<MigrateAccounts<'_, '_>> for MigrateKeys {
+    fn from(accounts: MigrateAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            payer: *accounts.payer.key,
+            system_program: *accounts.system_program.key,
+        }
+    }
+}
+impl From<MigrateKeys> for [AccountMeta; MIGRATE_IX_ACCOUNTS_LEN] {
+    fn from(keys: MigrateKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.system_program,
+                is_signer: false,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; MIGRATE_IX_ACCOUNTS_LEN]> for MigrateKeys {
+    fn from(pubkeys: [Pubkey; MIGRATE_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            payer: pubkeys[1],
+            system_program: pubkeys[2],
+        }
+    }
+}
+impl<'info> From<MigrateAccounts<'_, 'info>> for [AccountInfo<'info>; MIGRATE_IX_ACCOUNTS_LEN] {
+    fn from(accounts: MigrateAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; MIGRATE_IX_ACCOUNTS_LEN]>
+    for MigrateAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; MIGRATE_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            payer: &arr[1],
+            system_program: &arr[2],
+        }
+    }
+}
+pub const MIGRATE_IX_DISCM: u8 = 9u8;
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrateIxData;
+impl MigrateIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != MIGRATE_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    MIGRATE_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self)
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[MIGRATE_IX_DISCM])
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn migrate_ix<K: Into<MigrateKeys>>(accounts: K) -> std::io::Result<Instruction> {
+    let keys: MigrateKeys = accounts.into();
+    let metas: [AccountMeta; MIGRATE_IX_ACCOUNTS_LEN] = keys.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: MigrateIxData.try_to_vec()?,
+    })
+}
+pub fn migrate_invoke<'info>(accounts: MigrateAccounts<'_, 'info>) -> ProgramResult {
+    let ix = migrate_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; MIGRATE_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn migrate_invoke_signed<'info>(
+    accounts: MigrateAccounts<'_, 'info>,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = migrate_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; MIGRATE_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn migrate_verify_account_keys(
+    accounts: MigrateAccounts<'_, '_>,
+    keys: MigrateKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.payer.key, &keys.payer),
+        (accounts.system_program.key, &keys.system_program),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn migrate_verify_account_privileges<'me, 'info>(
+    accounts: MigrateAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord, accounts.payer] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.payer] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
+pub const SET_ADMIN_IX_ACCOUNTS_LEN: usize = 4;
+#[derive(Copy, Clone, Debug)]
+pub struct SetAdminAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The current admin, or the slumlord program's upgrade authority if no admin has been set
+    pub authority: &'me AccountInfo<'info>,
+    ///The new admin
+    pub new_admin: &'me AccountInfo<'info>,
+    ///The slumlord program's program data account
+    pub program_data: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct SetAdminKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The current admin, or the slumlord program's upgrade authority if no admin has been set
+    pub authority: Pubkey,
+    ///The new admin
+    pub new_admin: Pubkey,
+    ///The slumlord program's program data account
+    pub program_data: Pubkey,
+}
+impl From<SetAdminAccounts<'_, '_>> for SetAdminKeys {
+    fn from(accounts: SetAdminAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            authority: *accounts.authority.key,
+            new_admin: *accounts.new_admin.key,
+            program_data: *accounts.program_data.key,
+        }
+    }
+}
+impl From<SetAdminKeys> for [AccountMeta; SET_ADMIN_IX_ACCOUNTS_LEN] {
+    fn from(keys: SetAdminKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.authority,
+                is_signer: true,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.new_admin,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.program_data,
+                is_signer: false,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; SET_ADMIN_IX_ACCOUNTS_LEN]> for SetAdminKeys {
+    fn from(pubkeys: [Pubkey; SET_ADMIN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            authority: pubkeys[1],
+            new_admin: pubkeys[2],
+            program_data: pubkeys[3],
+        }
+    }
+}
+impl<'info> From<SetAdminAccounts<'_, 'info>> for [AccountInfo<'info>; SET_ADMIN_IX_ACCOUNTS_LEN] {
+    fn from(accounts: SetAdminAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.authority.clone(),
+            accounts.new_admin.clone(),
+            accounts.program_data.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; SET_ADMIN_IX_ACCOUNTS_LEN]>
+    for SetAdminAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; SET_ADMIN_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            authority: &arr[1],
+            new_admin: &arr[2],
+            program_data: &arr[3],
+        }
+    }
+}
+pub const SET_ADMIN_IX_DISCM: u8 = 10u8;
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetAdminIxData;
+impl SetAdminIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != SET_ADMIN_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    SET_ADMIN_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self)
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[SET_ADMIN_IX_DISCM])
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn set_admin_ix<K: Into<SetAdminKeys>>(accounts: K) -> std::io::Result<Instruction> {
+    let keys: SetAdminKeys = accounts.into();
+    let metas: [AccountMeta; SET_ADMIN_IX_ACCOUNTS_LEN] = keys.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: SetAdminIxData.try_to_vec()?,
+    })
+}
+pub fn set_admin_invoke<'info>(accounts: SetAdminAccounts<'_, 'info>) -> ProgramResult {
+    let ix = set_admin_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; SET_ADMIN_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn set_admin_invoke_signed<'info>(
+    accounts: SetAdminAccounts<'_, 'info>,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = set_admin_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; SET_ADMIN_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn set_admin_verify_account_keys(
+    accounts: SetAdminAccounts<'_, '_>,
+    keys: SetAdminKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.authority.key, &keys.authority),
+        (accounts.new_admin.key, &keys.new_admin),
+        (accounts.program_data.key, &keys.program_data),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn set_admin_verify_account_privileges<'me, 'info>(
+    accounts: SetAdminAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.authority] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
+pub const SET_ALLOWLIST_IX_ACCOUNTS_LEN: usize = 2;
+#[derive(Copy, Clone, Debug)]
+pub struct SetAllowlistAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The slumlord admin
+    pub admin: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct SetAllowlistKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The slumlord admin
+    pub admin: Pubkey,
+}
+impl From<SetAllowlistAccounts<'_, '_>> for SetAllowlistKeys {
+    fn from(accounts: SetAllowlistAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            admin: *accounts.admin.key,
+        }
+    }
+}
+impl From<SetAllowlistKeys> for [AccountMeta; SET_ALLOWLIST_IX_ACCOUNTS_LEN] {
+    fn from(keys: SetAllowlistKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.admin,
+                is_signer: true,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; SET_ALLOWLIST_IX_ACCOUNTS_LEN]> for SetAllowlistKeys {
+    fn from(pubkeys: [Pubkey; SET_ALLOWLIST_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            admin: pubkeys[1],
+        }
+    }
+}
+impl<'info> From<SetAllowlistAccounts<'_, 'info>>
+    for [AccountInfo<'info>; SET_ALLOWLIST_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: SetAllowlistAccounts<'_, 'info>) -> Self {
+        [accounts.slumlord.clone(), accounts.admin.clone()]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; SET_ALLOWLIST_IX_ACCOUNTS_LEN]>
+    for SetAllowlistAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; SET_ALLOWLIST_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            admin: &arr[1],
+        }
+    }
+}
+pub const SET_ALLOWLIST_IX_DISCM: u8 = 11u8;
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetAllowlistIxArgs {
+    pub enabled: bool,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetAllowlistIxData(pub SetAllowlistIxArgs);
+impl From<SetAllowlistIxArgs> for SetAllowlistIxData {
+    fn from(args: SetAllowlistIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl SetAllowlistIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != SET_ALLOWLIST_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    SET_ALLOWLIST_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(SetAllowlistIxArgs::deserialize(&mut reader)?))
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[SET_ALLOWLIST_IX_DISCM])?;
+        self.0.serialize(&mut writer)
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn set_allowlist_ix<K: Into<SetAllowlistKeys>, A: Into<SetAllowlistIxArgs>>(
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: SetAllowlistKeys = accounts.into();
+    let metas: [AccountMeta; SET_ALLOWLIST_IX_ACCOUNTS_LEN] = keys.into();
+    let args_full: SetAllowlistIxArgs = args.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: SetAllowlistIxData(args_full).try_to_vec()?,
+    })
+}
+pub fn set_allowlist_invoke<'info, A: Into<SetAllowlistIxArgs>>(
+    accounts: SetAllowlistAccounts<'_, 'info>,
+    args: A,
+) -> ProgramResult {
+    let ix = set_allowlist_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; SET_ALLOWLIST_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn set_allowlist_invoke_signed<'info, A: Into<SetAllowlistIxArgs>>(
+    accounts: SetAllowlistAccounts<'_, 'info>,
+    args: A,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = set_allowlist_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; SET_ALLOWLIST_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn set_allowlist_verify_account_keys(
+    accounts: SetAllowlistAccounts<'_, '_>,
+    keys: SetAllowlistKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.admin.key, &keys.admin),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn set_allowlist_verify_account_privileges<'me, 'info>(
+    accounts: SetAllowlistAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.admin] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
+pub const ADD_BORROWER_IX_ACCOUNTS_LEN: usize = 6;
+#[derive(Copy, Clone, Debug)]
+pub struct AddBorrowerAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The slumlord admin
+    pub admin: &'me AccountInfo<'info>,
+    ///Account paying for the borrower entry's rent-exemption
+    pub payer: &'me AccountInfo<'info>,
+    ///The borrower to allow
+    pub borrower: &'me AccountInfo<'info>,
+    ///The borrower's allowlist entry PDA ["borrower", borrower]
+    pub borrower_entry: &'me AccountInfo<'info>,
+    ///System Program
+    pub system_program: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct AddBorrowerKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The slumlord admin
+    pub admin: Pubkey,
+    ///Account paying for the borrower entry's rent-exemption
+    pub payer: Pubkey,
+    ///The borrower to allow
+    pub borrower: Pubkey,
+    ///The borrower's allowlist entry PDA ["borrower", borrower]
+    pub borrower_entry: Pubkey,
+    ///System Program
+    pub system_program: Pubkey,
+}
+impl From<AddBorrowerAccounts<'_, '_>> for AddBorrowerKeys {
+    fn from(accounts: AddBorrowerAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            admin: *accounts.admin.key,
+            payer: *accounts.payer.key,
+            borrower: *accounts.borrower.key,
+            borrower_entry: *accounts.borrower_entry.key,
+            system_program: *accounts.system_program.key,
+        }
+    }
+}
+impl From<AddBorrowerKeys> for [AccountMeta; ADD_BORROWER_IX_ACCOUNTS_LEN] {
+    fn from(keys: AddBorrowerKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.admin,
+                is_signer: true,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.borrower,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.borrower_entry,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.system_program,
+                is_signer: false,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; ADD_BORROWER_IX_ACCOUNTS_LEN]> for AddBorrowerKeys {
+    fn from(pubkeys: [Pubkey; ADD_BORROWER_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            admin: pubkeys[1],
+            payer: pubkeys[2],
+            borrower: pubkeys[3],
+            borrower_entry: pubkeys[4],
+            system_program: pubkeys[5],
+        }
+    }
+}
+impl<'info> From<AddBorrowerAccounts<'_, 'info>>
+    for [AccountInfo<'info>; ADD_BORROWER_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: AddBorrowerAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.admin.clone(),
+            accounts.payer.clone(),
+            accounts.borrower.clone(),
+            accounts.borrower_entry.clone(),
+            accounts.system_program.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; ADD_BORROWER_IX_ACCOUNTS_LEN]>
+    for AddBorrowerAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; ADD_BORROWER_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            admin: &arr[1],
+            payer: &arr[2],
+            borrower: &arr[3],
+            borrower_entry: &arr[4],
+            system_program: &arr[5],
+        }
+    }
+}
+pub const ADD_BORROWER_IX_DISCM: u8 = 12u8;
+#[derive(Clone, Debug, PartialEq)]
+pub struct AddBorrowerIxData;
+impl AddBorrowerIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != ADD_BORROWER_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    ADD_BORROWER_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self)
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[ADD_BORROWER_IX_DISCM])
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn add_borrower_ix<K: Into<AddBorrowerKeys>>(accounts: K) -> std::io::Result<Instruction> {
+    let keys: AddBorrowerKeys = accounts.into();
+    let metas: [AccountMeta; ADD_BORROWER_IX_ACCOUNTS_LEN] = keys.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: AddBorrowerIxData.try_to_vec()?,
+    })
+}
+pub fn add_borrower_invoke<'info>(accounts: AddBorrowerAccounts<'_, 'info>) -> ProgramResult {
+    let ix = add_borrower_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; ADD_BORROWER_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn add_borrower_invoke_signed<'info>(
+    accounts: AddBorrowerAccounts<'_, 'info>,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = add_borrower_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; ADD_BORROWER_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn add_borrower_verify_account_keys(
+    accounts: AddBorrowerAccounts<'_, '_>,
+    keys: AddBorrowerKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.admin.key, &keys.admin),
+        (accounts.payer.key, &keys.payer),
+        (accounts.borrower.key, &keys.borrower),
+        (accounts.borrower_entry.key, &keys.borrower_entry),
+        (accounts.system_program.key, &keys.system_program),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn add_borrower_verify_account_privileges<'me, 'info>(
+    accounts: AddBorrowerAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.payer, accounts.borrower_entry] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.admin, accounts.payer] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
+pub const REMOVE_BORROWER_IX_ACCOUNTS_LEN: usize = 5;
+#[derive(Copy, Clone, Debug)]
+pub struct RemoveBorrowerAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The slumlord admin
+    pub admin: &'me AccountInfo<'info>,
+    ///The borrower to disallow
+    pub borrower: &'me AccountInfo<'info>,
+    ///The borrower's allowlist entry PDA ["borrower", borrower]
+    pub borrower_entry: &'me AccountInfo<'info>,
+    ///Account refunded the borrower entry's rent-exemption lamports
+    pub refund_rent_to: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct RemoveBorrowerKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The slumlord admin
+    pub admin: Pubkey,
+    ///The borrower to disallow
+    pub borrower: Pubkey,
+    ///The borrower's allowlist entry PDA ["borrower", borrower]
+    pub borrower_entry: Pubkey,
+    ///Account refunded the borrower entry's rent-exemption lamports
+    pub refund_rent_to: Pubkey,
+}
+impl From<RemoveBorrowerAccounts<'_, '_>> for RemoveBorrowerKeys {
+    fn from(accounts: RemoveBorrowerAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            admin: *accounts.admin.key,
+            borrower: *accounts.borrower.key,
+            borrower_entry: *accounts.borrower_entry.key,
+            refund_rent_to: *accounts.refund_rent_to.key,
+        }
+    }
+}
+impl From<RemoveBorrowerKeys> for [AccountMeta; REMOVE_BORROWER_IX_ACCOUNTS_LEN] {
+    fn from(keys: RemoveBorrowerKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.admin,
+                is_signer: true,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.borrower,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: keys.borrower_entry,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.refund_rent_to,
+                is_signer: false,
+                is_writable: true,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; REMOVE_BORROWER_IX_ACCOUNTS_LEN]> for RemoveBorrowerKeys {
+    fn from(pubkeys: [Pubkey; REMOVE_BORROWER_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            admin: pubkeys[1],
+            borrower: pubkeys[2],
+            borrower_entry: pubkeys[3],
+            refund_rent_to: pubkeys[4],
+        }
+    }
+}
+impl<'info> From<RemoveBorrowerAccounts<'_, 'info>>
+    for [AccountInfo<'info>; REMOVE_BORROWER_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: RemoveBorrowerAccounts<'_, 'info>) -> Self {
+        [
+            accounts.slumlord.clone(),
+            accounts.admin.clone(),
+            accounts.borrower.clone(),
+            accounts.borrower_entry.clone(),
+            accounts.refund_rent_to.clone(),
+        ]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; REMOVE_BORROWER_IX_ACCOUNTS_LEN]>
+    for RemoveBorrowerAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; REMOVE_BORROWER_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            admin: &arr[1],
+            borrower: &arr[2],
+            borrower_entry: &arr[3],
+            refund_rent_to: &arr[4],
+        }
+    }
+}
+pub const REMOVE_BORROWER_IX_DISCM: u8 = 13u8;
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoveBorrowerIxData;
+impl RemoveBorrowerIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != REMOVE_BORROWER_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    REMOVE_BORROWER_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self)
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[REMOVE_BORROWER_IX_DISCM])
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn remove_borrower_ix<K: Into<RemoveBorrowerKeys>>(
+    accounts: K,
+) -> std::io::Result<Instruction> {
+    let keys: RemoveBorrowerKeys = accounts.into();
+    let metas: [AccountMeta; REMOVE_BORROWER_IX_ACCOUNTS_LEN] = keys.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: RemoveBorrowerIxData.try_to_vec()?,
+    })
+}
+pub fn remove_borrower_invoke<'info>(accounts: RemoveBorrowerAccounts<'_, 'info>) -> ProgramResult {
+    let ix = remove_borrower_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; REMOVE_BORROWER_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn remove_borrower_invoke_signed<'info>(
+    accounts: RemoveBorrowerAccounts<'_, 'info>,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = remove_borrower_ix(accounts)?;
+    let account_info: [AccountInfo<'info>; REMOVE_BORROWER_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn remove_borrower_verify_account_keys(
+    accounts: RemoveBorrowerAccounts<'_, '_>,
+    keys: RemoveBorrowerKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.admin.key, &keys.admin),
+        (accounts.borrower.key, &keys.borrower),
+        (accounts.borrower_entry.key, &keys.borrower_entry),
+        (accounts.refund_rent_to.key, &keys.refund_rent_to),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn remove_borrower_verify_account_privileges<'me, 'info>(
+    accounts: RemoveBorrowerAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.borrower_entry, accounts.refund_rent_to] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.admin] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
+pub const SET_POOL_LIMITS_IX_ACCOUNTS_LEN: usize = 2;
+#[derive(Copy, Clone, Debug)]
+pub struct SetPoolLimitsAccounts<'me, 'info> {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: &'me AccountInfo<'info>,
+    ///The slumlord admin
+    pub admin: &'me AccountInfo<'info>,
+}
+#[derive(Copy, Clone, Debug)]
+pub struct SetPoolLimitsKeys {
+    ///The slumlord PDA ["slumlord"]
+    pub slumlord: Pubkey,
+    ///The slumlord admin
+    pub admin: Pubkey,
+}
+impl From<SetPoolLimitsAccounts<'_, '_>> for SetPoolLimitsKeys {
+    fn from(accounts: SetPoolLimitsAccounts) -> Self {
+        Self {
+            slumlord: *accounts.slumlord.key,
+            admin: *accounts.admin.key,
+        }
+    }
+}
+impl From<SetPoolLimitsKeys> for [AccountMeta; SET_POOL_LIMITS_IX_ACCOUNTS_LEN] {
+    fn from(keys: SetPoolLimitsKeys) -> Self {
+        [
+            AccountMeta {
+                pubkey: keys.slumlord,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: keys.admin,
+                is_signer: true,
+                is_writable: false,
+            },
+        ]
+    }
+}
+impl From<[Pubkey; SET_POOL_LIMITS_IX_ACCOUNTS_LEN]> for SetPoolLimitsKeys {
+    fn from(pubkeys: [Pubkey; SET_POOL_LIMITS_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: pubkeys[0],
+            admin: pubkeys[1],
+        }
+    }
+}
+impl<'info> From<SetPoolLimitsAccounts<'_, 'info>>
+    for [AccountInfo<'info>; SET_POOL_LIMITS_IX_ACCOUNTS_LEN]
+{
+    fn from(accounts: SetPoolLimitsAccounts<'_, 'info>) -> Self {
+        [accounts.slumlord.clone(), accounts.admin.clone()]
+    }
+}
+impl<'me, 'info> From<&'me [AccountInfo<'info>; SET_POOL_LIMITS_IX_ACCOUNTS_LEN]>
+    for SetPoolLimitsAccounts<'me, 'info>
+{
+    fn from(arr: &'me [AccountInfo<'info>; SET_POOL_LIMITS_IX_ACCOUNTS_LEN]) -> Self {
+        Self {
+            slumlord: &arr[0],
+            admin: &arr[1],
+        }
+    }
+}
+pub const SET_POOL_LIMITS_IX_DISCM: u8 = 14u8;
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetPoolLimitsIxArgs {
+    pub max_borrow_lamports: u64,
+    pub reserve_lamports: u64,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetPoolLimitsIxData(pub SetPoolLimitsIxArgs);
+impl From<SetPoolLimitsIxArgs> for SetPoolLimitsIxData {
+    fn from(args: SetPoolLimitsIxArgs) -> Self {
+        Self(args)
+    }
+}
+impl SetPoolLimitsIxData {
+    pub fn deserialize(buf: &[u8]) -> std::io::Result<Self> {
+        let mut reader = buf;
+        let mut maybe_discm_buf = [0u8; 1];
+        reader.read_exact(&mut maybe_discm_buf)?;
+        let maybe_discm = maybe_discm_buf[0];
+        if maybe_discm != SET_POOL_LIMITS_IX_DISCM {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "discm does not match. Expected: {:?}. Received: {:?}",
+                    SET_POOL_LIMITS_IX_DISCM, maybe_discm
+                ),
+            ));
+        }
+        Ok(Self(SetPoolLimitsIxArgs::deserialize(&mut reader)?))
+    }
+    pub fn serialize<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&[SET_POOL_LIMITS_IX_DISCM])?;
+        self.0.serialize(&mut writer)
+    }
+    pub fn try_to_vec(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(data)
+    }
+}
+pub fn set_pool_limits_ix<K: Into<SetPoolLimitsKeys>, A: Into<SetPoolLimitsIxArgs>>(
+    accounts: K,
+    args: A,
+) -> std::io::Result<Instruction> {
+    let keys: SetPoolLimitsKeys = accounts.into();
+    let metas: [AccountMeta; SET_POOL_LIMITS_IX_ACCOUNTS_LEN] = keys.into();
+    let args_full: SetPoolLimitsIxArgs = args.into();
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from(metas),
+        data: SetPoolLimitsIxData(args_full).try_to_vec()?,
+    })
+}
+pub fn set_pool_limits_invoke<'info, A: Into<SetPoolLimitsIxArgs>>(
+    accounts: SetPoolLimitsAccounts<'_, 'info>,
+    args: A,
+) -> ProgramResult {
+    let ix = set_pool_limits_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; SET_POOL_LIMITS_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke(&ix, &account_info)
+}
+pub fn set_pool_limits_invoke_signed<'info, A: Into<SetPoolLimitsIxArgs>>(
+    accounts: SetPoolLimitsAccounts<'_, 'info>,
+    args: A,
+    seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let ix = set_pool_limits_ix(accounts, args)?;
+    let account_info: [AccountInfo<'info>; SET_POOL_LIMITS_IX_ACCOUNTS_LEN] = accounts.into();
+    invoke_signed(&ix, &account_info, seeds)
+}
+pub fn set_pool_limits_verify_account_keys(
+    accounts: SetPoolLimitsAccounts<'_, '_>,
+    keys: SetPoolLimitsKeys,
+) -> Result<(), (Pubkey, Pubkey)> {
+    for (actual, expected) in [
+        (accounts.slumlord.key, &keys.slumlord),
+        (accounts.admin.key, &keys.admin),
+    ] {
+        if actual != expected {
+            return Err((*actual, *expected));
+        }
+    }
+    Ok(())
+}
+pub fn set_pool_limits_verify_account_privileges<'me, 'info>(
+    accounts: SetPoolLimitsAccounts<'me, 'info>,
+) -> Result<(), (&'me AccountInfo<'info>, ProgramError)> {
+    for should_be_writable in [accounts.slumlord] {
+        if !should_be_writable.is_writable {
+            return Err((should_be_writable, ProgramError::InvalidAccountData));
+        }
+    }
+    for should_be_signer in [accounts.admin] {
+        if !should_be_signer.is_signer {
+            return Err((should_be_signer, ProgramError::MissingRequiredSignature));
+        }
+    }
+    Ok(())
+}
@@ -5,3 +5,5 @@ pub mod instructions;
 pub use instructions::*;
 pub mod errors;
 pub use errors::*;
+pub mod flash_borrower;
+pub use flash_borrower::*;
@@ -1,8 +1,71 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
 #[repr(C)]
 #[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Pod, Copy, Zeroable)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Slumlord {
     pub old_lamports: u64,
 }
+#[repr(C)]
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Pod, Copy, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlumlordHeader {
+    pub version: u8,
+    pub loan_active: u8,
+    pub padding: [u8; 6],
+}
+#[repr(C)]
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Pod, Copy, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoanContext {
+    pub dst: Pubkey,
+    pub borrow_ix_index: u16,
+    pub padding: [u8; 6],
+    pub lent_lamports: u64,
+}
+#[repr(C)]
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Pod, Copy, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolConfig {
+    pub admin: Pubkey,
+    pub allowlist_enabled: u8,
+    pub padding: [u8; 7],
+}
+#[repr(C)]
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Pod, Copy, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoolLimits {
+    pub max_borrow_lamports: u64,
+    pub reserve_lamports: u64,
+}
+#[repr(C)]
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Pod, Copy, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SlumlordAccount {
+    pub header: SlumlordHeader,
+    pub loan: Slumlord,
+    pub loan_context: LoanContext,
+    pub pool_config: PoolConfig,
+    pub pool_limits: PoolLimits,
+}
+#[repr(C)]
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq, Pod, Copy, Zeroable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorrowerEntry {
+    pub borrower: Pubkey,
+}
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheckRepaidReturn {
+    pub old_lamports: u64,
+    pub repaid: Option<u64>,
+    pub surplus: u64,
+}
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuoteLoanReturn {
+    pub loanable_lamports: u64,
+    pub fee_lamports: u64,
+    pub loan_active: bool,
+}